@@ -0,0 +1,115 @@
+// Todas as gerações da engine num só binário: `--engine <nome>` escolhe a
+// inicial e `setoption name Strategy value <nome>` troca durante a sessão.
+// As opções de linha de comando da sexta geração (`--book-file` etc.) valem
+// sempre que ela for escolhida.
+
+use chazz_core::engine::{Engine, Limits, SearchResult};
+use chazz_core::position::Position;
+use chazz_core::protocol;
+use engine_fifth::Fifth;
+use engine_first::First;
+use engine_fourth::Fourth;
+use engine_second::Second;
+use engine_sixth::{Options, Sixth};
+use engine_third::Third;
+use random_engine::Random;
+
+const STRATEGIES: [&str; 7] = ["random", "first", "second", "third", "fourth", "fifth", "sixth"];
+
+fn create(name: &str, args: &[String]) -> Result<Box<dyn Engine>, String> {
+    Ok(match name {
+        "random" => Box::new(Random::default()),
+        "first" => Box::new(First::default()),
+        "second" => Box::new(Second::default()),
+        "third" => Box::new(Third::default()),
+        "fourth" => Box::new(Fourth::default()),
+        "fifth" => Box::new(Fifth::default()),
+        "sixth" => Box::new(Sixth::new(Options::from_args(args)?)),
+        _ => return Err(format!("engine desconhecida: {} (use {})", name, STRATEGIES.join(", "))),
+    })
+}
+
+/// A geração escolhida, trocada por `Strategy` sem perder a posição.
+struct Chazz {
+    engine: Box<dyn Engine>,
+    position: Position,
+    args: Vec<String>,
+}
+
+impl Engine for Chazz {
+    fn name(&self) -> &'static str {
+        self.engine.name()
+    }
+
+    fn new_game(&mut self) {
+        self.position = Position::default();
+        self.engine.new_game();
+    }
+
+    fn set_position(&mut self, position: &Position) {
+        self.position = position.clone();
+        self.engine.set_position(position);
+    }
+
+    fn search(&mut self, limits: &Limits) -> SearchResult {
+        self.engine.search(limits)
+    }
+
+    fn options(&self) -> Vec<(&'static str, String)> {
+        let mut options = vec![("Strategy", self.engine.name().to_string())];
+        options.extend(self.engine.options());
+        options
+    }
+
+    fn set_option(&mut self, name: &str, value: &str) -> Result<(), String> {
+        if name != "Strategy" {
+            return self.engine.set_option(name, value);
+        }
+        self.engine = create(value, &self.args)?;
+        self.engine.set_position(&self.position);
+        Ok(())
+    }
+
+    fn command(&mut self, input: &str) -> bool {
+        self.engine.command(input)
+    }
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let name = match args.iter().position(|a| a == "--engine") {
+        Some(i) => args.get(i + 1).map_or("", String::as_str),
+        None => "sixth",
+    };
+
+    match create(name, &args) {
+        Ok(engine) => protocol::run(&mut Chazz { engine, position: Position::default(), args }),
+        Err(e) => eprintln!("Erro: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chazz_core::position::parse_position;
+
+    #[test]
+    fn strategy_switch_keeps_the_position() {
+        let engine = create("first", &[]).unwrap();
+        let mut chazz = Chazz { engine, position: Position::default(), args: Vec::new() };
+        let position = parse_position("position startpos moves e2e4 e7e5 g1f3", false).unwrap();
+        chazz.set_position(&position);
+
+        for name in ["fifth", "sixth", "random"] {
+            chazz.set_option("Strategy", name).unwrap();
+            assert_eq!(chazz.name(), name);
+            // A nova engine joga na posição da sessão: são as pretas
+            let chess_move = chazz.search(&Limits { depth: Some(2), ..Default::default() }).best_move.unwrap();
+            assert!(position.board.legal(chess_move), "{}: {}", name, chess_move);
+        }
+
+        assert!(chazz.set_option("Strategy", "seventh").is_err());
+        assert_eq!(chazz.name(), "random");
+        assert_eq!(chazz.options()[0], ("Strategy", "random".to_string()));
+    }
+}
@@ -0,0 +1,260 @@
+// Tabelas de ataque do gerador de lances próprio: saltos pré-calculados para
+// peões, cavalos e reis, e "magic bitboards" para bispos e torres.
+//
+// Casas são índices de 0 (a1) a 63 (h8) e bitboards são `u64` com o bit `i`
+// ligado para a casa `i`. As tabelas são montadas na primeira consulta.
+
+use std::sync::OnceLock;
+
+const ROOK_DIRECTIONS: [(i32, i32); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+const BISHOP_DIRECTIONS: [(i32, i32); 4] = [(1, 1), (1, -1), (-1, 1), (-1, -1)];
+const KNIGHT_STEPS: [(i32, i32); 8] = [(1, 2), (2, 1), (2, -1), (1, -2), (-1, -2), (-2, -1), (-2, 1), (-1, 2)];
+const KING_STEPS: [(i32, i32); 8] = [(1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0), (-1, -1), (0, -1), (1, -1)];
+
+// Constantes mágicas achadas por busca aleatória (candidatos esparsos de
+// xorshift64*, aceitos quando nenhum par de ocupações com ataques diferentes
+// cai no mesmo índice). Usam todos os bits da máscara: 2^bits entradas por casa.
+const BISHOP_MAGICS: [u64; 64] = [
+    0x1010_2002_004a_1420, 0x3009_0801_0408_2090, 0x20a2_0204_0020_0808, 0x0204_4040_8002_0102,
+    0x0101_1040_0000_0028, 0x2881_1008_0400_00e8, 0x1031_0110_3220_0020, 0x0041_0401_1892_1000,
+    0x0400_0410_0481_2400, 0x4100_1081_8800_8081, 0x0020_4846_0404_2a09, 0x0000_0220_8a00_2100,
+    0x0000_0a12_1000_2805, 0x400a_4104_6044_8100, 0x0130_6048_0a08_6000, 0x2101_4114_0084_0412,
+    0x1a10_1004_0450_0409, 0x4010_0284_0102_6400, 0x2050_0008_0040_1020, 0x0008_2024_0400_1420,
+    0x0032_8804_00a0_0600, 0x0202_0000_2210_0202, 0x0204_0820_8211_1040, 0x480c_2100_8401_0800,
+    0x00c2_6204_1020_0200, 0x80c2_1020_4290_1202, 0x9000_3200_5004_0040, 0x8004_0800_1022_0040,
+    0x0020_0440_0200_3004, 0x1204_0188_4100_a003, 0x2004_2080_1402_0128, 0x0401_0302_0054_00a0,
+    0x0950_0845_0060_0402, 0x81e0_9009_0110_2200, 0x1004_0128_0084_12c0, 0x0402_0040_4294_0100,
+    0x2104_2040_1004_0100, 0x0420_0091_0080_2400, 0x0204_0822_2080_8082, 0x2002_0042_4802_0218,
+    0x0001_0421_6020_8400, 0x0044_0d01_4810_1080, 0x8044_a020_3000_0802, 0xc081_0442_0620_4800,
+    0x0000_2190_2080_0400, 0x8404_0100_4100_0201, 0x0221_0c01_0249_2209, 0x8010_0121_1028_3100,
+    0x0183_8801_09a0_0001, 0x1001_4110_9090_0080, 0x2002_1200_8404_5420, 0x2126_0878_4202_0022,
+    0x8040_0040_1041_0128, 0x0802_4030_c200_8020, 0x0121_2410_0481_2002, 0x0308_0108_2200_4000,
+    0x0083_0428_0514_1020, 0x0220_8042_1210_2288, 0x8000_0141_0088_0400, 0x1000_0800_0084_0410,
+    0x0088_0800_3120_3200, 0x0010_0220_0202_c202, 0x0000_0548_0254_0400, 0xa010_0411_0800_3100,
+];
+
+const ROOK_MAGICS: [u64; 64] = [
+    0x1080_0040_0880_1020, 0x0840_0920_02c0_3000, 0x1900_2000_1040_0900, 0x0880_1000_0800_0480,
+    0x4200_1004_2008_0200, 0x8100_0201_0008_0400, 0x0200_0401_1088_6200, 0x0200_0080_4022_0411,
+    0x0404_8000_8440_0220, 0x0000_4010_0040_2000, 0x0086_0010_8122_0440, 0x0408_8008_0010_0280,
+    0x000a_0012_0104_0820, 0x8848_8002_0084_0080, 0x4001_0001_0004_0200, 0x0442_0001_0210_5084,
+    0x9080_0100_2080_4100, 0x0040_4040_0020_1009, 0x0000_8080_1000_2009, 0x2200_0900_21d0_0100,
+    0x0008_0080_0804_0080, 0x0004_0040_0201_0040, 0x0011_0400_0801_5042, 0x0000_0a00_0176_8104,
+    0x0000_8000_8020_4009, 0x2010_0041_4000_2001, 0x9800_2002_8010_0080, 0x1000_1000_8008_0080,
+    0x0050_5005_0008_0100, 0x0000_0200_8004_0080, 0x0c10_0104_0042_0810, 0x1040_0082_0000_5104,
+    0x0180_8240_0880_04a0, 0x0882_8040_0480_2000, 0x0880_4020_0100_1100, 0x0000_1000_8080_0800,
+    0x2000_4801_3100_1500, 0x0002_0004_0080_0280, 0x0080_0201_0400_0810, 0x8044_1044_1200_00a1,
+    0x0000_8000_4000_8020, 0x0410_4020_1000_c000, 0x0001_0040_2001_0010, 0x0800_1001_0009_0021,
+    0x0004_0800_0400_8080, 0x0010_0400_0200_8080, 0x2012_0048_8102_0004, 0x8300_8424_4482_0011,
+    0x0088_4038_8201_0200, 0x0820_4000_8021_0100, 0x0110_9100_40a0_0300, 0x0801_1002_8008_0480,
+    0x0242_0090_0820_0600, 0x1002_0004_8950_0200, 0x0040_8002_0001_0080, 0x0091_8000_4100_0080,
+    0x000c_9180_0020_c101, 0x0a41_1040_0980_2103, 0x0008_8040_1202_210a, 0x0000_3000_8914_2101,
+    0x8002_0020_0410_0802, 0x3001_0002_084c_0007, 0x0888_2218_0081_3004, 0x0000_0820_8044_010a,
+];
+
+const RANK_1: u64 = 0xff;
+const RANK_8: u64 = 0xff << 56;
+const FILE_A: u64 = 0x0101_0101_0101_0101;
+const FILE_H: u64 = FILE_A << 7;
+
+/// Iterador sobre as casas ligadas de um bitboard, da menor para a maior.
+pub struct Squares(pub u64);
+
+impl Iterator for Squares {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.0 == 0 {
+            return None;
+        }
+        let square = self.0.trailing_zeros() as usize;
+        self.0 &= self.0 - 1;
+        Some(square)
+    }
+}
+
+#[derive(Clone, Copy, Default)]
+struct Magic {
+    mask: u64,
+    magic: u64,
+    shift: u32,
+    offset: usize,
+}
+
+impl Magic {
+    fn index(&self, occupied: u64) -> usize {
+        self.offset + ((occupied & self.mask).wrapping_mul(self.magic) >> self.shift) as usize
+    }
+}
+
+struct Tables {
+    pawn: [[u64; 64]; 2],
+    knight: [u64; 64],
+    king: [u64; 64],
+    bishop: [Magic; 64],
+    rook: [Magic; 64],
+    sliding: Vec<u64>,
+    between: Vec<u64>,
+    line: Vec<u64>,
+}
+
+fn tables() -> &'static Tables {
+    static TABLES: OnceLock<Tables> = OnceLock::new();
+    TABLES.get_or_init(Tables::new)
+}
+
+fn offset(square: usize, (df, dr): (i32, i32)) -> Option<usize> {
+    let (file, rank) = ((square % 8) as i32 + df, (square / 8) as i32 + dr);
+    ((0..8).contains(&file) && (0..8).contains(&rank)).then_some((rank * 8 + file) as usize)
+}
+
+/// Casas alcançadas com um passo em cada direção.
+fn steps(square: usize, deltas: &[(i32, i32)]) -> u64 {
+    deltas.iter().filter_map(|&delta| offset(square, delta)).fold(0, |bits, to| bits | 1 << to)
+}
+
+/// Ataques de uma peça deslizante, devagar, casa a casa: a referência das
+/// tabelas mágicas.
+fn slide(square: usize, occupied: u64, directions: &[(i32, i32)]) -> u64 {
+    let mut attacks = 0;
+    for &direction in directions {
+        let mut current = square;
+        while let Some(to) = offset(current, direction) {
+            attacks |= 1 << to;
+            if occupied & (1 << to) != 0 {
+                break;
+            }
+            current = to;
+        }
+    }
+    attacks
+}
+
+/// Preenche a parte de `sliding` da casa com os ataques de cada ocupação
+/// relevante, no índice dado pela constante mágica.
+fn fill_magic(square: usize, directions: &[(i32, i32)], magic: u64, sliding: &mut Vec<u64>) -> Magic {
+    // As bordas não mudam os ataques, a não ser na própria fileira ou coluna
+    let edges = ((RANK_1 | RANK_8) & !(RANK_1 << (8 * (square / 8)))) | ((FILE_A | FILE_H) & !(FILE_A << (square % 8)));
+    let mask = slide(square, 0, directions) & !edges;
+    let bits = mask.count_ones();
+    let entry = Magic { mask, magic, shift: 64 - bits, offset: sliding.len() };
+    sliding.resize(sliding.len() + (1 << bits), 0);
+
+    // Percorre todos os subconjuntos da máscara (truque de Carry-Rippler)
+    let mut subset = 0u64;
+    loop {
+        sliding[entry.index(subset)] = slide(square, subset, directions);
+        subset = subset.wrapping_sub(mask) & mask;
+        if subset == 0 {
+            break;
+        }
+    }
+    entry
+}
+
+impl Tables {
+    fn new() -> Tables {
+        let mut tables = Tables {
+            pawn: [[0; 64]; 2],
+            knight: [0; 64],
+            king: [0; 64],
+            bishop: [Magic::default(); 64],
+            rook: [Magic::default(); 64],
+            sliding: Vec::new(),
+            between: vec![0; 64 * 64],
+            line: vec![0; 64 * 64],
+        };
+
+        for square in 0..64 {
+            tables.pawn[0][square] = steps(square, &[(-1, 1), (1, 1)]);
+            tables.pawn[1][square] = steps(square, &[(-1, -1), (1, -1)]);
+            tables.knight[square] = steps(square, &KNIGHT_STEPS);
+            tables.king[square] = steps(square, &KING_STEPS);
+            tables.bishop[square] = fill_magic(square, &BISHOP_DIRECTIONS, BISHOP_MAGICS[square], &mut tables.sliding);
+            tables.rook[square] = fill_magic(square, &ROOK_DIRECTIONS, ROOK_MAGICS[square], &mut tables.sliding);
+
+            for direction in KING_STEPS {
+                let opposite = (-direction.0, -direction.1);
+                let full = slide(square, 0, &[direction]) | slide(square, 0, &[opposite]) | 1 << square;
+                let mut ray = 0;
+                let mut current = square;
+                while let Some(to) = offset(current, direction) {
+                    tables.between[square * 64 + to] = ray;
+                    tables.line[square * 64 + to] = full;
+                    ray |= 1 << to;
+                    current = to;
+                }
+            }
+        }
+        tables
+    }
+}
+
+/// Casas atacadas por um peão da cor `color` (0 = brancas, 1 = pretas).
+pub fn pawn_attacks(color: usize, square: usize) -> u64 {
+    tables().pawn[color][square]
+}
+
+pub fn knight_attacks(square: usize) -> u64 {
+    tables().knight[square]
+}
+
+pub fn king_attacks(square: usize) -> u64 {
+    tables().king[square]
+}
+
+pub fn bishop_attacks(square: usize, occupied: u64) -> u64 {
+    let tables = tables();
+    tables.sliding[tables.bishop[square].index(occupied)]
+}
+
+pub fn rook_attacks(square: usize, occupied: u64) -> u64 {
+    let tables = tables();
+    tables.sliding[tables.rook[square].index(occupied)]
+}
+
+/// Casas estritamente entre `a` e `b`, se estiverem na mesma linha, coluna
+/// ou diagonal; senão, nenhuma.
+pub fn between(a: usize, b: usize) -> u64 {
+    tables().between[a * 64 + b]
+}
+
+/// A linha inteira (de borda a borda) que passa por `a` e `b`, ou nenhuma
+/// casa se não estiverem alinhadas.
+pub fn line(a: usize, b: usize) -> u64 {
+    tables().line[a * 64 + b]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Cada ocupação de cada casa, não só as relevantes: as casas fora da
+    /// máscara não podem mudar o resultado.
+    #[test]
+    fn magic_lookups_match_slow_attacks() {
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        for square in 0..64 {
+            for _ in 0..2000 {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                let occupied = state & (state >> 3);
+                assert_eq!(bishop_attacks(square, occupied), slide(square, occupied, &BISHOP_DIRECTIONS));
+                assert_eq!(rook_attacks(square, occupied), slide(square, occupied, &ROOK_DIRECTIONS));
+            }
+        }
+    }
+
+    #[test]
+    fn lines_and_segments() {
+        // a1..h8
+        assert_eq!(between(0, 63), 0x0040_2010_0804_0200);
+        assert_eq!(line(9, 18), 0x8040_2010_0804_0201);
+        // b1 e c3 não estão alinhadas; a1 e b1 são vizinhas
+        assert_eq!(between(1, 18), 0);
+        assert_eq!(line(1, 18), 0);
+        assert_eq!(between(0, 1), 0);
+        assert_eq!(line(0, 1), RANK_1);
+    }
+}
@@ -0,0 +1,742 @@
+// Tabuleiro próprio em bitboards, com lances feitos e desfeitos no lugar por
+// uma pilha de desfazer e hash Zobrist atualizado a cada lance. Consultas e
+// tipos (`ChessMove`, `Square`, `Piece`, `Color`, `BitBoard`) seguem os do
+// `chess::Board`, para que uma busca troque um pelo outro sem mudar de forma;
+// a diferença é que `make_move` altera o tabuleiro em vez de devolver uma
+// cópia, e `unmake_move` volta atrás.
+
+use chess::{BitBoard, BoardStatus, CastleRights, ChessMove, Color, Piece, Square, ALL_SQUARES};
+use std::ops::{Deref, DerefMut};
+use std::sync::OnceLock;
+
+use crate::bitboard::{
+    between, bishop_attacks, king_attacks, knight_attacks, line, pawn_attacks, rook_attacks, Squares,
+};
+
+const WHITE_KINGSIDE: u8 = 1;
+const WHITE_QUEENSIDE: u8 = 2;
+const BLACK_KINGSIDE: u8 = 4;
+const BLACK_QUEENSIDE: u8 = 8;
+
+const PROMOTIONS: [Piece; 4] = [Piece::Queen, Piece::Knight, Piece::Rook, Piece::Bishop];
+const PROMOTION_RANKS: u64 = 0xff | 0xff << 56;
+
+/// Direitos de roque que sobrevivem a um lance que sai de ou chega à casa:
+/// mexer no rei ou numa torre (ou capturá-la) perde os direitos dela.
+const CASTLING_MASK: [u8; 64] = {
+    let mut mask = [0b1111; 64];
+    mask[0] = !WHITE_QUEENSIDE;
+    mask[4] = !(WHITE_KINGSIDE | WHITE_QUEENSIDE);
+    mask[7] = !WHITE_KINGSIDE;
+    mask[56] = !BLACK_QUEENSIDE;
+    mask[60] = !(BLACK_KINGSIDE | BLACK_QUEENSIDE);
+    mask[63] = !BLACK_KINGSIDE;
+    mask
+};
+
+/// Chaves Zobrist: peça e cor por casa, direitos de roque, coluna do en
+/// passant e lado a mover.
+struct Keys {
+    pieces: [[[u64; 64]; 6]; 2],
+    castling: [u64; 16],
+    en_passant: [u64; 8],
+    side: u64,
+}
+
+fn keys() -> &'static Keys {
+    static KEYS: OnceLock<Keys> = OnceLock::new();
+    KEYS.get_or_init(|| {
+        // splitmix64 com semente fixa
+        let mut state = 0x5eed_c4a2_2a7e_b00du64;
+        let mut next = || {
+            state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            let mut z = state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+            z ^ (z >> 31)
+        };
+        let mut keys = Keys { pieces: [[[0; 64]; 6]; 2], castling: [0; 16], en_passant: [0; 8], side: 0 };
+        keys.pieces.iter_mut().flatten().flatten().for_each(|key| *key = next());
+        keys.castling.iter_mut().for_each(|key| *key = next());
+        keys.en_passant.iter_mut().for_each(|key| *key = next());
+        keys.side = next();
+        keys
+    })
+}
+
+/// Lances legais de uma posição, sem alocação.
+pub struct MoveList {
+    moves: [ChessMove; 256],
+    len: usize,
+}
+
+impl MoveList {
+    fn new() -> MoveList {
+        MoveList { moves: [ChessMove::default(); 256], len: 0 }
+    }
+
+    fn push(&mut self, from: usize, to: usize, promotion: Option<Piece>) {
+        self.moves[self.len] = ChessMove::new(ALL_SQUARES[from], ALL_SQUARES[to], promotion);
+        self.len += 1;
+    }
+}
+
+impl Deref for MoveList {
+    type Target = [ChessMove];
+
+    fn deref(&self) -> &[ChessMove] {
+        &self.moves[..self.len]
+    }
+}
+
+impl DerefMut for MoveList {
+    fn deref_mut(&mut self) -> &mut [ChessMove] {
+        &mut self.moves[..self.len]
+    }
+}
+
+/// O que `make_move` não consegue reconstruir ao desfazer o lance.
+#[derive(Clone, Copy)]
+struct Undo {
+    chess_move: ChessMove,
+    captured: Option<Piece>,
+    castling: u8,
+    en_passant: Option<usize>,
+    checkers: u64,
+    halfmove: u32,
+    hash: u64,
+}
+
+#[derive(Clone)]
+pub struct Board {
+    pieces: [u64; 6],
+    colors: [u64; 2],
+    squares: [Option<Piece>; 64],
+    side: Color,
+    castling: u8,
+    /// Casa de destino de uma captura en passant, só quando algum peão a ataca.
+    en_passant: Option<usize>,
+    /// Peças que dão xeque em quem joga, calculadas ao fazer o lance.
+    checkers: u64,
+    halfmove: u32,
+    hash: u64,
+    history: Vec<Undo>,
+}
+
+impl Default for Board {
+    fn default() -> Board {
+        Board::from(&chess::Board::default())
+    }
+}
+
+impl From<&chess::Board> for Board {
+    fn from(board: &chess::Board) -> Board {
+        let mut new = Board {
+            pieces: [0; 6],
+            colors: [0; 2],
+            squares: [None; 64],
+            side: board.side_to_move(),
+            castling: 0,
+            en_passant: None,
+            checkers: 0,
+            halfmove: 0,
+            hash: 0,
+            history: Vec::new(),
+        };
+        for square in *board.combined() {
+            new.put(square.to_index(), board.color_on(square).unwrap(), board.piece_on(square).unwrap());
+        }
+        for (color, kingside, queenside) in
+            [(Color::White, WHITE_KINGSIDE, WHITE_QUEENSIDE), (Color::Black, BLACK_KINGSIDE, BLACK_QUEENSIDE)]
+        {
+            let rights = board.castle_rights(color);
+            if rights.has_kingside() {
+                new.castling |= kingside;
+            }
+            if rights.has_queenside() {
+                new.castling |= queenside;
+            }
+        }
+        // O `chess` guarda a casa do peão que avançou duas casas; aqui vale a
+        // casa atrás dele, onde a captura termina
+        new.en_passant = board.en_passant().map(|square| match board.side_to_move() {
+            Color::White => square.to_index() + 8,
+            Color::Black => square.to_index() - 8,
+        });
+        new.checkers = new.compute_checkers();
+        new.hash = new.compute_hash();
+        new
+    }
+}
+
+impl Board {
+    pub fn side_to_move(&self) -> Color {
+        self.side
+    }
+
+    pub fn piece_on(&self, square: Square) -> Option<Piece> {
+        self.squares[square.to_index()]
+    }
+
+    pub fn color_on(&self, square: Square) -> Option<Color> {
+        let bit = 1 << square.to_index();
+        if self.colors[0] & bit != 0 {
+            Some(Color::White)
+        } else if self.colors[1] & bit != 0 {
+            Some(Color::Black)
+        } else {
+            None
+        }
+    }
+
+    pub fn pieces(&self, piece: Piece) -> BitBoard {
+        BitBoard(self.pieces[piece.to_index()])
+    }
+
+    pub fn color_combined(&self, color: Color) -> BitBoard {
+        BitBoard(self.colors[color.to_index()])
+    }
+
+    pub fn combined(&self) -> BitBoard {
+        BitBoard(self.colors[0] | self.colors[1])
+    }
+
+    /// Peças adversárias que dão xeque no rei de quem joga.
+    pub fn checkers(&self) -> BitBoard {
+        BitBoard(self.checkers)
+    }
+
+    pub fn king_square(&self, color: Color) -> Square {
+        ALL_SQUARES[self.king(color.to_index())]
+    }
+
+    pub fn castle_rights(&self, color: Color) -> CastleRights {
+        let (kingside, queenside) = match color {
+            Color::White => (WHITE_KINGSIDE, WHITE_QUEENSIDE),
+            Color::Black => (BLACK_KINGSIDE, BLACK_QUEENSIDE),
+        };
+        match (self.castling & kingside != 0, self.castling & queenside != 0) {
+            (true, true) => CastleRights::Both,
+            (true, false) => CastleRights::KingSide,
+            (false, true) => CastleRights::QueenSide,
+            (false, false) => CastleRights::NoRights,
+        }
+    }
+
+    /// Casa do peão que acabou de avançar duas casas, se puder ser capturado
+    /// en passant (como no `chess`, não a casa onde a captura termina).
+    pub fn en_passant(&self) -> Option<Square> {
+        self.en_passant.map(|square| match self.side {
+            Color::White => ALL_SQUARES[square - 8],
+            Color::Black => ALL_SQUARES[square + 8],
+        })
+    }
+
+    pub fn get_hash(&self) -> u64 {
+        self.hash
+    }
+
+    /// Meios-lances desde a última captura ou lance de peão.
+    pub fn halfmove_clock(&self) -> u32 {
+        self.halfmove
+    }
+
+    pub fn status(&self) -> BoardStatus {
+        if self.legal_move_count() > 0 {
+            BoardStatus::Ongoing
+        } else if self.checkers().0 != 0 {
+            BoardStatus::Checkmate
+        } else {
+            BoardStatus::Stalemate
+        }
+    }
+
+    fn king(&self, color: usize) -> usize {
+        (self.pieces[Piece::King.to_index()] & self.colors[color]).trailing_zeros() as usize
+    }
+
+    fn compute_checkers(&self) -> u64 {
+        let us = self.side.to_index();
+        self.attackers_to(self.king(us), self.colors[0] | self.colors[1]) & self.colors[1 - us]
+    }
+
+    /// `square` é atacada por alguma peça da cor `by` com a ocupação `occupied`?
+    fn attacked(&self, square: usize, occupied: u64, by: usize) -> bool {
+        let [pawns, knights, bishops, rooks, queens, kings] = self.pieces;
+        let enemy = self.colors[by];
+        pawn_attacks(1 - by, square) & pawns & enemy != 0
+            || knight_attacks(square) & knights & enemy != 0
+            || king_attacks(square) & kings & enemy != 0
+            || bishop_attacks(square, occupied) & (bishops | queens) & enemy != 0
+            || rook_attacks(square, occupied) & (rooks | queens) & enemy != 0
+    }
+
+    /// Peças das duas cores que atacam `square` com a ocupação `occupied`.
+    fn attackers_to(&self, square: usize, occupied: u64) -> u64 {
+        let [pawns, knights, bishops, rooks, queens, kings] = self.pieces;
+        (pawn_attacks(0, square) & pawns & self.colors[1])
+            | (pawn_attacks(1, square) & pawns & self.colors[0])
+            | (knight_attacks(square) & knights)
+            | (king_attacks(square) & kings)
+            | (bishop_attacks(square, occupied) & (bishops | queens))
+            | (rook_attacks(square, occupied) & (rooks | queens))
+    }
+
+    fn compute_hash(&self) -> u64 {
+        let keys = keys();
+        let mut hash = keys.castling[self.castling as usize];
+        for square in Squares(self.colors[0] | self.colors[1]) {
+            let color = usize::from(self.colors[1] & (1 << square) != 0);
+            hash ^= keys.pieces[color][self.squares[square].unwrap().to_index()][square];
+        }
+        if let Some(square) = self.en_passant {
+            hash ^= keys.en_passant[square % 8];
+        }
+        if self.side == Color::Black {
+            hash ^= keys.side;
+        }
+        hash
+    }
+
+    fn put(&mut self, square: usize, color: Color, piece: Piece) {
+        self.pieces[piece.to_index()] |= 1 << square;
+        self.colors[color.to_index()] |= 1 << square;
+        self.squares[square] = Some(piece);
+        self.hash ^= keys().pieces[color.to_index()][piece.to_index()][square];
+    }
+
+    fn remove(&mut self, square: usize, color: Color, piece: Piece) {
+        self.pieces[piece.to_index()] &= !(1 << square);
+        self.colors[color.to_index()] &= !(1 << square);
+        self.squares[square] = None;
+        self.hash ^= keys().pieces[color.to_index()][piece.to_index()][square];
+    }
+
+    /// Casas da torre no roque do rei que vai de `from` para `to`.
+    fn castling_rook(from: usize, to: usize) -> (usize, usize) {
+        if to > from {
+            (from + 3, from + 1)
+        } else {
+            (from - 4, from - 1)
+        }
+    }
+
+    /// Faz um lance legal no lugar; `unmake_move` o desfaz.
+    pub fn make_move(&mut self, chess_move: ChessMove) {
+        let keys = keys();
+        let (from, to) = (chess_move.get_source().to_index(), chess_move.get_dest().to_index());
+        let us = self.side;
+        let them = !us;
+        let piece = self.squares[from].expect("lance sem peça na origem");
+        let captured = self.squares[to];
+
+        self.history.push(Undo {
+            chess_move,
+            captured,
+            castling: self.castling,
+            en_passant: self.en_passant,
+            checkers: self.checkers,
+            halfmove: self.halfmove,
+            hash: self.hash,
+        });
+
+        let en_passant = self.en_passant.take();
+        if let Some(square) = en_passant {
+            self.hash ^= keys.en_passant[square % 8];
+        }
+        self.halfmove += 1;
+
+        if let Some(captured) = captured {
+            self.remove(to, them, captured);
+            self.halfmove = 0;
+        }
+        self.remove(from, us, piece);
+        self.put(to, us, chess_move.get_promotion().unwrap_or(piece));
+
+        if piece == Piece::Pawn {
+            self.halfmove = 0;
+            if Some(to) == en_passant {
+                let victim = if us == Color::White { to - 8 } else { to + 8 };
+                self.remove(victim, them, Piece::Pawn);
+            } else if from.abs_diff(to) == 16 {
+                // Só marca o en passant se algum peão puder capturar
+                let square = (from + to) / 2;
+                if pawn_attacks(us.to_index(), square) & self.pieces[0] & self.colors[them.to_index()] != 0 {
+                    self.en_passant = Some(square);
+                    self.hash ^= keys.en_passant[square % 8];
+                }
+            }
+        } else if piece == Piece::King && from.abs_diff(to) == 2 {
+            let (rook_from, rook_to) = Board::castling_rook(from, to);
+            self.remove(rook_from, us, Piece::Rook);
+            self.put(rook_to, us, Piece::Rook);
+        }
+
+        self.hash ^= keys.castling[self.castling as usize];
+        self.castling &= CASTLING_MASK[from] & CASTLING_MASK[to];
+        self.hash ^= keys.castling[self.castling as usize];
+
+        self.side = them;
+        self.hash ^= keys.side;
+        self.checkers = self.compute_checkers();
+    }
+
+    /// Desfaz o último lance de `make_move`.
+    pub fn unmake_move(&mut self) {
+        let undo = self.history.pop().expect("nenhum lance para desfazer");
+        let (from, to) = (undo.chess_move.get_source().to_index(), undo.chess_move.get_dest().to_index());
+        let them = self.side;
+        let us = !them;
+        let moved = self.squares[to].unwrap();
+        let piece = if undo.chess_move.get_promotion().is_some() { Piece::Pawn } else { moved };
+
+        self.remove(to, us, moved);
+        self.put(from, us, piece);
+        if let Some(captured) = undo.captured {
+            self.put(to, them, captured);
+        } else if piece == Piece::Pawn && Some(to) == undo.en_passant {
+            let victim = if us == Color::White { to - 8 } else { to + 8 };
+            self.put(victim, them, Piece::Pawn);
+        } else if piece == Piece::King && from.abs_diff(to) == 2 {
+            let (rook_from, rook_to) = Board::castling_rook(from, to);
+            self.remove(rook_to, us, Piece::Rook);
+            self.put(rook_from, us, Piece::Rook);
+        }
+
+        self.side = us;
+        self.castling = undo.castling;
+        self.en_passant = undo.en_passant;
+        self.checkers = undo.checkers;
+        self.halfmove = undo.halfmove;
+        self.hash = undo.hash;
+    }
+
+    /// Todos os lances legais de quem joga.
+    pub fn legal_moves(&self) -> MoveList {
+        let mut list = MoveList::new();
+        self.generate(|from, targets, pawn| {
+            for to in Squares(targets) {
+                if pawn && (1 << to) & PROMOTION_RANKS != 0 {
+                    for promotion in PROMOTIONS {
+                        list.push(from, to, Some(promotion));
+                    }
+                } else {
+                    list.push(from, to, None);
+                }
+            }
+        });
+        list
+    }
+
+    /// Quantos lances legais quem joga tem, sem montar a lista.
+    pub fn legal_move_count(&self) -> usize {
+        let mut count = 0;
+        self.generate(|_, targets, pawn| {
+            count += targets.count_ones() as usize;
+            if pawn {
+                count += 3 * (targets & PROMOTION_RANKS).count_ones() as usize;
+            }
+        });
+        count
+    }
+
+    /// Gera os lances legais como origem e casas de destino; `pawn` marca os
+    /// de peão, que chegando à última fileira valem um lance por promoção.
+    fn generate(&self, mut emit: impl FnMut(usize, u64, bool)) {
+        let us = self.side.to_index();
+        let them = 1 - us;
+        let own = self.colors[us];
+        let enemy = self.colors[them];
+        let occupied = own | enemy;
+        let king = self.king(us);
+        let checkers = self.checkers;
+
+        // O rei não pode ir para casa atacada, nem se esconder atrás de si mesmo
+        let without_king = occupied & !(1 << king);
+        let king_moves = Squares(king_attacks(king) & !own)
+            .filter(|&to| !self.attacked(to, without_king, them))
+            .fold(0, |bits, to| bits | 1 << to);
+        emit(king, king_moves, false);
+        if checkers.count_ones() > 1 {
+            return;
+        }
+
+        // Em xeque, só valem lances que capturam a peça ou se interpõem
+        let target = match checkers {
+            0 => !own,
+            _ => checkers | between(king, checkers.trailing_zeros() as usize),
+        };
+
+        let [pawns, knights, bishops, rooks, queens, _] = self.pieces;
+        let mut pinned = 0;
+        let snipers = (bishop_attacks(king, 0) & (bishops | queens) | rook_attacks(king, 0) & (rooks | queens)) & enemy;
+        for sniper in Squares(snipers) {
+            let blockers = between(king, sniper) & occupied;
+            if blockers.count_ones() == 1 {
+                pinned |= blockers & own;
+            }
+        }
+        // Peça cravada só anda na linha do rei
+        let allowed = |from: usize| if pinned & (1 << from) != 0 { line(king, from) } else { !0 };
+
+        for from in Squares(knights & own & !pinned) {
+            emit(from, knight_attacks(from) & target, false);
+        }
+        for from in Squares((bishops | queens) & own) {
+            emit(from, bishop_attacks(from, occupied) & target & allowed(from), false);
+        }
+        for from in Squares((rooks | queens) & own) {
+            emit(from, rook_attacks(from, occupied) & target & allowed(from), false);
+        }
+
+        let (forward, start_rank) = if us == 0 { (8, 1) } else { (-8i32, 6) };
+        for from in Squares(pawns & own) {
+            let mut moves = pawn_attacks(us, from) & enemy;
+            let one = (from as i32 + forward) as usize;
+            if occupied & (1 << one) == 0 {
+                moves |= 1 << one;
+                let two = (one as i32 + forward) as usize;
+                if from / 8 == start_rank && occupied & (1 << two) == 0 {
+                    moves |= 1 << two;
+                }
+            }
+            emit(from, moves & target & allowed(from), true);
+        }
+
+        if let Some(to) = self.en_passant {
+            let victim = (to as i32 - forward) as usize;
+            for from in Squares(pawn_attacks(them, to) & pawns & own) {
+                // Tira dois peões da mesma fileira de uma vez: confere o rei direto
+                let after = (occupied & !(1 << from) & !(1 << victim)) | 1 << to;
+                if self.attackers_to(king, after) & enemy & !(1 << victim) == 0 {
+                    emit(from, 1 << to, true);
+                }
+            }
+        }
+
+        if checkers == 0 {
+            let (kingside, queenside) =
+                if us == 0 { (WHITE_KINGSIDE, WHITE_QUEENSIDE) } else { (BLACK_KINGSIDE, BLACK_QUEENSIDE) };
+            let safe = |square: usize| !self.attacked(square, occupied, them);
+            if self.castling & kingside != 0
+                && occupied & (0b110 << king) == 0
+                && safe(king + 1)
+                && safe(king + 2)
+            {
+                emit(king, 1 << (king + 2), false);
+            }
+            if self.castling & queenside != 0
+                && occupied & (0b111 << (king - 3)) == 0
+                && safe(king - 1)
+                && safe(king - 2)
+            {
+                emit(king, 1 << (king - 2), false);
+            }
+        }
+    }
+}
+
+/// Consultas que o `chess::Board` e o `Board` daqui respondem do mesmo jeito.
+/// Código escrito sobre elas, como uma avaliação, serve aos dois tabuleiros:
+/// à busca, que faz e desfaz lances neste, e ao resto, que usa o do `chess`.
+pub trait Query {
+    fn side_to_move(&self) -> Color;
+    fn piece_on(&self, square: Square) -> Option<Piece>;
+    fn color_on(&self, square: Square) -> Option<Color>;
+    fn pieces(&self, piece: Piece) -> BitBoard;
+    fn color_combined(&self, color: Color) -> BitBoard;
+    fn combined(&self) -> BitBoard;
+    fn checkers(&self) -> BitBoard;
+    fn king_square(&self, color: Color) -> Square;
+    fn castle_rights(&self, color: Color) -> CastleRights;
+    fn en_passant(&self) -> Option<Square>;
+    fn status(&self) -> BoardStatus;
+}
+
+impl Query for chess::Board {
+    fn side_to_move(&self) -> Color {
+        self.side_to_move()
+    }
+
+    fn piece_on(&self, square: Square) -> Option<Piece> {
+        self.piece_on(square)
+    }
+
+    fn color_on(&self, square: Square) -> Option<Color> {
+        self.color_on(square)
+    }
+
+    fn pieces(&self, piece: Piece) -> BitBoard {
+        *self.pieces(piece)
+    }
+
+    fn color_combined(&self, color: Color) -> BitBoard {
+        *self.color_combined(color)
+    }
+
+    fn combined(&self) -> BitBoard {
+        *self.combined()
+    }
+
+    fn checkers(&self) -> BitBoard {
+        *self.checkers()
+    }
+
+    fn king_square(&self, color: Color) -> Square {
+        self.king_square(color)
+    }
+
+    fn castle_rights(&self, color: Color) -> CastleRights {
+        self.castle_rights(color)
+    }
+
+    fn en_passant(&self) -> Option<Square> {
+        chess::Board::en_passant(*self)
+    }
+
+    fn status(&self) -> BoardStatus {
+        self.status()
+    }
+}
+
+impl Query for Board {
+    fn side_to_move(&self) -> Color {
+        self.side_to_move()
+    }
+
+    fn piece_on(&self, square: Square) -> Option<Piece> {
+        self.piece_on(square)
+    }
+
+    fn color_on(&self, square: Square) -> Option<Color> {
+        self.color_on(square)
+    }
+
+    fn pieces(&self, piece: Piece) -> BitBoard {
+        self.pieces(piece)
+    }
+
+    fn color_combined(&self, color: Color) -> BitBoard {
+        self.color_combined(color)
+    }
+
+    fn combined(&self) -> BitBoard {
+        self.combined()
+    }
+
+    fn checkers(&self) -> BitBoard {
+        self.checkers()
+    }
+
+    fn king_square(&self, color: Color) -> Square {
+        self.king_square(color)
+    }
+
+    fn castle_rights(&self, color: Color) -> CastleRights {
+        self.castle_rights(color)
+    }
+
+    fn en_passant(&self) -> Option<Square> {
+        self.en_passant()
+    }
+
+    fn status(&self) -> BoardStatus {
+        self.status()
+    }
+}
+
+impl std::fmt::Debug for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for rank in (0..8).rev() {
+            for file in 0..8 {
+                let square = ALL_SQUARES[rank * 8 + file];
+                let symbol = match (self.piece_on(square), self.color_on(square)) {
+                    (Some(piece), Some(color)) => piece.to_string(color),
+                    _ => ".".to_string(),
+                };
+                write!(f, "{}", symbol)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chess::MoveGen;
+    use std::collections::BTreeSet;
+    use std::str::FromStr;
+
+    const FENS: [&str; 6] = [
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+        "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+        "8/8/1k6/2b5/2pP4/8/5K2/8 b - d3 0 1",
+    ];
+
+    /// Joga partidas aleatórias pelas posições de teste conferindo, a cada
+    /// lance, os lances legais contra o `chess`, o hash incremental contra o
+    /// recalculado e que `unmake_move` volta exatamente ao que era.
+    #[test]
+    fn random_games_match_the_chess_crate() {
+        let mut state = 0x1234_5678u64;
+        for fen in FENS {
+            for _ in 0..20 {
+                let mut reference = chess::Board::from_str(fen).unwrap();
+                let mut board = Board::from(&reference);
+                for _ in 0..80 {
+                    let expected: BTreeSet<String> = MoveGen::new_legal(&reference).map(|m| m.to_string()).collect();
+                    let moves = board.legal_moves();
+                    let actual: BTreeSet<String> = moves.iter().map(|m| m.to_string()).collect();
+                    assert_eq!(actual, expected, "{}", reference);
+                    assert_eq!(moves.len(), expected.len(), "lances repetidos em {}", reference);
+                    assert_eq!(board.legal_move_count(), expected.len());
+                    assert_eq!(board.checkers().0, reference.checkers().0);
+                    assert_eq!(board.checkers, board.compute_checkers());
+                    assert_eq!(board.get_hash(), board.compute_hash());
+                    assert_eq!(board.get_hash(), Board::from(&reference).get_hash(), "{}", reference);
+                    for color in [Color::White, Color::Black] {
+                        assert_eq!(board.king_square(color), reference.king_square(color));
+                        assert_eq!(board.castle_rights(color), reference.castle_rights(color), "{}", reference);
+                    }
+                    assert_eq!(board.en_passant(), reference.en_passant(), "{}", reference);
+                    if moves.is_empty() {
+                        break;
+                    }
+
+                    state ^= state << 13;
+                    state ^= state >> 7;
+                    state ^= state << 17;
+                    let chess_move = moves[(state % moves.len() as u64) as usize];
+
+                    let (before, hash) = (format!("{:?}", board), board.get_hash());
+                    board.make_move(chess_move);
+                    board.unmake_move();
+                    assert_eq!(format!("{:?}", board), before);
+                    assert_eq!(board.get_hash(), hash);
+
+                    board.make_move(chess_move);
+                    reference = reference.make_move_new(chess_move);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn status_and_halfmove_clock() {
+        let mate = Board::from(&chess::Board::from_str("7k/6Q1/6K1/8/8/8/8/8 b - - 0 1").unwrap());
+        assert_eq!(mate.status(), BoardStatus::Checkmate);
+        let stalemate = Board::from(&chess::Board::from_str("7k/8/6Q1/8/8/8/8/K7 b - - 0 1").unwrap());
+        assert_eq!(stalemate.status(), BoardStatus::Stalemate);
+
+        let mut board = Board::default();
+        board.make_move(ChessMove::from_str("g1f3").unwrap());
+        assert_eq!(board.halfmove_clock(), 1);
+        board.make_move(ChessMove::from_str("e7e5").unwrap());
+        assert_eq!(board.halfmove_clock(), 0);
+    }
+}
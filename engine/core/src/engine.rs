@@ -0,0 +1,53 @@
+// Interface comum às gerações da engine, para que o mesmo laço do protocolo
+// (e o binário `chazz`) possa usar qualquer uma delas.
+
+use chess::ChessMove;
+
+use crate::position::Position;
+
+/// Limites de `go`; o que ficar `None` é decidido pela engine, assim como
+/// os limites que ela não souber respeitar. `movetime` é em milissegundos.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Limits {
+    pub depth: Option<u32>,
+    pub nodes: Option<u64>,
+    pub movetime: Option<u64>,
+}
+
+/// Resultado de uma busca. `score` é do ponto de vista de quem joga, na
+/// escala da engine; `nodes` fica em 0 nas engines que não contam nós.
+#[derive(Clone, Debug, Default)]
+pub struct SearchResult {
+    pub best_move: Option<ChessMove>,
+    pub score: i32,
+    pub depth: u32,
+    pub nodes: u64,
+    pub pv: Vec<ChessMove>,
+}
+
+pub trait Engine {
+    /// Nome da geração, como aceito por `--engine` e pela opção `Strategy`.
+    fn name(&self) -> &'static str;
+
+    /// Início de uma nova partida: descarta o que a engine guardou da anterior.
+    fn new_game(&mut self) {}
+
+    fn set_position(&mut self, position: &Position);
+
+    fn search(&mut self, limits: &Limits) -> SearchResult;
+
+    /// Opções aceitas por `set_option`, com o valor atual.
+    fn options(&self) -> Vec<(&'static str, String)> {
+        Vec::new()
+    }
+
+    fn set_option(&mut self, name: &str, _value: &str) -> Result<(), String> {
+        Err(format!("opção desconhecida: {}", name))
+    }
+
+    /// Comando que o protocolo não conhece; devolve `false` se também não for
+    /// desta engine.
+    fn command(&mut self, _input: &str) -> bool {
+        false
+    }
+}
@@ -0,0 +1,41 @@
+// Peças e termos de avaliação comuns a todas as gerações da engine.
+
+use chess::{Board, Piece, Square};
+
+/// Valor das peças em peões, usado pela avaliação das primeiras engines e
+/// pela ordenação de lances.
+pub fn piece_value(piece: Piece) -> i32 {
+    match piece {
+        Piece::Pawn => 1,
+        Piece::Knight | Piece::Bishop => 3,
+        Piece::Rook => 5,
+        Piece::Queen => 9,
+        Piece::King => 1000,
+    }
+}
+
+pub const CENTER_SQUARES: [Square; 4] = [Square::D4, Square::D5, Square::E4, Square::E5];
+
+/// Soma de `piece_value` do lado a mover menos a do adversário.
+pub fn material(board: &Board) -> i32 {
+    let us = *board.color_combined(board.side_to_move());
+    let mut score = 0;
+    for square in *board.combined() {
+        let value = piece_value(board.piece_on(square).unwrap());
+        if us & chess::BitBoard::from_square(square) != chess::EMPTY {
+            score += value;
+        } else {
+            score -= value;
+        }
+    }
+    score
+}
+
+/// `bonus` por peça própria no centro, menos `bonus` por peça adversária.
+pub fn center_control(board: &Board, bonus: i32) -> i32 {
+    CENTER_SQUARES
+        .iter()
+        .filter_map(|&square| board.color_on(square))
+        .map(|color| if color == board.side_to_move() { bonus } else { -bonus })
+        .sum()
+}
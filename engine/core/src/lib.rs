@@ -0,0 +1,14 @@
+// Núcleo compartilhado pelas engines: a interface `Engine`, o laço do
+// protocolo, leitura e escrita de posições e lances, termos de avaliação
+// comuns, o gerador de lances próprio (`board`, sobre as tabelas de
+// `bitboard`) e as posições do bench.
+
+pub mod bench;
+pub mod bitboard;
+pub mod board;
+pub mod engine;
+pub mod eval;
+pub mod notation;
+pub mod perft;
+pub mod position;
+pub mod protocol;
@@ -0,0 +1,236 @@
+// Notação de lances: coordenadas (e2e4, e7e8q) e SAN (Nf3, exd5, O-O, e8=Q+).
+
+use chess::{Board, BoardStatus, ChessMove, Color, File, MoveGen, Piece, Rank, Square};
+use std::str::FromStr;
+
+fn piece_from_letter(letter: char) -> Option<Piece> {
+    match letter {
+        'N' => Some(Piece::Knight),
+        'B' => Some(Piece::Bishop),
+        'R' => Some(Piece::Rook),
+        'Q' => Some(Piece::Queen),
+        'K' => Some(Piece::King),
+        _ => None,
+    }
+}
+
+/// Lance em SAN; tolera sufixos (+, #, !, ?), "e.p.", "0-0" e a promoção sem "=".
+fn parse_san(board: &Board, text: &str) -> Option<ChessMove> {
+    let text = text.trim_end_matches(" e.p.").trim_end_matches(['+', '#', '!', '?']);
+
+    let castle = match text {
+        "O-O" | "0-0" => Some(File::G),
+        "O-O-O" | "0-0-0" => Some(File::C),
+        _ => None,
+    };
+    if let Some(file) = castle {
+        let king = board.king_square(board.side_to_move());
+        let chess_move = ChessMove::new(king, Square::make_square(king.get_rank(), file), None);
+        return (king.get_file() == File::E && board.legal(chess_move)).then_some(chess_move);
+    }
+
+    let mut chars: Vec<char> = text.chars().collect();
+    let piece = match chars.first().copied().and_then(piece_from_letter) {
+        Some(piece) => {
+            chars.remove(0);
+            piece
+        }
+        None => Piece::Pawn,
+    };
+
+    // Promoção no fim: "=Q" ou só "Q"
+    let promotion = match chars.last().copied().and_then(piece_from_letter) {
+        Some(promotion) if piece == Piece::Pawn => {
+            chars.pop();
+            if chars.last() == Some(&'=') {
+                chars.pop();
+            }
+            Some(promotion)
+        }
+        _ => None,
+    };
+
+    // Destino nos dois últimos caracteres; antes dele, desambiguação e "x"
+    if chars.len() < 2 {
+        return None;
+    }
+    let dest = Square::from_str(&chars[chars.len() - 2..].iter().collect::<String>()).ok()?;
+    let mut from_file = None;
+    let mut from_rank = None;
+    for &c in &chars[..chars.len() - 2] {
+        match c {
+            'a'..='h' => from_file = Some(File::from_index(c as usize - 'a' as usize)),
+            '1'..='8' => from_rank = Some(Rank::from_index(c as usize - '1' as usize)),
+            'x' | '-' | ':' => {}
+            _ => return None,
+        }
+    }
+
+    let mut candidates = MoveGen::new_legal(board).filter(|m| {
+        m.get_dest() == dest
+            && board.piece_on(m.get_source()) == Some(piece)
+            && m.get_promotion() == promotion
+            && from_file.is_none_or(|f| m.get_source().get_file() == f)
+            && from_rank.is_none_or(|r| m.get_source().get_rank() == r)
+    });
+    let chess_move = candidates.next()?;
+    // Mais de um lance possível: SAN ambígua
+    candidates.next().is_none().then_some(chess_move)
+}
+
+/// Lance escrito em coordenadas ou em SAN, legal em `board`.
+pub fn parse_move(board: &Board, text: &str) -> Result<ChessMove, String> {
+    if let Ok(chess_move) = ChessMove::from_str(text) {
+        if board.legal(chess_move) {
+            return Ok(chess_move);
+        }
+    }
+    parse_san(board, text).ok_or_else(|| format!("lance inválido: {}", text))
+}
+
+/// Lance em SAN, com desambiguação mínima e sufixo de xeque (+) ou mate (#).
+pub fn to_san(board: &Board, chess_move: ChessMove) -> String {
+    let source = chess_move.get_source();
+    let dest = chess_move.get_dest();
+    let piece = board.piece_on(source).unwrap_or(Piece::Pawn);
+    let mut san = String::new();
+
+    if piece == Piece::King && source.get_file().to_index().abs_diff(dest.get_file().to_index()) == 2 {
+        san.push_str(if dest.get_file() == File::G { "O-O" } else { "O-O-O" });
+    } else {
+        let capture = board.piece_on(dest).is_some() || (piece == Piece::Pawn && source.get_file() != dest.get_file());
+        if piece == Piece::Pawn {
+            if capture {
+                san.push(file_letter(source.get_file()));
+            }
+        } else {
+            san.push_str(&piece.to_string(Color::White));
+            // Outras peças iguais que também chegam ao destino
+            let rivals: Vec<Square> = MoveGen::new_legal(board)
+                .filter(|m| m.get_dest() == dest && m.get_source() != source && board.piece_on(m.get_source()) == Some(piece))
+                .map(|m| m.get_source())
+                .collect();
+            if !rivals.is_empty() {
+                let same_file = rivals.iter().any(|s| s.get_file() == source.get_file());
+                let same_rank = rivals.iter().any(|s| s.get_rank() == source.get_rank());
+                if !same_file {
+                    san.push(file_letter(source.get_file()));
+                } else if !same_rank {
+                    san.push(rank_digit(source.get_rank()));
+                } else {
+                    san.push(file_letter(source.get_file()));
+                    san.push(rank_digit(source.get_rank()));
+                }
+            }
+        }
+        if capture {
+            san.push('x');
+        }
+        san.push(file_letter(dest.get_file()));
+        san.push(rank_digit(dest.get_rank()));
+        if let Some(promotion) = chess_move.get_promotion() {
+            san.push('=');
+            san.push_str(&promotion.to_string(Color::White));
+        }
+    }
+
+    let after = board.make_move_new(chess_move);
+    if after.status() == BoardStatus::Checkmate {
+        san.push('#');
+    } else if after.checkers().popcnt() > 0 {
+        san.push('+');
+    }
+    san
+}
+
+/// Sequência de lances em SAN com números ("12. Nf3 Nc6 13. O-O"), a partir
+/// de `board` no lance `fullmove`.
+pub fn san_line(board: &Board, moves: &[ChessMove], fullmove: u32) -> String {
+    let mut board = *board;
+    let mut number = fullmove;
+    let mut words = Vec::new();
+    for (i, &chess_move) in moves.iter().enumerate() {
+        if board.side_to_move() == Color::White {
+            words.push(format!("{}.", number));
+        } else if i == 0 {
+            words.push(format!("{}...", number));
+        }
+        words.push(to_san(&board, chess_move));
+        if board.side_to_move() == Color::Black {
+            number += 1;
+        }
+        board = board.make_move_new(chess_move);
+    }
+    words.join(" ")
+}
+
+fn file_letter(file: File) -> char {
+    (b'a' + file.to_index() as u8) as char
+}
+
+fn rank_digit(rank: Rank) -> char {
+    (b'1' + rank.to_index() as u8) as char
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn board(fen: &str) -> Board {
+        Board::from_str(fen).unwrap()
+    }
+
+    fn mv(text: &str) -> ChessMove {
+        ChessMove::from_str(text).unwrap()
+    }
+
+    #[test]
+    fn check_and_mate_suffixes() {
+        // Mate do pastor
+        let before_mate = board("r1bqkb1r/pppp1ppp/2n2n2/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - 4 4");
+        assert_eq!(to_san(&before_mate, mv("h5f7")), "Qxf7#");
+        assert_eq!(to_san(&before_mate, mv("c4f7")), "Bxf7+");
+        assert_eq!(parse_move(&before_mate, "Qxf7#"), Ok(mv("h5f7")));
+        assert_eq!(parse_move(&before_mate, "Bxf7+!?"), Ok(mv("c4f7")));
+    }
+
+    #[test]
+    fn every_legal_move_round_trips() {
+        let fens = [
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+            "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+            "1k6/8/8/8/8/8/8/Q3K2Q w - - 0 1",
+        ];
+        for fen in fens {
+            let board = board(fen);
+            for chess_move in MoveGen::new_legal(&board) {
+                let san = to_san(&board, chess_move);
+                assert_eq!(parse_move(&board, &san), Ok(chess_move), "{} em {}", san, fen);
+            }
+        }
+    }
+
+    #[test]
+    fn queens_need_file_and_rank() {
+        let board = board("1k6/8/8/8/Q6Q/8/8/Q3K3 w - - 0 1");
+        // Damas em a4, h4 e a1 alcançam d4; a4 e a1 alcançam d1
+        assert_eq!(to_san(&board, mv("a4d4")), "Qa4d4");
+        assert_eq!(to_san(&board, mv("h4d4")), "Qhd4");
+        assert_eq!(to_san(&board, mv("a4d1")), "Q4d1");
+        assert_eq!(to_san(&board, mv("a1d1")), "Q1d1");
+    }
+
+    #[test]
+    fn coordinate_and_san_lines() {
+        let start = Board::default();
+        assert_eq!(parse_move(&start, "e2e4"), Ok(mv("e2e4")));
+        assert_eq!(parse_move(&start, "Nf3"), Ok(mv("g1f3")));
+        assert!(parse_move(&start, "Nf4").is_err());
+        assert_eq!(san_line(&start, &[mv("e2e4"), mv("e7e5"), mv("g1f3")], 1), "1. e4 e5 2. Nf3");
+
+        let black = start.make_move_new(mv("e2e4"));
+        assert_eq!(san_line(&black, &[mv("c7c5"), mv("g1f3")], 1), "1... c5 2. Nf3");
+    }
+}
@@ -0,0 +1,53 @@
+// Perft: contagem das folhas da árvore de lances legais até uma profundidade,
+// para conferir o gerador de lances contra valores conhecidos e contra o
+// gerador do `chess`.
+
+use chess::{ChessMove, MoveGen};
+
+use crate::board::Board;
+
+/// Número de posições alcançáveis em exatamente `depth` meios-lances, pelo
+/// gerador próprio.
+pub fn perft(board: &mut Board, depth: u32) -> u64 {
+    match depth {
+        0 => 1,
+        // Na última camada basta contar os lances
+        1 => board.legal_move_count() as u64,
+        _ => board
+            .legal_moves()
+            .iter()
+            .map(|&m| {
+                board.make_move(m);
+                let nodes = perft(board, depth - 1);
+                board.unmake_move();
+                nodes
+            })
+            .sum(),
+    }
+}
+
+/// Perft separado por lance da raiz, em ordem alfabética dos lances.
+pub fn divide(board: &mut Board, depth: u32) -> Vec<(ChessMove, u64)> {
+    let mut counts: Vec<(ChessMove, u64)> = board
+        .legal_moves()
+        .iter()
+        .map(|&m| {
+            board.make_move(m);
+            let nodes = perft(board, depth.saturating_sub(1));
+            board.unmake_move();
+            (m, nodes)
+        })
+        .collect();
+    counts.sort_by_key(|(m, _)| m.to_string());
+    counts
+}
+
+/// Perft pelo gerador do `chess`, a referência do gerador próprio.
+pub fn reference_perft(board: &chess::Board, depth: u32) -> u64 {
+    let moves = MoveGen::new_legal(board);
+    match depth {
+        0 => 1,
+        1 => moves.len() as u64,
+        _ => moves.map(|m| reference_perft(&board.make_move_new(m), depth - 1)).sum(),
+    }
+}
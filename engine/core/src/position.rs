@@ -0,0 +1,141 @@
+// Entrada e saída de posições: `position` do protocolo e o tabuleiro em ASCII.
+
+use chess::{Board, ChessMove, Color, File, Piece, Rank, Square};
+use std::str::FromStr;
+
+use crate::notation::parse_move;
+
+/// Posição atual da partida, meios-lances desde o início e as posições
+/// anteriores desde o último lance irreversível, as únicas que ainda podem se
+/// repetir. O histórico guarda o hash do `board::Board`, o mesmo que as buscas
+/// sobre ele calculam.
+#[derive(Clone, Default)]
+pub struct Position {
+    pub board: Board,
+    pub ply: u32,
+    pub history: Vec<u64>,
+}
+
+impl Position {
+    pub fn new(board: Board, ply: u32) -> Position {
+        Position { board, ply, history: Vec::new() }
+    }
+
+    /// Joga um lance legal, guardando a posição anterior no histórico.
+    pub fn push(&mut self, chess_move: ChessMove) {
+        if is_irreversible(&self.board, chess_move) {
+            self.history.clear();
+        } else {
+            self.history.push(crate::board::Board::from(&self.board).get_hash());
+        }
+        self.board = self.board.make_move_new(chess_move);
+        self.ply += 1;
+    }
+
+    /// Número do lance, como na FEN.
+    pub fn fullmove(&self) -> u32 {
+        self.ply / 2 + 1
+    }
+}
+
+/// Meios-lances desde o início da partida, pelo número do lance da FEN.
+pub fn game_ply(fen: &str, board: &Board) -> u32 {
+    let fullmove: u32 = fen.split_whitespace().nth(5).and_then(|n| n.parse().ok()).unwrap_or(1);
+    (fullmove.max(1) - 1) * 2 + u32::from(board.side_to_move() == Color::Black)
+}
+
+/// Contador de meios-lances da regra dos 50 lances, pelo campo da FEN.
+pub fn fen_halfmove(fen: &str) -> u32 {
+    fen.split_whitespace().nth(4).and_then(|n| n.parse().ok()).unwrap_or(0)
+}
+
+/// Captura ou lance de peão: zera o contador dos 50 lances e nenhuma posição
+/// anterior pode se repetir depois dele.
+pub fn is_irreversible(board: &Board, chess_move: ChessMove) -> bool {
+    board.piece_on(chess_move.get_source()) == Some(Piece::Pawn) || board.piece_on(chess_move.get_dest()).is_some()
+}
+
+/// FEN completa: o `Display` do `chess` sempre escreve `0 1` nos contadores.
+pub fn fen_with_counters(board: &Board, halfmove: u32, fullmove: u32) -> String {
+    let fen = board.to_string();
+    let fields: Vec<&str> = fen.split_whitespace().take(4).collect();
+    format!("{} {} {}", fields.join(" "), halfmove, fullmove)
+}
+
+/// `position <FEN | startpos> [moves <lance>...]`. Os lances vêm em
+/// coordenadas, ou também em SAN com `san` ligado.
+pub fn parse_position(input: &str, san: bool) -> Result<Position, String> {
+    let rest = input.strip_prefix("position").unwrap_or("").trim();
+    let (fen, moves) = match rest.split_once("moves") {
+        Some((fen, moves)) => (fen.trim(), moves),
+        None => (rest, ""),
+    };
+
+    let mut position = if fen == "startpos" {
+        Position::default()
+    } else {
+        let board = Board::from_str(fen).map_err(|_| "FEN inválida".to_string())?;
+        Position::new(board, game_ply(fen, &board))
+    };
+
+    for text in moves.split_whitespace() {
+        let board = &position.board;
+        let chess_move = if san {
+            parse_move(board, text)?
+        } else {
+            ChessMove::from_str(text).ok().filter(|m| board.legal(*m)).ok_or(format!("lance inválido: {}", text))?
+        };
+        position.push(chess_move);
+    }
+    Ok(position)
+}
+
+/// Desenha o tabuleiro em ASCII, brancas em maiúsculas, com a fileira 8 no topo.
+pub fn print_board(board: &Board) {
+    for rank in (0..8).rev() {
+        let mut line = format!("{} ", rank + 1);
+        for file in 0..8 {
+            let square = Square::make_square(Rank::from_index(rank), File::from_index(file));
+            let symbol = match (board.piece_on(square), board.color_on(square)) {
+                (Some(piece), Some(color)) => piece.to_string(color),
+                _ => ".".to_string(),
+            };
+            line.push(' ');
+            line.push_str(&symbol);
+        }
+        println!("{}", line);
+    }
+    println!("   a b c d e f g h");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counters_and_irreversible_moves() {
+        let fen = "r1bqkb1r/pppp1ppp/2n2n2/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - 4 4";
+        let board = Board::from_str(fen).unwrap();
+        assert_eq!(fen_halfmove(fen), 4);
+        assert_eq!(fen_halfmove("8/8/8/8/8/8/8/K6k w - -"), 0);
+        assert_eq!(fen_with_counters(&board, 4, 4), fen);
+
+        let mv = |text: &str| ChessMove::from_str(text).unwrap();
+        assert!(is_irreversible(&board, mv("h5f7")));
+        assert!(is_irreversible(&board, mv("d2d4")));
+        assert!(!is_irreversible(&board, mv("g1f3")));
+    }
+
+    #[test]
+    fn history_since_the_last_irreversible_move() {
+        let position = parse_position("position startpos moves g1f3 g8f6 f3g1 f6g8", false).unwrap();
+        assert_eq!(position.board, Board::default());
+        assert_eq!(position.ply, 4);
+        assert_eq!(position.history.len(), 4);
+        assert_eq!(position.history[0], crate::board::Board::default().get_hash());
+
+        let position = parse_position("position startpos moves g1f3 g8f6 e2e4 f6e4 b1c3", false).unwrap();
+        // A captura em e4 zera o histórico; sobra a posição antes de Nc3
+        assert_eq!(position.history.len(), 1);
+    }
+}
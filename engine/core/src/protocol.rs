@@ -0,0 +1,165 @@
+// Laço do protocolo de texto falado por todas as engines pela entrada padrão:
+//   position <FEN | startpos> [moves ...]   define a posição
+//   go [depth <n>] [nodes <n>] [movetime <ms>]
+//                                           responde com um lance em stdout
+//   newgame                                 avisa a engine de uma nova partida
+//   setoption name <nome> value <valor>     altera uma opção
+//   options                                 lista as opções e seus valores
+//   perft <n> | divide <n>                  conta as folhas da árvore de lances
+//   quit                                    encerra
+// Os demais comandos vão para `Engine::command`.
+
+use chess::ChessMove;
+use std::io::{self, BufRead, Write};
+use std::time::Instant;
+
+use crate::board::Board;
+use crate::engine::{Engine, Limits};
+use crate::perft::{divide, perft};
+use crate::position::{parse_position, Position};
+
+/// Escreve o lance em stdout, de onde a interface o lê.
+pub fn send_move(chess_move: ChessMove) {
+    println!("{}", chess_move);
+    io::stdout().flush().unwrap();
+}
+
+/// `go [depth <n>] [nodes <n>] [movetime <ms>]`.
+pub fn parse_go(input: &str) -> Result<Limits, String> {
+    let mut limits = Limits::default();
+    let mut words = input.split_whitespace().skip(1);
+    while let Some(word) = words.next() {
+        let value = words.next().ok_or(format!("{} precisa de um valor", word))?;
+        let invalid = |_| format!("valor inválido para {}: {}", word, value);
+        match word {
+            "depth" => limits.depth = Some(value.parse().map_err(invalid)?),
+            "nodes" => limits.nodes = Some(value.parse().map_err(invalid)?),
+            "movetime" => limits.movetime = Some(value.parse().map_err(invalid)?),
+            other => return Err(format!("limite desconhecido: {}", other)),
+        }
+    }
+    Ok(limits)
+}
+
+/// `setoption name <nome> [value <valor>]`: nome e valor.
+pub fn parse_setoption(input: &str) -> (&str, &str) {
+    let rest = input.strip_prefix("setoption name ").unwrap_or("");
+    match rest.split_once(" value ") {
+        Some((name, value)) => (name.trim(), value.trim()),
+        None => (rest.trim(), ""),
+    }
+}
+
+/// `perft <n>` e `divide <n>`: total de folhas, ou a contagem de cada lance
+/// da raiz seguida do total.
+fn run_perft(input: &str, position: &Position) -> Result<(), String> {
+    let (command, depth) = input.split_once(' ').ok_or(format!("{} precisa da profundidade", input))?;
+    let depth: u32 = depth.trim().parse().map_err(|_| format!("profundidade inválida: {}", depth))?;
+
+    let mut board = Board::from(&position.board);
+    let start = Instant::now();
+    let nodes = if command == "divide" {
+        let counts = divide(&mut board, depth);
+        for (chess_move, count) in &counts {
+            println!("{}: {}", chess_move, count);
+        }
+        counts.iter().map(|(_, count)| count).sum()
+    } else {
+        perft(&mut board, depth)
+    };
+    let elapsed = start.elapsed().as_millis().max(1) as u64;
+    println!("Nós: {} ({} ms, {} nps)", nodes, elapsed, nodes * 1000 / elapsed);
+    io::stdout().flush().unwrap();
+    Ok(())
+}
+
+/// Lê comandos até `quit` ou o fim da entrada. A opção `san`, tratada aqui
+/// para todas as engines, aceita SAN em `position ... moves`.
+pub fn run(engine: &mut dyn Engine) {
+    let mut position = Position::default();
+    let mut san = false;
+
+    for line in io::stdin().lock().lines() {
+        let Ok(line) = line else { break };
+        let input = line.trim();
+
+        if input == "quit" {
+            break;
+        } else if input.starts_with("position") {
+            match parse_position(input, san) {
+                Ok(new_position) => {
+                    position = new_position;
+                    engine.set_position(&position);
+                }
+                Err(e) => eprintln!("Erro: {}", e),
+            }
+        } else if input == "go" || input.starts_with("go ") {
+            let limits = match parse_go(input) {
+                Ok(limits) => limits,
+                Err(e) => {
+                    eprintln!("Erro: {}", e);
+                    continue;
+                }
+            };
+            let start = Instant::now();
+            let result = engine.search(&limits);
+            let elapsed = start.elapsed().as_millis().max(1) as u64;
+            // Estatísticas vão para stderr para não atrapalhar quem lê o lance em stdout
+            if result.nodes > 0 {
+                eprintln!("info nodes {} time {} nps {}", result.nodes, elapsed, result.nodes * 1000 / elapsed);
+            }
+            if let Some(chess_move) = result.best_move {
+                send_move(chess_move);
+            }
+        } else if input == "newgame" {
+            position = Position::default();
+            engine.new_game();
+        } else if input.starts_with("perft") || input.starts_with("divide") {
+            if let Err(e) = run_perft(input, &position) {
+                eprintln!("Erro: {}", e);
+            }
+        } else if input.starts_with("setoption") {
+            match parse_setoption(input) {
+                ("san", value) => san = value == "true",
+                (name, value) => {
+                    if let Err(e) = engine.set_option(name, value) {
+                        eprintln!("Erro: {}", e);
+                    }
+                }
+            }
+        } else if input == "options" {
+            println!("option name san value {}", san);
+            for (name, value) in engine.options() {
+                println!("option name {} value {}", name, value);
+            }
+            io::stdout().flush().unwrap();
+        } else if !input.is_empty() && !engine.command(input) {
+            eprintln!("Erro: comando desconhecido: {}", input);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn go_limits() {
+        let limits = parse_go("go").unwrap();
+        assert_eq!((limits.depth, limits.nodes, limits.movetime), (None, None, None));
+        let limits = parse_go("go depth 5 nodes 20000 movetime 150").unwrap();
+        assert_eq!((limits.depth, limits.nodes, limits.movetime), (Some(5), Some(20000), Some(150)));
+        assert!(parse_go("go depth").is_err());
+        assert!(parse_go("go depth -2").is_err());
+        assert!(parse_go("go nodes many").is_err());
+        assert!(parse_go("go wtime 1000").is_err());
+    }
+
+    #[test]
+    fn setoption_name_and_value() {
+        assert_eq!(parse_setoption("setoption name Contempt value 20"), ("Contempt", "20"));
+        assert_eq!(parse_setoption("setoption name SyzygyPath value /a b/c"), ("SyzygyPath", "/a b/c"));
+        assert_eq!(parse_setoption("setoption name Clear Hash"), ("Clear Hash", ""));
+        assert_eq!(parse_setoption("setoption"), ("", ""));
+    }
+}
@@ -0,0 +1,121 @@
+// Perft das posições de referência contra as contagens conhecidas
+// (chessprogramming.org/Perft_Results e a coleção de casos especiais de
+// Martin Sedlák), pelo gerador próprio e pelo do `chess`. As profundidades
+// ficam baixas o bastante para rodar em modo debug; `--ignored` inclui as
+// mais profundas.
+
+use chazz_core::board::Board;
+use chazz_core::perft::{divide, perft, reference_perft};
+use std::str::FromStr;
+
+const STARTPOS: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+const KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+const POSITION_3: &str = "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1";
+const POSITION_4: &str = "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1";
+const POSITION_4_MIRRORED: &str = "r2q1rk1/pP1p2pp/Q4n2/bbp1p3/Np6/1B3NBn/pPPP1PPP/R3K2R b KQ - 0 1";
+const POSITION_5: &str = "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8";
+const POSITION_6: &str = "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10";
+
+/// Perft pelos dois geradores, que precisam concordar.
+fn perft_at(fen: &str, depth: u32) -> u64 {
+    let reference = chess::Board::from_str(fen).unwrap();
+    let nodes = perft(&mut Board::from(&reference), depth);
+    assert_eq!(nodes, reference_perft(&reference, depth), "{} a profundidade {}", fen, depth);
+    nodes
+}
+
+fn assert_perft(fen: &str, expected: &[u64]) {
+    for (depth, &nodes) in (1..).zip(expected) {
+        assert_eq!(perft_at(fen, depth), nodes, "{} a profundidade {}", fen, depth);
+    }
+}
+
+#[test]
+fn startpos() {
+    assert_perft(STARTPOS, &[20, 400, 8902, 197281]);
+}
+
+#[test]
+fn kiwipete() {
+    assert_perft(KIWIPETE, &[48, 2039, 97862]);
+}
+
+#[test]
+fn position_3() {
+    assert_perft(POSITION_3, &[14, 191, 2812, 43238, 674624]);
+}
+
+#[test]
+fn position_4() {
+    assert_perft(POSITION_4, &[6, 264, 9467, 422333]);
+    assert_perft(POSITION_4_MIRRORED, &[6, 264, 9467, 422333]);
+}
+
+#[test]
+fn position_5() {
+    assert_perft(POSITION_5, &[44, 1486, 62379]);
+}
+
+#[test]
+fn position_6() {
+    assert_perft(POSITION_6, &[46, 2079, 89890]);
+}
+
+#[test]
+fn en_passant() {
+    // Captura en passant que deixaria o próprio rei em xeque
+    assert_eq!(perft_at("3k4/3p4/8/K1P4r/8/8/8/8 b - - 0 1", 6), 1134888);
+    // Captura en passant que dá xeque
+    assert_eq!(perft_at("8/8/1k6/2b5/2pP4/8/5K2/8 b - d3 0 1", 6), 1440467);
+    // Captura en passant que tira o rei adversário do xeque
+    assert_eq!(perft_at("8/8/4k3/8/2p5/8/B2P2K1/8 w - - 0 1", 6), 1015133);
+}
+
+#[test]
+fn castling() {
+    // Roques que dão xeque
+    assert_eq!(perft_at("5k2/8/8/8/8/8/8/4K2R w K - 0 1", 6), 661072);
+    assert_eq!(perft_at("3k4/8/8/8/8/8/8/R3K3 w Q - 0 1", 6), 803711);
+    // Direitos de roque perdidos por capturas de torre
+    assert_eq!(perft_at("r3k2r/1b4bq/8/8/8/8/7B/R3K2R w KQkq - 0 1", 4), 1274206);
+    // Roques impedidos por casas atacadas
+    assert_eq!(perft_at("r3k2r/8/3Q4/8/8/5q2/8/R3K2R b KQkq - 0 1", 4), 1720476);
+}
+
+#[test]
+fn promotion() {
+    // Promoção que sai do xeque
+    assert_eq!(perft_at("2K2r2/4P3/8/8/8/8/8/3k4 w - - 0 1", 6), 3821001);
+    // Promoção e subpromoção que dão xeque
+    assert_eq!(perft_at("4k3/1P6/8/8/8/8/K7/8 w - - 0 1", 6), 217342);
+    assert_eq!(perft_at("8/P1k5/K7/8/8/8/8/8 w - - 0 1", 6), 92683);
+}
+
+#[test]
+fn checks_and_game_end() {
+    // Xeque descoberto
+    assert_eq!(perft_at("8/8/1P2K3/8/2n5/1q6/8/5k2 b - - 0 1", 5), 1004658);
+    // Afogamento e mate logo à frente
+    assert_eq!(perft_at("K1k5/8/P7/8/8/8/8/8 w - - 0 1", 6), 2217);
+    assert_eq!(perft_at("8/k1P5/8/1K6/8/8/8/8 w - - 0 1", 7), 567584);
+    assert_eq!(perft_at("8/8/2k5/5q2/5n2/8/5K2/8 b - - 0 1", 4), 23527);
+}
+
+#[test]
+fn divide_adds_up_to_perft() {
+    let mut board = Board::from(&chess::Board::from_str(KIWIPETE).unwrap());
+    let counts = divide(&mut board, 3);
+    assert_eq!(counts.len(), 48);
+    assert_eq!(counts.iter().map(|(_, count)| count).sum::<u64>(), 97862);
+    let e5f7 = counts.iter().find(|(m, _)| m.to_string() == "e5f7").unwrap();
+    assert_eq!(e5f7.1, 2080);
+}
+
+#[test]
+#[ignore]
+fn deep() {
+    assert_perft(STARTPOS, &[20, 400, 8902, 197281, 4865609]);
+    assert_perft(KIWIPETE, &[48, 2039, 97862, 4085603]);
+    assert_perft(POSITION_5, &[44, 1486, 62379, 2103487]);
+    assert_perft(POSITION_6, &[46, 2079, 89890, 3894594]);
+}
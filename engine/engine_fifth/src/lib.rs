@@ -0,0 +1,205 @@
+// Quinta geração: alfa-beta com ordenação de lances, detecção de repetição,
+// mobilidade e penalidade por xeque.
+
+use chazz_core::bench::bench;
+use chazz_core::board::Board;
+use chazz_core::engine::{Engine, Limits, SearchResult};
+use chazz_core::eval::{piece_value, CENTER_SQUARES};
+use chazz_core::position::Position;
+use chess::{BoardStatus, ChessMove};
+use std::collections::HashMap;
+
+const DEPTH: i32 = 3; // Abaixo da raiz; ajuste conforme necessário
+
+
+fn evaluate_board(board: &Board) -> i32 {
+    let mut score = 0;
+    let side_to_move = board.side_to_move();
+
+    // Avaliar material e controle do centro
+    for square in chess::ALL_SQUARES.iter() {
+        if let Some(piece) = board.piece_on(*square) {
+            let value = piece_value(piece);
+            if board.color_on(*square) == Some(side_to_move) {
+                score += value;
+                // Bônus para controle do centro
+                if CENTER_SQUARES.contains(square) {
+                    score += 2;
+                }
+            } else {
+                score -= value;
+                if CENTER_SQUARES.contains(square) {
+                    score -= 2;
+                }
+            }
+        }
+    }
+
+    // Verificar status do jogo
+    match board.status() {
+        BoardStatus::Checkmate => {
+            // IMPORTANTE: Se estamos em xeque-mate, quem tem que mover perdeu
+            return -20000; // Valor negativo muito alto, pois xeque-mate é ruim para quem deve mover
+        }
+        BoardStatus::Stalemate => {
+            return 0; // Empate
+        }
+        _ => {
+            // Bônus para xeque
+            if board.checkers().popcnt() > 0 {
+                score -= 30; // Estar em xeque é ruim para o lado que vai mover
+            }
+            
+            // Bônus para mobilidade
+            let mobility = board.legal_move_count() as i32;
+            score += mobility / 10; // Mais movimentos é melhor para quem vai mover
+        }
+    }
+
+    score
+}
+
+/// Estado da busca: o tabuleiro, em que os lances são feitos e desfeitos no
+/// lugar, a contagem de repetições no caminho atual e os nós visitados.
+struct Search {
+    board: Board,
+    repetitions: HashMap<u64, i32>,
+    nodes: u64,
+}
+
+impl Search {
+    fn alpha_beta(&mut self, depth: i32, mut alpha: i32, mut beta: i32, maximizing: bool) -> i32 {
+        self.nodes += 1;
+        let board = &mut self.board;
+
+        // Se chegamos à profundidade máxima ou fim de jogo, avaliar a posição
+        if depth == 0 || board.status() != BoardStatus::Ongoing {
+            return if maximizing { evaluate_board(board) } else { -evaluate_board(board) };
+        }
+
+        let hash = board.get_hash();
+        let count = self.repetitions.get(&hash).cloned().unwrap_or(0);
+        if count >= 3 {
+            return 0; // Empate por repetição
+        }
+        self.repetitions.insert(hash, count + 1);
+
+        let mut moves = board.legal_moves();
+
+        // Ordenar movimentos (capturas, xeques e xeque-mates primeiro)
+        moves.sort_by_cached_key(|m| {
+            let mut score = 0;
+
+            // Verificar se captura alguma peça
+            if let Some(captured) = board.piece_on(m.get_dest()) {
+                score += piece_value(captured) * 10;
+            }
+
+            // Verificar se é uma promoção
+            if m.get_promotion().is_some() {
+                score += 800;
+            }
+
+            board.make_move(*m);
+
+            // Verificar se é xeque
+            if board.checkers().popcnt() > 0 {
+                score += 100;
+            }
+
+            // Verificar se é xeque-mate (maior prioridade)
+            if board.status() == BoardStatus::Checkmate {
+                score += 20000;
+            }
+
+            board.unmake_move();
+            score
+        });
+        moves.reverse(); // Prioriza maiores valores primeiro
+
+        let mut best_eval = if maximizing { -100000 } else { 100000 };
+
+        for &chess_move in moves.iter() {
+            self.board.make_move(chess_move);
+            let eval = self.alpha_beta(depth - 1, alpha, beta, !maximizing);
+            self.board.unmake_move();
+
+            if maximizing {
+                best_eval = best_eval.max(eval);
+                alpha = alpha.max(eval);
+            } else {
+                best_eval = best_eval.min(eval);
+                beta = beta.min(eval);
+            }
+
+            if beta <= alpha {
+                break; // Poda alfa-beta
+            }
+        }
+
+        self.repetitions.insert(hash, count); // Reverte a contagem
+        best_eval
+    }
+
+    fn best_move(&mut self, depth: i32) -> Option<(ChessMove, i32)> {
+        let mut best_move = None;
+        let mut best_value = -100000;
+
+        for &chess_move in self.board.legal_moves().iter() {
+            self.board.make_move(chess_move);
+            // IMPORTANTE: Nós somos o jogador maximizante no nível raiz,
+            // mas após nosso movimento, é o oponente que joga (minimizante)
+            let eval = self.alpha_beta(depth, -100000, 100000, false);
+            self.board.unmake_move();
+
+            // Debugging: imprimir valores para verificar
+            // eprintln!("Move: {}, Eval: {}", chess_move, eval);
+
+            if eval > best_value {
+                best_value = eval;
+                best_move = Some((chess_move, eval));
+            }
+        }
+
+        best_move
+    }
+}
+
+#[derive(Default)]
+pub struct Fifth {
+    board: chess::Board,
+}
+
+impl Engine for Fifth {
+    fn name(&self) -> &'static str {
+        "fifth"
+    }
+
+    fn set_position(&mut self, position: &Position) {
+        self.board = position.board;
+    }
+
+    fn search(&mut self, limits: &Limits) -> SearchResult {
+        let depth = limits.depth.map_or(DEPTH, |depth| depth as i32);
+        let mut search = Search { board: Board::from(&self.board), repetitions: HashMap::new(), nodes: 0 };
+        let (best_move, score) = search.best_move(depth).unzip();
+        SearchResult { best_move, score: score.unwrap_or(0), depth: depth as u32 + 1, nodes: search.nodes, ..Default::default() }
+    }
+
+    /// `bench [depth]`: nós por segundo nas posições do bench comum, pela
+    /// interface `Engine`. Serve para comparar o tabuleiro com make/unmake
+    /// com as cópias de `make_move_new` da versão anterior.
+    fn command(&mut self, input: &str) -> bool {
+        let mut words = input.split_whitespace();
+        if words.next() != Some("bench") {
+            return false;
+        }
+        match words.next().map_or(Ok(DEPTH as u32), str::parse) {
+            Ok(depth) if depth > 0 => {
+                bench(self, depth);
+            }
+            _ => eprintln!("Erro: profundidade inválida: {}", input),
+        }
+        true
+    }
+}
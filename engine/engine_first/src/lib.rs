@@ -0,0 +1,46 @@
+// Primeira geração: joga o lance que deixa mais material, sem busca.
+
+use chazz_core::engine::{Engine, Limits, SearchResult};
+use chazz_core::eval::material;
+use chazz_core::position::Position;
+use chess::{Board, ChessMove, MoveGen};
+
+fn best_move(board: &Board) -> Option<(ChessMove, i32)> {
+    let movegen = MoveGen::new_legal(board);
+    let mut best_move = None;
+    let mut best_value = -9999; 
+
+    for chess_move in movegen {
+        let new_board = board.make_move_new(chess_move);
+
+   
+        let eval = material(&new_board);
+        
+        if eval > best_value {
+            best_value = eval;
+            best_move = Some((chess_move, eval));
+        }
+    }
+    
+    best_move
+}
+
+#[derive(Default)]
+pub struct First {
+    board: Board,
+}
+
+impl Engine for First {
+    fn name(&self) -> &'static str {
+        "first"
+    }
+
+    fn set_position(&mut self, position: &Position) {
+        self.board = position.board;
+    }
+
+    fn search(&mut self, _limits: &Limits) -> SearchResult {
+        let (best_move, score) = best_move(&self.board).unzip();
+        SearchResult { best_move, score: score.unwrap_or(0), depth: 1, ..Default::default() }
+    }
+}
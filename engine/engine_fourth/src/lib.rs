@@ -0,0 +1,90 @@
+// Quarta geração: alfa-beta com bônus para as casas centrais.
+
+use chazz_core::engine::{Engine, Limits, SearchResult};
+use chazz_core::eval::{center_control, material};
+use chazz_core::position::Position;
+use chess::{Board, BoardStatus, ChessMove, MoveGen};
+
+const DEPTH: i32 = 3; // Abaixo da raiz
+
+
+fn evaluate_board(board: &Board) -> i32 {
+    material(board) + center_control(board, 2)
+}
+
+
+fn alpha_beta(board: &Board, depth: i32, mut alpha: i32, mut beta: i32, maximizing: bool) -> i32 {
+    if depth == 0 || board.status() != BoardStatus::Ongoing {
+        return evaluate_board(board);
+    }
+
+    let movegen = MoveGen::new_legal(board);
+    if maximizing {
+        let mut max_eval = -9999;
+        for chess_move in movegen {
+            let new_board = board.make_move_new(chess_move);
+
+            let eval = alpha_beta(&new_board, depth - 1, alpha, beta, false);
+            max_eval = max_eval.max(eval);
+            alpha = alpha.max(eval);
+
+            if beta <= alpha {
+                break;
+            }
+        }
+        max_eval
+    } else {
+        let mut min_eval = 9999;
+        for chess_move in movegen {
+            let new_board = board.make_move_new(chess_move);
+
+            let eval = alpha_beta(&new_board, depth - 1, alpha, beta, true);
+            min_eval = min_eval.min(eval);
+            beta = beta.min(eval);
+
+            if beta <= alpha {
+                break;
+            }
+        }
+        min_eval
+    }
+}
+
+
+fn best_move(board: &Board, depth: i32) -> Option<(ChessMove, i32)> {
+    let movegen = MoveGen::new_legal(board);
+    let mut best_move = None;
+    let mut best_value = -9999;
+
+    for chess_move in movegen {
+        let new_board = board.make_move_new(chess_move);
+
+        let eval = alpha_beta(&new_board, depth, -10000, 10000, false);
+        if eval > best_value {
+            best_value = eval;
+            best_move = Some((chess_move, eval));
+        }
+    }
+    best_move
+}
+
+#[derive(Default)]
+pub struct Fourth {
+    board: Board,
+}
+
+impl Engine for Fourth {
+    fn name(&self) -> &'static str {
+        "fourth"
+    }
+
+    fn set_position(&mut self, position: &Position) {
+        self.board = position.board;
+    }
+
+    fn search(&mut self, limits: &Limits) -> SearchResult {
+        let depth = limits.depth.map_or(DEPTH, |depth| depth as i32);
+        let (best_move, score) = best_move(&self.board, depth).unzip();
+        SearchResult { best_move, score: score.unwrap_or(0), depth: depth as u32 + 1, ..Default::default() }
+    }
+}
@@ -0,0 +1,62 @@
+// Segunda geração: minimax de profundidade fixa sobre o material.
+
+use chazz_core::engine::{Engine, Limits, SearchResult};
+use chazz_core::eval::material;
+use chazz_core::position::Position;
+use chess::{Board, ChessMove, MoveGen};
+
+const DEPTH: u32 = 3;
+
+fn minimax(board: &Board, depth: u32, maximizing: bool) -> (i32, Option<ChessMove>) {
+    if depth == 0 {
+        return (material(board), None);
+    }
+    
+    let movegen = MoveGen::new_legal(board);
+    let mut best_move = None;
+    
+    if maximizing {
+        let mut max_eval = i32::MIN;
+        for chess_move in movegen {
+            let new_board = board.make_move_new(chess_move);
+            let (eval, _) = minimax(&new_board, depth - 1, false);
+            if eval > max_eval {
+                max_eval = eval;
+                best_move = Some(chess_move);
+            }
+        }
+        (max_eval, best_move)
+    } else {
+        let mut min_eval = i32::MAX;
+        for chess_move in movegen {
+            let new_board = board.make_move_new(chess_move);
+            let (eval, _) = minimax(&new_board, depth - 1, true);
+            if eval < min_eval {
+                min_eval = eval;
+                best_move = Some(chess_move);
+            }
+        }
+        (min_eval, best_move)
+    }
+}
+
+#[derive(Default)]
+pub struct Second {
+    board: Board,
+}
+
+impl Engine for Second {
+    fn name(&self) -> &'static str {
+        "second"
+    }
+
+    fn set_position(&mut self, position: &Position) {
+        self.board = position.board;
+    }
+
+    fn search(&mut self, limits: &Limits) -> SearchResult {
+        let depth = limits.depth.unwrap_or(DEPTH);
+        let (score, best_move) = minimax(&self.board, depth, true);
+        SearchResult { best_move, score, depth, ..Default::default() }
+    }
+}
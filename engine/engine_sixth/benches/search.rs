@@ -0,0 +1,54 @@
+// Benchmarks da avaliação, da ordenação de lances e da busca sobre algumas
+// posições do `bench`: `cargo bench -p engine_sixth`.
+
+use chess::Board;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use std::str::FromStr;
+
+use chazz_core::bench::POSITIONS;
+use chazz_core::engine::Limits;
+use engine_sixth::eval::evaluate_board;
+use engine_sixth::search::{order_moves, search};
+use engine_sixth::Options;
+
+/// Abertura, meio-jogo tático, meio-jogo fechado e final.
+fn boards() -> Vec<(&'static str, Board)> {
+    [("abertura", 0), ("kiwipete", 1), ("meio-jogo", 12), ("final", 23)]
+        .into_iter()
+        .map(|(name, i)| (name, Board::from_str(POSITIONS[i]).unwrap()))
+        .collect()
+}
+
+fn evaluation(c: &mut Criterion) {
+    let options = Options::default();
+    let mut group = c.benchmark_group("evaluate_board");
+    for (name, board) in boards() {
+        group.bench_function(name, |b| b.iter(|| evaluate_board(black_box(&board), &options.params)));
+    }
+    group.finish();
+}
+
+fn ordering(c: &mut Criterion) {
+    let options = Options::default();
+    let mut group = c.benchmark_group("order_moves");
+    for (name, board) in boards() {
+        // A ordenação faz e desfaz os lances no tabuleiro da busca
+        let mut board = chazz_core::board::Board::from(&board);
+        group.bench_function(name, |b| b.iter(|| order_moves(black_box(&mut board), &options.params)));
+    }
+    group.finish();
+}
+
+fn searching(c: &mut Criterion) {
+    let options = Options::default();
+    let limits = Limits { depth: Some(3), ..Default::default() };
+    let mut group = c.benchmark_group("search");
+    group.sample_size(10);
+    for (name, board) in boards() {
+        group.bench_function(name, |b| b.iter(|| search(black_box(&board), &options, &limits)));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, evaluation, ordering, searching);
+criterion_main!(benches);
@@ -0,0 +1,486 @@
+// Gera as tabelas Syzygy de 3 peças de tests/fixtures/syzygy (KQvK, KRvK,
+// KBvK, KNvK e KPvK, WDL e DTZ) por análise retrógrada, sem depender de
+// download:
+//
+//   cargo run --release -p engine_sixth --example syzygy_fixtures
+//
+// Os arquivos seguem o formato lido pelo shakmaty-syzygy, com a mesma
+// indexação das tabelas oficiais. Só a compressão é mais simples: cada valor
+// vira um símbolo de tamanho fixo, sem os pares e os códigos de Huffman do
+// gerador oficial. Ficam maiores, mas dão os mesmos resultados. No fim, as
+// tabelas são relidas pelo shakmaty-syzygy e conferidas posição por posição,
+// com as cores trocadas também.
+//
+// A DTZ segue a convenção do shakmaty-syzygy: meios-lances até o próximo
+// lance que zera o contador de 50 lances (captura ou lance de peão) ou até o
+// mate; 1 para quem captura, promove ou dá mate em seguida, -1 para quem
+// levou mate.
+
+use shakmaty::{CastlingMode, Chess, Color, EnPassantMode, FromSetup, Piece, Position, Role, Setup, Square};
+use shakmaty_syzygy::{Tablebase, Wdl};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+const WDL_MAGIC: [u8; 4] = [0x71, 0xe8, 0x23, 0x5d];
+const DTZ_MAGIC: [u8; 4] = [0xd7, 0x66, 0x0c, 0xa5];
+
+// Tamanho dos blocos comprimidos e distância entre entradas do índice esparso
+const BLOCK_BITS: u8 = 10;
+const SPAN_BITS: u8 = 10;
+
+// Flags da subtabela DTZ: valores em meios-lances exatos para vitória e derrota
+const DTZ_FLAGS: u8 = 4 | 8;
+
+const WIN: i8 = 2;
+const DRAW: i8 = 0;
+const LOSS: i8 = -2;
+const UNKNOWN: i8 = i8::MIN;
+
+/// Casas do a1-d1-d4, para a peça que decide a simetria (tabelas sem peões).
+#[rustfmt::skip]
+const TRIANGLE: [u64; 64] = [
+    6, 0, 1, 2, 2, 1, 0, 6,
+    0, 7, 3, 4, 4, 3, 7, 0,
+    1, 3, 8, 5, 5, 8, 3, 1,
+    2, 4, 5, 9, 9, 5, 4, 2,
+    2, 4, 5, 9, 9, 5, 4, 2,
+    1, 3, 8, 5, 5, 8, 3, 1,
+    0, 7, 3, 4, 4, 3, 7, 0,
+    6, 0, 1, 2, 2, 1, 0, 6,
+];
+
+/// Casas abaixo da diagonal a1-h8, numeradas de 0 a 27.
+#[rustfmt::skip]
+const LOWER: [u64; 64] = [
+    28,  0,  1,  2,  3,  4,  5,  6,
+     0, 29,  7,  8,  9, 10, 11, 12,
+     1,  7, 30, 13, 14, 15, 16, 17,
+     2,  8, 13, 31, 18, 19, 20, 21,
+     3,  9, 14, 18, 32, 22, 23, 24,
+     4, 10, 15, 19, 22, 33, 25, 26,
+     5, 11, 16, 20, 23, 25, 34, 27,
+     6, 12, 17, 21, 24, 26, 27, 35,
+];
+
+/// Todas as posições de uma tabela, com as peças na ordem do cabeçalho: rei
+/// branco, peça branca e rei preto.
+const POSITIONS: usize = 2 * 64 * 64 * 64;
+
+fn index(turn: Color, squares: [u32; 3]) -> usize {
+    let turn = if turn == Color::White { 0 } else { 1 };
+    ((turn * 64 + squares[0] as usize) * 64 + squares[1] as usize) * 64 + squares[2] as usize
+}
+
+fn squares_of(index: usize) -> (Color, [u32; 3]) {
+    let turn = if index / (64 * 64 * 64) == 0 { Color::White } else { Color::Black };
+    (turn, [(index / 4096 % 64) as u32, (index / 64 % 64) as u32, (index % 64) as u32])
+}
+
+fn position(role: Role, turn: Color, squares: [u32; 3]) -> Option<Chess> {
+    if squares[0] == squares[1] || squares[0] == squares[2] || squares[1] == squares[2] {
+        return None;
+    }
+    let mut setup = Setup::empty();
+    setup.board.set_piece_at(Square::new(squares[0]), Color::White.king());
+    setup.board.set_piece_at(Square::new(squares[1]), Piece { color: Color::White, role });
+    setup.board.set_piece_at(Square::new(squares[2]), Color::Black.king());
+    setup.turn = turn;
+    Chess::from_setup(setup, CastlingMode::Standard).ok()
+}
+
+/// Índice de uma posição qualquer com o material de `role`, ou `None` se só
+/// sobraram os reis.
+fn index_of(position: &Chess) -> Option<(Role, usize)> {
+    let board = position.board();
+    let piece = (board.occupied() & !board.kings()).first()?;
+    let role = board.role_at(piece)?;
+    let white_king = board.king_of(Color::White)?;
+    let black_king = board.king_of(Color::Black)?;
+    let squares = [u32::from(white_king), u32::from(piece), u32::from(black_king)];
+    Some((role, index(position.turn(), squares)))
+}
+
+enum Edge {
+    /// Posição da mesma tabela; `zeroing` para lances de peão
+    Same { index: usize, zeroing: bool },
+    /// Captura ou promoção: o resultado já é conhecido
+    Known(i8),
+}
+
+struct Solution {
+    wdl: Vec<i8>,
+    dtz: Vec<i32>,
+}
+
+/// Resolve a tabela de `role` por análise retrógrada. Promoções consultam as
+/// tabelas já resolvidas em `solved`.
+fn solve(role: Role, solved: &HashMap<Role, Solution>) -> Solution {
+    let mut wdl = vec![UNKNOWN; POSITIONS];
+    let mut edges: Vec<Vec<Edge>> = (0..POSITIONS).map(|_| Vec::new()).collect();
+    let mut mated = vec![false; POSITIONS];
+    let mut legal = Vec::new();
+
+    for (i, node) in edges.iter_mut().enumerate() {
+        let (turn, squares) = squares_of(i);
+        let Some(position) = self::position(role, turn, squares) else {
+            continue;
+        };
+        legal.push(i);
+        let moves = position.legal_moves();
+        if moves.is_empty() {
+            mated[i] = position.is_checkmate();
+            wdl[i] = if mated[i] { LOSS } else { DRAW };
+            continue;
+        }
+        for m in moves {
+            let after = position.clone().play(m).unwrap();
+            node.push(match index_of(&after) {
+                None => Edge::Known(DRAW),
+                Some((child_role, index)) if child_role == role => Edge::Same { index, zeroing: m.is_zeroing() },
+                Some((child_role, index)) => Edge::Known(solved[&child_role].wdl[index]),
+            });
+        }
+    }
+
+    let value = |wdl: &[i8], edge: &Edge| match *edge {
+        Edge::Same { index, .. } => wdl[index],
+        Edge::Known(value) => value,
+    };
+
+    // WDL: vence quem tem um lance para uma derrota do adversário, perde quem
+    // só tem lances para vitórias dele; o que sobrar é empate
+    loop {
+        let mut changed = false;
+        for &i in &legal {
+            if wdl[i] != UNKNOWN {
+                continue;
+            }
+            let children: Vec<i8> = edges[i].iter().map(|edge| value(&wdl, edge)).collect();
+            if children.contains(&LOSS) {
+                wdl[i] = WIN;
+                changed = true;
+            } else if children.iter().all(|&child| child == WIN) {
+                wdl[i] = LOSS;
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+    for &i in &legal {
+        if wdl[i] == UNKNOWN {
+            wdl[i] = DRAW;
+        }
+    }
+
+    // DTZ, por distância crescente
+    let mut dtz = vec![0; POSITIONS];
+    for &i in &legal {
+        let quiet = || {
+            edges[i].iter().filter_map(|edge| match *edge {
+                Edge::Same { index, zeroing: false } => Some(index),
+                _ => None,
+            })
+        };
+        dtz[i] = match wdl[i] {
+            WIN if edges[i].iter().any(|edge| match *edge {
+                Edge::Same { index, zeroing } => wdl[index] == LOSS && (zeroing || mated[index]),
+                Edge::Known(value) => value == LOSS,
+            }) =>
+            {
+                1
+            }
+            LOSS if quiet().next().is_none() => -1,
+            _ => 0,
+        };
+    }
+    for level in 2.. {
+        let mut found = Vec::new();
+        for &i in &legal {
+            if dtz[i] != 0 || wdl[i] == DRAW {
+                continue;
+            }
+            let quiet: Vec<i32> = edges[i]
+                .iter()
+                .filter_map(|edge| match *edge {
+                    Edge::Same { index, zeroing: false } => Some(dtz[index]),
+                    _ => None,
+                })
+                .collect();
+            if wdl[i] == WIN && quiet.contains(&(1 - level)) {
+                found.push((i, level));
+            } else if wdl[i] == LOSS && quiet.iter().all(|&child| child > 0) {
+                found.push((i, -(quiet.iter().max().unwrap() + 1)));
+            }
+        }
+        if found.is_empty() {
+            break;
+        }
+        for (i, value) in found {
+            dtz[i] = value;
+        }
+    }
+    assert!(legal.iter().all(|&i| wdl[i] == DRAW || dtz[i] != 0), "DTZ incompleta");
+
+    Solution { wdl, dtz }
+}
+
+/// Subtabela (`file`, lado) e índice da posição dentro dela, como calculados
+/// pelo shakmaty-syzygy para posições em que as brancas têm a peça.
+fn encode(role: Role, turn: Color, squares: [u32; 3]) -> (usize, usize, u64) {
+    let side = if turn == Color::White { 0 } else { 1 };
+    let mut squares = squares.map(u64::from);
+    let flip_horizontal = |squares: &mut [u64; 3]| squares.iter_mut().for_each(|square| *square ^= 7);
+    let file = |square: u64| square & 7;
+    let rank = |square: u64| square >> 3;
+
+    if role == Role::Pawn {
+        // O peão vai na frente, seguido do rei branco e do rei preto
+        let mut squares = [squares[1], squares[0], squares[2]];
+        if file(squares[0]) >= 4 {
+            flip_horizontal(&mut squares);
+        }
+        let lead = file(squares[0]) as usize;
+        let white_king = squares[1] - u64::from(squares[0] < squares[1]);
+        let black_king = squares[2] - u64::from(squares[0] < squares[2]) - u64::from(squares[1] < squares[2]);
+        return (lead, side, rank(squares[0]) - 1 + white_king * 6 + black_king * 6 * 63);
+    }
+
+    if file(squares[0]) >= 4 {
+        flip_horizontal(&mut squares);
+    }
+    if rank(squares[0]) >= 4 {
+        squares.iter_mut().for_each(|square| *square ^= 56);
+    }
+    for i in 0..3 {
+        if file(squares[i]) == rank(squares[i]) {
+            continue;
+        }
+        if rank(squares[i]) > file(squares[i]) {
+            for square in &mut squares[i..] {
+                *square = (*square >> 3) | ((*square & 7) << 3);
+            }
+        }
+        break;
+    }
+
+    let offdiag = |square: u64| file(square) != rank(square);
+    let adjust1 = u64::from(squares[1] > squares[0]);
+    let adjust2 = u64::from(squares[2] > squares[0]) + u64::from(squares[2] > squares[1]);
+    let index = if offdiag(squares[0]) {
+        TRIANGLE[squares[0] as usize] * 63 * 62 + (squares[1] - adjust1) * 62 + (squares[2] - adjust2)
+    } else if offdiag(squares[1]) {
+        6 * 63 * 62 + rank(squares[0]) * 28 * 62 + LOWER[squares[1] as usize] * 62 + squares[2] - adjust2
+    } else if offdiag(squares[2]) {
+        6 * 63 * 62
+            + 4 * 28 * 62
+            + rank(squares[0]) * 7 * 28
+            + (rank(squares[1]) - adjust1) * 28
+            + LOWER[squares[2] as usize]
+    } else {
+        6 * 63 * 62
+            + 4 * 28 * 62
+            + 4 * 7 * 28
+            + rank(squares[0]) * 7 * 6
+            + (rank(squares[1]) - adjust1) * 6
+            + (rank(squares[2]) - adjust2)
+    };
+    (0, side, index)
+}
+
+/// Uma subtabela já comprimida: cabeçalho, índice esparso, tamanho dos
+/// blocos e dados.
+struct Encoded {
+    header: Vec<u8>,
+    sparse_index: Vec<u8>,
+    block_lengths: Vec<u8>,
+    data: Vec<u8>,
+}
+
+/// Comprime `values` com um símbolo de tamanho fixo por valor.
+fn compress(values: &[u8], flags: u8) -> Encoded {
+    let mut symbols: Vec<u8> = values.to_vec();
+    symbols.sort_unstable();
+    symbols.dedup();
+    let bits = (usize::BITS - (symbols.len() - 1).leading_zeros()).max(1) as usize;
+
+    let block_size = 1usize << BLOCK_BITS;
+    let per_block = block_size * 8 / bits;
+    let blocks = values.len().div_ceil(per_block);
+
+    let mut header = vec![flags, BLOCK_BITS, SPAN_BITS, 0];
+    header.extend((blocks as u32).to_le_bytes());
+    header.extend([bits as u8, bits as u8]);
+    header.extend(0u16.to_le_bytes());
+    header.extend((symbols.len() as u16).to_le_bytes());
+    for &value in &symbols {
+        // Símbolo sem filhos: o valor em 12 bits e 0xfff à direita
+        header.extend([value, 0xf0, 0xff]);
+    }
+    if symbols.len() % 2 == 1 {
+        header.push(0);
+    }
+
+    let span = 1usize << SPAN_BITS;
+    let mut sparse_index = Vec::new();
+    for entry in 0..values.len().div_ceil(span) {
+        let middle = entry * span + span / 2;
+        let block = (middle / per_block).min(blocks - 1);
+        sparse_index.extend((block as u32).to_le_bytes());
+        sparse_index.extend(u16::try_from(middle - block * per_block).unwrap().to_le_bytes());
+    }
+
+    let mut block_lengths = Vec::new();
+    let mut data = vec![0u8; blocks * block_size];
+    for (block, chunk) in values.chunks(per_block).enumerate() {
+        block_lengths.extend((chunk.len() as u16 - 1).to_le_bytes());
+        for (i, value) in chunk.iter().enumerate() {
+            let code = symbols.binary_search(value).unwrap();
+            for bit in 0..bits {
+                if code >> (bits - 1 - bit) & 1 == 1 {
+                    let position = block * block_size * 8 + i * bits + bit;
+                    data[position / 8] |= 0x80 >> (position % 8);
+                }
+            }
+        }
+    }
+
+    Encoded { header, sparse_index, block_lengths, data }
+}
+
+/// Arquivo completo: `subtables[file][lado]`.
+fn write_table(path: &Path, magic: [u8; 4], role: Role, subtables: &[Vec<Encoded>]) {
+    let nibble = |role: Role| match role {
+        Role::Pawn => 1,
+        Role::Knight => 2,
+        Role::Bishop => 3,
+        Role::Rook => 4,
+        Role::Queen => 5,
+        Role::King => 6,
+    };
+    let pieces = if role == Role::Pawn {
+        [nibble(Role::Pawn), nibble(Role::King), nibble(Role::King) | 8]
+    } else {
+        [nibble(Role::King), nibble(role), nibble(Role::King) | 8]
+    };
+
+    let mut out = magic.to_vec();
+    out.push(if role == Role::Pawn { 3 } else { 1 });
+    for _ in subtables {
+        out.push(0);
+        out.extend(pieces.map(|piece| piece | piece << 4));
+    }
+    out.resize(out.len().next_multiple_of(2), 0);
+    for encoded in subtables.iter().flatten() {
+        out.extend(&encoded.header);
+    }
+    if magic == DTZ_MAGIC {
+        out.resize(out.len().next_multiple_of(2), 0);
+    }
+    for encoded in subtables.iter().flatten() {
+        out.extend(&encoded.sparse_index);
+    }
+    for encoded in subtables.iter().flatten() {
+        out.extend(&encoded.block_lengths);
+    }
+    for encoded in subtables.iter().flatten() {
+        out.resize(out.len().next_multiple_of(64), 0);
+        out.extend(&encoded.data);
+    }
+    // As tabelas terminam com 16 bytes depois do último bloco alinhado
+    out.resize(out.len().next_multiple_of(64) + 16, 0);
+    fs::write(path, out).unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+}
+
+/// Grava WDL e DTZ da tabela de `role` em `dir`.
+fn write_files(dir: &Path, name: &str, role: Role, solution: &Solution) {
+    let (files, size) = if role == Role::Pawn { (4, 6 * 63 * 62) } else { (1, 31332) };
+    // Posições impossíveis ficam com empate (WDL 2) e DTZ 0
+    let mut wdl = vec![vec![vec![None; size]; 2]; files];
+    let mut dtz = vec![vec![None; size]; files];
+
+    for i in 0..POSITIONS {
+        if solution.wdl[i] == UNKNOWN {
+            continue;
+        }
+        let (turn, squares) = squares_of(i);
+        let (file, side, index) = encode(role, turn, squares);
+        let store = |slot: &mut Option<u8>, value: u8| {
+            assert!(slot.is_none_or(|old| old == value), "{}: índice {} com dois valores", name, index);
+            *slot = Some(value);
+        };
+        store(&mut wdl[file][side][index as usize], (solution.wdl[i] + 2) as u8);
+        // A DTZ guarda só as brancas a mover; as pretas saem de uma busca de 1 lance
+        if side == 0 {
+            let plies = solution.dtz[i].unsigned_abs().saturating_sub(1);
+            store(&mut dtz[file][index as usize], u8::try_from(plies).unwrap());
+        }
+    }
+
+    let filled = |values: &[Option<u8>], default: u8| values.iter().map(|v| v.unwrap_or(default)).collect::<Vec<_>>();
+    let wdl: Vec<Vec<Encoded>> =
+        wdl.iter().map(|sides| sides.iter().map(|values| compress(&filled(values, 2), 0)).collect()).collect();
+    let dtz: Vec<Vec<Encoded>> = dtz.iter().map(|values| vec![compress(&filled(values, 0), DTZ_FLAGS)]).collect();
+    write_table(&dir.join(format!("{}.rtbw", name)), WDL_MAGIC, role, &wdl);
+    write_table(&dir.join(format!("{}.rtbz", name)), DTZ_MAGIC, role, &dtz);
+}
+
+/// Relê as tabelas e compara WDL e DTZ de todas as posições, também com as
+/// cores trocadas.
+fn check(dir: &Path, name: &str, role: Role, solution: &Solution) {
+    let mut tablebase = Tablebase::new();
+    tablebase.add_directory(dir).unwrap();
+    let mut positions = 0;
+    for i in 0..POSITIONS {
+        if solution.wdl[i] == UNKNOWN {
+            continue;
+        }
+        let (turn, squares) = squares_of(i);
+        let original = position(role, turn, squares).unwrap();
+        let mut setup = original.to_setup(EnPassantMode::Legal);
+        setup.board.mirror();
+        setup.turn = !setup.turn;
+        let mirrored = Chess::from_setup(setup, CastlingMode::Standard).unwrap();
+
+        let expected = match solution.wdl[i] {
+            WIN => Wdl::Win,
+            LOSS => Wdl::Loss,
+            _ => Wdl::Draw,
+        };
+        for position in [&original, &mirrored] {
+            let wdl = tablebase.probe_wdl_after_zeroing(position).unwrap();
+            let dtz = tablebase.probe_dtz(position).unwrap().ignore_rounding().0;
+            assert_eq!((wdl, dtz), (expected, solution.dtz[i]), "{}: {:?}", name, position.board());
+        }
+        positions += 1;
+    }
+    let longest = solution.dtz.iter().max().unwrap();
+    println!("{}: {} posições conferidas, maior DTZ {}", name, positions, longest);
+}
+
+fn main() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/syzygy");
+    let mut solved = HashMap::new();
+    for (name, role) in [
+        ("KQvK", Role::Queen),
+        ("KRvK", Role::Rook),
+        ("KBvK", Role::Bishop),
+        ("KNvK", Role::Knight),
+        ("KPvK", Role::Pawn),
+    ] {
+        let solution = solve(role, &solved);
+        write_files(&dir, name, role, &solution);
+        solved.insert(role, solution);
+    }
+    for (name, role) in [
+        ("KQvK", Role::Queen),
+        ("KRvK", Role::Rook),
+        ("KBvK", Role::Bishop),
+        ("KNvK", Role::Knight),
+        ("KPvK", Role::Pawn),
+    ] {
+        check(&dir, name, role, &solved[&role]);
+    }
+}
@@ -0,0 +1,342 @@
+// Análise de partidas: a busca avalia cada posição da linha principal e cada
+// lance recebe a perda em centipeões em relação ao melhor lance. Imprecisões,
+// erros e erros graves ganham o NAG correspondente e a melhor linha da engine
+// como variante; todos os lances ganham a avaliação como comentário.
+//
+// As avaliações são limitadas a ±MAX_CP antes de calcular a perda, para que
+// deixar de dar um mate numa posição ganha não pese mais que uma peça. A
+// precisão segue a fórmula do Lichess: a pontuação vira chance de vitória e a
+// queda dessa chance vira uma nota de 0 a 100 por lance.
+
+use chess::{Board, ChessMove, Color};
+use std::fs;
+use std::io::{self, Write};
+
+use chazz_core::engine::Limits;
+use chazz_core::notation::to_san;
+use chazz_core::position::Position;
+
+use crate::cli::{parse_search_args, SearchArgs};
+use crate::eval::{evaluate_board, game_status, GameStatus};
+use crate::options::Options;
+use crate::pgn::{parse_games, write_game, Game, Node};
+use crate::search::search_game;
+use crate::syzygy::TB_WIN;
+
+pub const MAX_CP: i32 = 1000;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Judgement {
+    Inaccuracy,
+    Mistake,
+    Blunder,
+}
+
+impl Judgement {
+    /// Classificação pela perda em centipeões: 50, 100 e 300.
+    pub fn from_loss(loss: i32) -> Option<Judgement> {
+        match loss {
+            300.. => Some(Judgement::Blunder),
+            100.. => Some(Judgement::Mistake),
+            50.. => Some(Judgement::Inaccuracy),
+            _ => None,
+        }
+    }
+
+    /// `?!`, `?` e `??`.
+    fn nag(self) -> u8 {
+        match self {
+            Judgement::Inaccuracy => 6,
+            Judgement::Mistake => 2,
+            Judgement::Blunder => 4,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Judgement::Inaccuracy => "Imprecisão",
+            Judgement::Mistake => "Erro",
+            Judgement::Blunder => "Erro grave",
+        }
+    }
+}
+
+/// Chance de vitória (0 a 100) de quem tem a pontuação `cp`.
+pub fn win_percent(cp: i32) -> f64 {
+    50.0 + 50.0 * (2.0 / (1.0 + (-0.00368208 * cp as f64).exp()) - 1.0)
+}
+
+/// Nota do lance pela queda da chance de vitória de quem jogou.
+fn move_accuracy(before: i32, after: i32) -> f64 {
+    let drop = (win_percent(before) - win_percent(after)).max(0.0);
+    (103.1668 * (-0.04354 * drop).exp() - 3.1669).clamp(0.0, 100.0)
+}
+
+/// Avaliação das brancas em peões, ou `+#`/`-#` para mates e tablebases.
+fn format_score(white: i32) -> String {
+    if white.abs() >= TB_WIN {
+        return if white > 0 { "+#".to_string() } else { "-#".to_string() };
+    }
+    format!("{:+.2}", white as f64 / 100.0)
+}
+
+/// Avaliação de uma posição do ponto de vista de quem joga e a melhor linha.
+struct Analysis {
+    score: i32,
+    pv: Vec<ChessMove>,
+}
+
+/// `position.history` deixa a busca ver as repetições da própria partida.
+fn analyse_position(position: &Position, options: &Options, limits: &Limits) -> Analysis {
+    let board = &position.board;
+    // Fim de partida: a busca não tem lance, a avaliação estática diz o resultado
+    if game_status(board) != GameStatus::Ongoing {
+        return Analysis { score: evaluate_board(board, &options.params), pv: Vec::new() };
+    }
+    let result = search_game(board, &position.history, options, limits);
+    let pv = if result.pv.is_empty() { result.best_move.into_iter().collect() } else { result.pv };
+    Analysis { score: result.score, pv }
+}
+
+/// Um lance analisado, com as pontuações do ponto de vista de quem jogou.
+#[derive(Debug)]
+pub struct Review {
+    pub best: Option<ChessMove>,
+    pub pv: Vec<ChessMove>,
+    pub before: i32,
+    pub after: i32,
+    pub loss: i32,
+    pub judgement: Option<Judgement>,
+}
+
+/// Análise de cada lance da linha principal.
+pub fn review(game: &Game, options: &Options, limits: &Limits) -> Vec<Review> {
+    let nodes: Vec<&Node> = game.mainline().collect();
+    // Cada posição com o histórico desde o último lance irreversível
+    let mut positions = Vec::new();
+    if let Some(first) = nodes.first() {
+        let mut position = Position::new(first.board, 0);
+        for node in &nodes {
+            positions.push(position.clone());
+            position.push(node.chess_move);
+        }
+        positions.push(position);
+    }
+    let analyses: Vec<Analysis> =
+        positions.iter().map(|position| analyse_position(position, options, limits)).collect();
+
+    nodes
+        .iter()
+        .enumerate()
+        .map(|(i, node)| {
+            let best = analyses[i].pv.first().copied();
+            let before = analyses[i].score;
+            // O melhor lance não perde nada, mesmo que a busca do filho discorde
+            let after = if best == Some(node.chess_move) { before } else { -analyses[i + 1].score };
+            let loss = (before.clamp(-MAX_CP, MAX_CP) - after.clamp(-MAX_CP, MAX_CP)).max(0);
+            Review { best, pv: analyses[i].pv.clone(), before, after, loss, judgement: Judgement::from_loss(loss) }
+        })
+        .collect()
+}
+
+/// Números de um jogador numa partida.
+#[derive(Default)]
+pub struct Stats {
+    pub moves: u32,
+    pub total_loss: i64,
+    pub total_accuracy: f64,
+    /// Imprecisões, erros e erros graves
+    pub judgements: [u32; 3],
+}
+
+impl Stats {
+    fn add(&mut self, review: &Review) {
+        self.moves += 1;
+        self.total_loss += review.loss as i64;
+        self.total_accuracy += move_accuracy(review.before.clamp(-MAX_CP, MAX_CP), review.after.clamp(-MAX_CP, MAX_CP));
+        if let Some(judgement) = review.judgement {
+            self.judgements[judgement as usize] += 1;
+        }
+    }
+
+    pub fn average_loss(&self) -> f64 {
+        self.total_loss as f64 / self.moves.max(1) as f64
+    }
+
+    pub fn accuracy(&self) -> f64 {
+        if self.moves == 0 {
+            return 100.0;
+        }
+        self.total_accuracy / self.moves as f64
+    }
+}
+
+/// Variante com a melhor linha a partir de `board`, comentada com a avaliação.
+fn variation(board: Board, pv: &[ChessMove], comment: String) -> Option<Node> {
+    let mut boards = vec![board];
+    for &chess_move in pv {
+        boards.push(boards.last().unwrap().make_move_new(chess_move));
+    }
+    let mut children = Vec::new();
+    for (i, &chess_move) in pv.iter().enumerate().rev() {
+        let mut node = Node::new(boards[i], chess_move);
+        node.children = children;
+        children = vec![node];
+    }
+    let mut first = children.pop()?;
+    first.comment = Some(comment);
+    Some(first)
+}
+
+fn append_comment(node: &mut Node, comment: String) {
+    node.comment = Some(match node.comment.take() {
+        Some(existing) => format!("{} {}", existing, comment),
+        None => comment,
+    });
+}
+
+/// Anota a partida com as análises e devolve os números das brancas e das pretas.
+pub fn annotate(game: &mut Game, reviews: &[Review]) -> [Stats; 2] {
+    let mut stats = [Stats::default(), Stats::default()];
+    let mut children = &mut game.children;
+    for review in reviews {
+        let node = &mut children[0];
+        let mover = node.board.side_to_move();
+        let white = |score: i32| if mover == Color::White { score } else { -score };
+        stats[mover.to_index()].add(review);
+
+        let mut comment = format_score(white(review.after));
+        if let Some(judgement) = review.judgement {
+            node.nags.push(judgement.nag());
+            if let Some(best) = review.best {
+                comment.push_str(&format!(" {}. Melhor era {}", judgement.name(), to_san(&node.board, best)));
+            }
+        }
+        append_comment(node, comment);
+
+        let line = review.judgement.and_then(|_| variation(node.board, &review.pv, format_score(white(review.before))));
+        children.extend(line);
+        children = &mut children[0].children;
+    }
+    stats
+}
+
+fn print_stats(game: &Game, stats: &[Stats; 2]) {
+    let names = [game.tag("White").unwrap_or("Brancas"), game.tag("Black").unwrap_or("Pretas")];
+    for (name, stats) in names.iter().zip(stats) {
+        let [inaccuracies, mistakes, blunders] = stats.judgements;
+        eprintln!(
+            "  {}: precisão {:.1}%, perda média {:.0} cp, {} imprecisões, {} erros, {} erros graves",
+            name,
+            stats.accuracy(),
+            stats.average_loss(),
+            inaccuracies,
+            mistakes,
+            blunders
+        );
+    }
+}
+
+/// `analyse <pgn>... [--time <ms>] [--depth <n>] [--output <arquivo>]
+/// [--eval-file <arquivo>] [--nnue-file <arquivo>] [--syzygy-path <diretórios>]`:
+/// PGN anotado em stdout (ou no arquivo) e os números de cada jogador em stderr.
+pub fn run(args: &[String]) {
+    let SearchArgs { inputs, limits, output, .. } = match parse_search_args(args, &[]) {
+        Ok(parsed) => parsed,
+        Err(e) => return eprintln!("Erro: {}", e),
+    };
+    if inputs.is_empty() {
+        return eprintln!("Uso: analyse <pgn>... [--time <ms>] [--depth <n>] [--output <arquivo>] [--eval-file <arquivo>] [--nnue-file <arquivo>] [--syzygy-path <diretórios>]");
+    }
+    // Na análise um empate vale zero para os dois lados
+    let mut options = match Options::from_args(args) {
+        Ok(options) => options,
+        Err(e) => return eprintln!("Erro: {}", e),
+    };
+    options.params.contempt = 0;
+
+    let mut text = String::new();
+    let mut read = 0;
+    for path in &inputs {
+        let contents = match fs::read(path) {
+            Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+            Err(e) => return eprintln!("Erro: {}: {}", path, e),
+        };
+        for game in parse_games(&contents) {
+            read += 1;
+            let mut game = match game {
+                Ok(game) => game,
+                Err(e) => {
+                    eprintln!("Aviso: {}: partida {} ignorada: {}", path, read, e);
+                    continue;
+                }
+            };
+            let reviews = review(&game, &options, &limits);
+            let stats = annotate(&mut game, &reviews);
+            eprintln!("Partida {}: {} lances", read, reviews.len());
+            print_stats(&game, &stats);
+            text.push_str(&write_game(&game));
+        }
+    }
+
+    match output {
+        Some(path) => match fs::write(&path, &text) {
+            Ok(()) => eprintln!("Análise salva em {}", path),
+            Err(e) => eprintln!("Erro: {}: {}", path, e),
+        },
+        None => {
+            print!("{}", text);
+            io::stdout().flush().unwrap();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn judgements_and_accuracy() {
+        assert_eq!(Judgement::from_loss(20), None);
+        assert_eq!(Judgement::from_loss(60), Some(Judgement::Inaccuracy));
+        assert_eq!(Judgement::from_loss(150), Some(Judgement::Mistake));
+        assert_eq!(Judgement::from_loss(900), Some(Judgement::Blunder));
+        assert!((move_accuracy(30, 30) - 100.0).abs() < 0.01);
+        assert!(move_accuracy(0, -300) < move_accuracy(0, -50));
+        assert_eq!(format_score(-35), "-0.35");
+        assert_eq!(format_score(30000), "+#");
+    }
+
+    #[test]
+    fn finds_the_blunder_that_allows_mate() {
+        let text = "[White \"A\"]\n[Black \"B\"]\n\n1. e4 e5 2. Qh5 Nc6 3. Bc4 Nf6 4. Qxf7# 1-0";
+        let mut game = parse_games(text).remove(0).unwrap();
+        let limits = Limits { depth: Some(2), ..Default::default() };
+        let reviews = review(&game, &Options::default(), &limits);
+        assert_eq!(reviews.len(), 7);
+        assert_eq!(reviews[5].judgement, Some(Judgement::Blunder));
+        // O mate em si é o melhor lance
+        assert_eq!(reviews[6].loss, 0);
+
+        let stats = annotate(&mut game, &reviews);
+        assert!(stats[1].judgements[2] >= 1);
+        assert!(stats[0].accuracy() > stats[1].accuracy());
+        let nf6 = game.mainline().nth(5).unwrap();
+        assert!(nf6.nags.contains(&4));
+        let written = write_game(&game);
+        assert!(written.contains("Nf6 $4"), "{}", written);
+        assert!(written.contains("Erro grave"), "{}", written);
+    }
+
+    #[test]
+    fn sees_repetitions_of_the_game() {
+        // Brancas com um cavalo contra a dama: repetir a posição é o melhor resultado
+        let text = "[FEN \"3qk3/8/8/8/8/8/8/4K1N1 w - - 0 1\"]\n\n1. Nf3 Kf8 2. Ng1 Ke8 3. Nh3 *";
+        let game = parse_games(text).remove(0).unwrap();
+        let limits = Limits { depth: Some(3), ..Default::default() };
+        let reviews = review(&game, &Options::default(), &limits);
+        assert_eq!(reviews[4].best, Some(ChessMove::from_str("g1f3").unwrap()));
+        assert!(reviews[4].loss > 0);
+    }
+}
@@ -0,0 +1,90 @@
+// Bench: busca de profundidade fixa nas posições do bench comum
+// (`chazz_core::bench`), direto na busca e sem livro. O total de nós é
+// determinístico para uma mesma versão, então serve de assinatura: se mudou,
+// o comportamento da busca mudou. O tempo e os nós por segundo mostram se
+// ela ficou mais rápida.
+//
+// A assinatura da profundidade padrão fica em SIGNATURE e só é conferida num
+// teste ignorado (rode com `--release`); a da profundidade 2 entra nos testes
+// normais. `cargo bench -p engine_sixth` tem os benchmarks do Criterion.
+
+use chess::Board;
+use std::io::{self, Write};
+use std::str::FromStr;
+use std::time::Instant;
+
+use chazz_core::bench::POSITIONS;
+use chazz_core::engine::Limits;
+
+use crate::options::Options;
+use crate::search::search;
+
+/// Profundidade usada quando `bench` vem sem argumento.
+pub const DEFAULT_DEPTH: u32 = 3;
+
+/// Assinatura publicada: total de nós de `bench` na profundidade padrão.
+pub const SIGNATURE: u64 = 166992;
+
+/// Busca cada posição até `depth`, sem livro de aberturas, e imprime os nós
+/// de cada uma e o total. Devolve o total de nós.
+pub fn bench(options: &Options, depth: u32) -> u64 {
+    let limits = Limits { depth: Some(depth), ..Default::default() };
+    let start = Instant::now();
+    let mut nodes = 0;
+
+    for (i, fen) in POSITIONS.iter().enumerate() {
+        let board = Board::from_str(fen).expect("posição do bench inválida");
+        let result = search(&board, options, &limits);
+        let best_move = result.best_move.map_or("-".to_string(), |m| m.to_string());
+        println!("Posição {:>2}/{}: {:<6} {:>10} nós", i + 1, POSITIONS.len(), best_move, result.nodes);
+        nodes += result.nodes;
+    }
+
+    let elapsed = start.elapsed().as_millis().max(1) as u64;
+    println!("===========================");
+    println!("Profundidade: {}", depth);
+    println!("Tempo (ms)  : {}", elapsed);
+    println!("Nós         : {}", nodes);
+    println!("Nós/segundo : {}", nodes * 1000 / elapsed);
+    io::stdout().flush().unwrap();
+    nodes
+}
+
+/// Profundidade de `bench [depth]`.
+pub fn parse_depth(arg: Option<&str>) -> Result<u32, String> {
+    match arg {
+        None => Ok(DEFAULT_DEPTH),
+        Some(depth) => depth.parse().ok().filter(|&d| d > 0).ok_or(format!("profundidade inválida: {}", depth)),
+    }
+}
+
+/// `bench [depth] [--eval-file <arquivo>] [--nnue-file <arquivo>] [--syzygy-path <diretórios>]`
+pub fn run(args: &[String]) {
+    let depth = args.first().filter(|arg| !arg.starts_with("--")).map(String::as_str);
+    match parse_depth(depth).and_then(|depth| Ok((depth, Options::from_args(args)?))) {
+        Ok((depth, options)) => {
+            bench(&options, depth);
+        }
+        Err(e) => eprintln!("Erro: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn node_count_matches_the_signature() {
+        // A profundidade 3 (SIGNATURE) é lenta sem otimização; a 2 tem total
+        // próprio. Se a busca ou a avaliação mudou de propósito, atualize os
+        // dois números com `bench` e `bench 2`.
+        assert_eq!(bench(&Options::default(), 2), 34647);
+    }
+
+    // Lento sem --release: `cargo test --release -- --ignored`
+    #[test]
+    #[ignore]
+    fn node_count_matches_the_signature_at_default_depth() {
+        assert_eq!(bench(&Options::default(), DEFAULT_DEPTH), SIGNATURE);
+    }
+}
@@ -0,0 +1,97 @@
+// Livro de aberturas no formato Polyglot (`.bin`).
+
+use chess::{Board, ChessMove};
+use std::fs;
+
+use crate::polyglot::{decode_move, hash, ENTRY_SIZE};
+use crate::rng::Rng;
+
+pub struct Entry {
+    pub key: u64,
+    pub code: u16,
+    pub weight: u16,
+}
+
+pub struct Book {
+    entries: Vec<Entry>,
+}
+
+impl Book {
+    pub fn new(mut entries: Vec<Entry>) -> Book {
+        entries.sort_by_key(|entry| (entry.key, std::cmp::Reverse(entry.weight)));
+        Book { entries }
+    }
+
+    pub fn load(path: &str) -> Result<Book, String> {
+        let bytes = fs::read(path).map_err(|e| format!("{}: {}", path, e))?;
+        if bytes.len() % ENTRY_SIZE != 0 {
+            return Err(format!("{}: não é um livro Polyglot válido", path));
+        }
+
+        let mut entries: Vec<Entry> = bytes
+            .chunks_exact(ENTRY_SIZE)
+            .map(|b| Entry {
+                key: u64::from_be_bytes(b[..8].try_into().unwrap()),
+                code: u16::from_be_bytes([b[8], b[9]]),
+                weight: u16::from_be_bytes([b[10], b[11]]),
+            })
+            .collect();
+        // O formato exige as entradas ordenadas; garante para livros mal gerados
+        entries.sort_by_key(|entry| entry.key);
+        Ok(Book { entries })
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let mut bytes = Vec::with_capacity(self.entries.len() * ENTRY_SIZE);
+        for entry in &self.entries {
+            bytes.extend_from_slice(&entry.key.to_be_bytes());
+            bytes.extend_from_slice(&entry.code.to_be_bytes());
+            bytes.extend_from_slice(&entry.weight.to_be_bytes());
+            bytes.extend_from_slice(&0u32.to_be_bytes());
+        }
+        fs::write(path, bytes).map_err(|e| format!("{}: {}", path, e))
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Lances do livro para `board` com seus pesos, do maior para o menor.
+    pub fn moves(&self, board: &Board) -> Vec<(ChessMove, u16)> {
+        let key = hash(board);
+        let start = self.entries.partition_point(|entry| entry.key < key);
+        let mut moves: Vec<(ChessMove, u16)> = self.entries[start..]
+            .iter()
+            .take_while(|entry| entry.key == key)
+            .filter_map(|entry| decode_move(board, entry.code).map(|m| (m, entry.weight)))
+            .collect();
+        moves.sort_by_key(|&(_, weight)| std::cmp::Reverse(weight));
+        moves
+    }
+
+    /// Lance escolhido para `board`: o de maior peso com `best`, senão
+    /// sorteado com probabilidade proporcional ao peso.
+    pub fn choose(&self, board: &Board, best: bool, rng: &mut Rng) -> Option<ChessMove> {
+        let moves = self.moves(board);
+        if best {
+            return moves.first().map(|&(m, _)| m);
+        }
+
+        let total: u64 = moves.iter().map(|&(_, weight)| weight as u64).sum();
+        if total == 0 {
+            return None;
+        }
+        let mut pick = rng.next_u64() % total;
+        for (chess_move, weight) in moves {
+            if pick < weight as u64 {
+                return Some(chess_move);
+            }
+            pick -= weight as u64;
+        }
+        None
+    }
+}
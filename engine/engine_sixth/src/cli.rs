@@ -0,0 +1,99 @@
+// Linha de comando dos subcomandos que buscam posições de arquivos (`epd`,
+// `analyse`, `puzzles`): arquivos de entrada, limites da busca, arquivo de
+// saída e as opções da engine, que ficam para `Options::from_args`. Valor
+// ausente ou inválido é erro, em vez de cair num padrão sem aviso; `value` e
+// `number` aplicam a mesma regra aos outros subcomandos.
+
+use chazz_core::engine::Limits;
+use std::str::FromStr;
+
+/// Opções da engine aceitas por todos esses subcomandos.
+const ENGINE_OPTIONS: [&str; 3] = ["--eval-file", "--nnue-file", "--syzygy-path"];
+
+#[derive(Debug, Default)]
+pub struct SearchArgs {
+    pub inputs: Vec<String>,
+    pub limits: Limits,
+    pub output: Option<String>,
+    /// Opções próprias do subcomando, entre as de `extra`, com o valor
+    pub extra: Vec<(String, String)>,
+}
+
+impl SearchArgs {
+    /// Valor da opção própria `name`, se foi passada.
+    pub fn extra(&self, name: &str) -> Option<&str> {
+        self.extra.iter().rev().find(|(option, _)| option == name).map(|(_, value)| value.as_str())
+    }
+}
+
+/// `<arquivo>... [--time <ms>] [--depth <n>] [--output <arquivo>]
+/// [--eval-file <arquivo>] [--nnue-file <arquivo>] [--syzygy-path
+/// <diretórios>]`, mais as opções com valor listadas em `extra`. Tempo e
+/// profundidade precisam ser inteiros de pelo menos 1.
+pub fn parse_search_args(args: &[String], extra: &[&str]) -> Result<SearchArgs, String> {
+    let mut parsed = SearchArgs::default();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let mut value = || iter.next().cloned().ok_or(format!("{} precisa de um valor", arg));
+        let positive = |text: String| match text.parse::<u64>() {
+            Ok(number) if number >= 1 => Ok(number),
+            _ => Err(format!("valor inválido para {}: {}", arg, text)),
+        };
+        match arg.as_str() {
+            "--time" => parsed.limits.movetime = Some(positive(value()?)?),
+            "--depth" => parsed.limits.depth = Some(positive(value()?)?.min(u32::MAX as u64) as u32),
+            "--output" => parsed.output = Some(value()?),
+            name if ENGINE_OPTIONS.contains(&name) => {
+                value()?;
+            }
+            name if extra.contains(&name) => parsed.extra.push((name.to_string(), value()?)),
+            other if other.starts_with("--") => return Err(format!("opção desconhecida: {}", other)),
+            path => parsed.inputs.push(path.to_string()),
+        }
+    }
+    Ok(parsed)
+}
+
+/// Próximo argumento, como valor da opção `arg`.
+pub fn value<'a>(args: &mut impl Iterator<Item = &'a String>, arg: &str) -> Result<String, String> {
+    args.next().cloned().ok_or(format!("{} precisa de um valor", arg))
+}
+
+/// Próximo argumento, como número para a opção `arg`.
+pub fn number<'a, T: FromStr>(args: &mut impl Iterator<Item = &'a String>, arg: &str) -> Result<T, String> {
+    let text = value(args, arg)?;
+    text.parse().map_err(|_| format!("valor inválido para {}: {}", arg, text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(line: &str, extra: &[&str]) -> Result<SearchArgs, String> {
+        let args: Vec<String> = line.split_whitespace().map(String::from).collect();
+        parse_search_args(&args, extra)
+    }
+
+    #[test]
+    fn limits_output_and_inputs() {
+        let parsed = parse("a.epd --time 250 b.epd --depth 6 --output r.txt --eval-file p.toml", &[]).unwrap();
+        assert_eq!(parsed.inputs, ["a.epd", "b.epd"]);
+        assert_eq!((parsed.limits.movetime, parsed.limits.depth), (Some(250), Some(6)));
+        assert_eq!(parsed.output.as_deref(), Some("r.txt"));
+
+        let parsed = parse("g.pgn --format json", &["--format"]).unwrap();
+        assert_eq!(parsed.extra("--format"), Some("json"));
+        assert_eq!(parsed.extra("--other"), None);
+    }
+
+    #[test]
+    fn bad_or_missing_values_are_errors() {
+        assert_eq!(parse("a.epd --time abc", &[]).unwrap_err(), "valor inválido para --time: abc");
+        assert_eq!(parse("a.epd --depth", &[]).unwrap_err(), "--depth precisa de um valor");
+        assert!(parse("a.epd --depth 0", &[]).is_err());
+        assert!(parse("a.epd --depth -3", &[]).is_err());
+        assert!(parse("a.epd --output", &[]).is_err());
+        assert!(parse("a.epd --format json", &[]).is_err());
+        assert!(parse("a.epd --eval-file", &[]).is_err());
+    }
+}
@@ -0,0 +1,320 @@
+// Geração de posições rotuladas por autojogo, para treinar a NNUE ou ajustar
+// a avaliação clássica com o `tune`.
+//
+// Cada partida começa com alguns lances aleatórios e segue com buscas de
+// número fixo de nós. Para cada posição tranquila grava-se a FEN, a
+// avaliação da busca (centipeões, ponto de vista das brancas) e o resultado
+// final (1.0, 0.5 ou 0.0 para as brancas). A partida `i` usa a semente
+// `seed + i`, então a saída não depende do número de threads.
+//
+// Formato texto, uma posição por linha (lido diretamente pelo `tune`):
+//   <fen> | <avaliação> | <resultado>
+//
+// Formato binário, 32 bytes por posição (little-endian):
+//   u64      ocupação (bit = índice da casa, a1 = 0)
+//   16 bytes uma peça por nibble, na ordem das casas ocupadas:
+//            0-5 peão..rei brancos, 6-11 peão..rei pretos
+//   u8       bit 0 lado a mover (1 = pretas), bits 1-4 roques KQkq
+//   u8       coluna do en passant (8 = nenhum)
+//   i16      avaliação
+//   u8       resultado (0 = derrota, 1 = empate, 2 = vitória das brancas)
+//   3 bytes  zeros
+
+use chess::{Board, BoardStatus, Color, MoveGen, Piece};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+
+use crate::cli::{number, value};
+use crate::eval::insufficient_material;
+use crate::nnue::Network;
+use crate::options::Options;
+use crate::params::Params;
+use crate::rng::Rng;
+use crate::search::{is_quiet, search_nodes};
+
+struct Config {
+    games: usize,
+    nodes: u64,
+    threads: usize,
+    seed: u64,
+    random_plies: usize,
+    max_plies: usize,
+    binary: bool,
+}
+
+struct Record {
+    board: Board,
+    score: i32,
+}
+
+/// Sorteia `plies` lances legais; repete se a partida terminar no caminho.
+fn random_opening(rng: &mut Rng, plies: usize) -> Board {
+    loop {
+        let mut board = Board::default();
+        for _ in 0..plies {
+            let moves: Vec<_> = MoveGen::new_legal(&board).collect();
+            if moves.is_empty() {
+                break;
+            }
+            board = board.make_move_new(moves[rng.below(moves.len())]);
+        }
+        if board.status() == BoardStatus::Ongoing {
+            return board;
+        }
+    }
+}
+
+/// Joga uma partida e devolve as posições gravadas e o resultado para as brancas.
+fn play_game(seed: u64, config: &Config, options: &Options) -> (Vec<Record>, f32) {
+    let mut rng = Rng::new(seed);
+    let mut board = random_opening(&mut rng, config.random_plies);
+    let mut history: HashMap<u64, u32> = HashMap::new();
+    let mut halfmove_clock = 0;
+    let mut plies = 0;
+    let mut records = Vec::new();
+
+    *history.entry(board.get_hash()).or_insert(0) += 1;
+
+    let result = loop {
+        match board.status() {
+            BoardStatus::Checkmate => {
+                break if board.side_to_move() == Color::White { 0.0 } else { 1.0 };
+            }
+            BoardStatus::Stalemate => break 0.5,
+            BoardStatus::Ongoing => {}
+        }
+        if history[&board.get_hash()] >= 3
+            || halfmove_clock >= 100
+            || insufficient_material(&board)
+            || plies >= config.max_plies
+        {
+            break 0.5;
+        }
+
+        let Some((chess_move, score)) = search_nodes(&board, options, config.nodes) else {
+            break 0.5;
+        };
+        let score = if board.side_to_move() == Color::White { score } else { -score };
+        if score.abs() < 20000 && is_quiet(&board, &options.params) {
+            records.push(Record { board, score });
+        }
+
+        let capture = board.piece_on(chess_move.get_dest()).is_some();
+        let pawn = board.piece_on(chess_move.get_source()) == Some(Piece::Pawn);
+        halfmove_clock = if capture || pawn { 0 } else { halfmove_clock + 1 };
+
+        board = board.make_move_new(chess_move);
+        plies += 1;
+        *history.entry(board.get_hash()).or_insert(0) += 1;
+    };
+
+    (records, result)
+}
+
+fn encode(board: &Board, score: i32, result: f32) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    let occupied = *board.combined();
+    bytes[..8].copy_from_slice(&occupied.0.to_le_bytes());
+
+    for (i, square) in occupied.enumerate() {
+        let piece = board.piece_on(square).unwrap().to_index() as u8;
+        let code = if board.color_on(square) == Some(Color::White) { piece } else { piece + 6 };
+        bytes[8 + i / 2] |= code << (4 * (i % 2));
+    }
+
+    let mut flags = u8::from(board.side_to_move() == Color::Black);
+    for (i, color) in [Color::White, Color::Black].into_iter().enumerate() {
+        let rights = board.castle_rights(color);
+        if rights.has_kingside() {
+            flags |= 1 << (1 + 2 * i);
+        }
+        if rights.has_queenside() {
+            flags |= 1 << (2 + 2 * i);
+        }
+    }
+    bytes[24] = flags;
+    bytes[25] = board.en_passant().map_or(8, |square| square.get_file().to_index() as u8);
+    bytes[26..28].copy_from_slice(&(score.clamp(-32767, 32767) as i16).to_le_bytes());
+    bytes[28] = (result * 2.0) as u8;
+    bytes
+}
+
+fn write_game(out: &mut impl Write, records: &[Record], result: f32, binary: bool) -> std::io::Result<()> {
+    for record in records {
+        if binary {
+            out.write_all(&encode(&record.board, record.score, result))?;
+        } else {
+            writeln!(out, "{} | {} | {:.1}", record.board, record.score, result)?;
+        }
+    }
+    Ok(())
+}
+
+/// Configuração, arquivo de saída e opções da engine; valor ausente ou
+/// inválido é erro.
+fn parse_args(args: &[String]) -> Result<(Config, String, Options), String> {
+    let mut config = Config {
+        games: 100,
+        nodes: 5000,
+        threads: thread::available_parallelism().map_or(1, |n| n.get()),
+        seed: 1,
+        random_plies: 8,
+        max_plies: 400,
+        binary: false,
+    };
+    let mut output = String::from("data.txt");
+    let mut params = Params::default();
+    let mut network = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--games" => config.games = number(&mut args, arg)?,
+            "--nodes" => config.nodes = number(&mut args, arg)?,
+            "--threads" => config.threads = number::<usize>(&mut args, arg)?.max(1),
+            "--seed" => config.seed = number(&mut args, arg)?,
+            "--random-plies" => config.random_plies = number(&mut args, arg)?,
+            "--max-plies" => config.max_plies = number(&mut args, arg)?,
+            "--output" => output = value(&mut args, arg)?,
+            "--binary" => config.binary = true,
+            "--eval-file" => params = Params::load(&value(&mut args, arg)?)?,
+            "--nnue-file" => network = Some(Network::load(&value(&mut args, arg)?)?),
+            other => return Err(format!("opção desconhecida: {}", other)),
+        }
+    }
+
+    let use_nnue = network.is_some();
+    Ok((config, output, Options { params, network, use_nnue, ..Default::default() }))
+}
+
+/// `datagen [--games <n>] [--nodes <n>] [--threads <n>] [--seed <n>]
+/// [--random-plies <n>] [--max-plies <n>] [--output <arquivo>] [--binary]
+/// [--eval-file <arquivo>] [--nnue-file <arquivo>]`
+pub fn run(args: &[String]) {
+    let (config, output, options) = match parse_args(args) {
+        Ok(parsed) => parsed,
+        Err(e) => return eprintln!("Erro: {}", e),
+    };
+
+    let mut out = match File::create(&output) {
+        Ok(file) => BufWriter::new(file),
+        Err(e) => return eprintln!("Erro: {}: {}", output, e),
+    };
+
+    let next_game = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+    let (config, options) = (&config, &options);
+
+    thread::scope(|scope| {
+        for _ in 0..config.threads {
+            let sender = sender.clone();
+            let next_game = &next_game;
+            scope.spawn(move || loop {
+                let game = next_game.fetch_add(1, Ordering::Relaxed);
+                if game >= config.games {
+                    break;
+                }
+                let seed = config.seed.wrapping_add(game as u64);
+                let (records, result) = play_game(seed, config, options);
+                if sender.send((game, records, result)).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(sender);
+
+        // Grava na ordem das partidas, independente de qual thread terminou antes
+        let mut pending = BTreeMap::new();
+        let mut next_to_write = 0;
+        let mut positions = 0;
+        for (game, records, result) in receiver {
+            pending.insert(game, (records, result));
+            while let Some((records, result)) = pending.remove(&next_to_write) {
+                if let Err(e) = write_game(&mut out, &records, result, config.binary) {
+                    eprintln!("Erro: {}: {}", output, e);
+                }
+                positions += records.len();
+                next_to_write += 1;
+                eprintln!("Partida {}/{}: {} posições, resultado {:.1}", next_to_write, config.games, positions, result);
+            }
+        }
+    });
+
+    if let Err(e) = out.flush() {
+        eprintln!("Erro: {}: {}", output, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn config() -> Config {
+        Config { games: 1, nodes: 200, threads: 1, seed: 7, random_plies: 8, max_plies: 16, binary: false }
+    }
+
+    fn text(seed: u64) -> String {
+        let (records, result) = play_game(seed, &config(), &Options::default());
+        let mut out = Vec::new();
+        write_game(&mut out, &records, result, false).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn same_seed_same_games() {
+        let first = text(7);
+        assert!(!first.is_empty());
+        assert_eq!(text(7), first);
+        assert_ne!(text(8), first);
+
+        let mut rng = Rng::new(7);
+        let opening = random_opening(&mut rng, 8);
+        assert_eq!(opening, random_opening(&mut Rng::new(7), 8));
+    }
+
+    #[test]
+    fn text_lines_have_fen_score_and_result() {
+        let text = text(7);
+        let lines: Vec<&str> = text.lines().collect();
+        let result = lines[0].rsplit(" | ").next().unwrap();
+        assert!(["1.0", "0.5", "0.0"].contains(&result), "{}", lines[0]);
+        for line in lines {
+            let fields: Vec<&str> = line.split(" | ").collect();
+            assert_eq!(fields.len(), 3, "{}", line);
+            let board = Board::from_str(fields[0]).unwrap_or_else(|e| panic!("{}: {}", line, e));
+            assert!(is_quiet(&board, &Params::default()), "{}", line);
+            assert!(fields[1].parse::<i32>().unwrap().abs() < 20000, "{}", line);
+            assert_eq!(fields[2], result, "{}", line);
+        }
+    }
+
+    #[test]
+    fn binary_record_layout() {
+        let board = Board::from_str("4k3/8/8/3pP3/8/8/8/R3K3 w Q d6 0 1").unwrap();
+        let bytes = encode(&board, -150, 0.5);
+        assert_eq!(u64::from_le_bytes(bytes[..8].try_into().unwrap()), board.combined().0);
+        // Casas ocupadas em ordem: a1 torre, e1 rei, d5 peão preto, e5 peão, e8 rei preto
+        assert_eq!(&bytes[8..11], &[0x53, 0x06, 0x0B]);
+        assert_eq!(bytes[24], 0b0100);
+        assert_eq!(bytes[25], 3);
+        assert_eq!(i16::from_le_bytes([bytes[26], bytes[27]]), -150);
+        assert_eq!(bytes[28], 1);
+        assert_eq!(&bytes[29..], &[0, 0, 0]);
+    }
+
+    #[test]
+    fn bad_or_missing_values_are_errors() {
+        let parse = |line: &str| parse_args(&line.split_whitespace().map(String::from).collect::<Vec<_>>()).map(|_| ());
+        assert!(parse("--games 4 --nodes 300 --seed 9 --binary").is_ok());
+        assert_eq!(parse("--games dez").unwrap_err(), "valor inválido para --games: dez");
+        assert_eq!(parse("--nodes -5").unwrap_err(), "valor inválido para --nodes: -5");
+        assert_eq!(parse("--max-plies").unwrap_err(), "--max-plies precisa de um valor");
+        assert_eq!(parse("--output").unwrap_err(), "--output precisa de um valor");
+        assert_eq!(parse("--jogos 4").unwrap_err(), "opção desconhecida: --jogos");
+    }
+}
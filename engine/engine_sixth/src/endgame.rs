@@ -0,0 +1,527 @@
+// Conhecimento específico de finais, escolhido pela assinatura de material.
+//
+// Finais com rei sozinho contra material suficiente (KQK, KRK, KBBK, KBNK)
+// recebem uma avaliação própria que empurra o rei fraco para a borda ou para
+// o canto certo; KPK é resolvido por uma bitbase gerada na primeira consulta;
+// finais conhecidos como empatados (bispos de cores opostas, peão da torre
+// com o bispo errado, vantagem pequena sem peões) têm a avaliação reduzida.
+
+use chazz_core::board::Query;
+use chess::{BitBoard, Color, Piece, Square, EMPTY};
+use std::sync::OnceLock;
+
+use crate::params::Params;
+
+// Bônus de vitória conhecida: acima de qualquer avaliação normal e abaixo
+// das vitórias de tablebase e do mate
+pub const KNOWN_WIN: i32 = 10000;
+
+// Fator de escala neutro: a avaliação é multiplicada por fator / 64
+pub const SCALE_NORMAL: i32 = 64;
+
+const LIGHT_SQUARES: BitBoard = BitBoard(0x55AA_55AA_55AA_55AA);
+
+/// Quantidade de cada peça de uma cor.
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Material {
+    pawns: u32,
+    knights: u32,
+    bishops: u32,
+    rooks: u32,
+    queens: u32,
+}
+
+impl Material {
+    fn of(board: &impl Query, color: Color) -> Material {
+        let ours = board.color_combined(color);
+        let count = |piece| (board.pieces(piece) & ours).popcnt();
+        Material {
+            pawns: count(Piece::Pawn),
+            knights: count(Piece::Knight),
+            bishops: count(Piece::Bishop),
+            rooks: count(Piece::Rook),
+            queens: count(Piece::Queen),
+        }
+    }
+
+    fn is_lone_king(&self) -> bool {
+        self.pieces() == 0 && self.pawns == 0
+    }
+
+    fn pieces(&self) -> u32 {
+        self.knights + self.bishops + self.rooks + self.queens
+    }
+
+    /// Material sem peões, em centipeões.
+    fn non_pawn(&self, params: &Params) -> i32 {
+        self.knights as i32 * params.knight_value
+            + self.bishops as i32 * params.bishop_value
+            + self.rooks as i32 * params.rook_value
+            + self.queens as i32 * params.queen_value
+    }
+}
+
+fn file_of(square: Square) -> i32 {
+    square.get_file().to_index() as i32
+}
+
+fn rank_of(square: Square) -> i32 {
+    square.get_rank().to_index() as i32
+}
+
+fn distance(a: Square, b: Square) -> i32 {
+    (file_of(a) - file_of(b)).abs().max((rank_of(a) - rank_of(b)).abs())
+}
+
+/// Bônus por ter o rei fraco longe do centro (0 no centro, 120 no canto).
+fn push_to_edge(square: Square) -> i32 {
+    let center = ((2 * file_of(square) - 7).abs() + (2 * rank_of(square) - 7).abs()) / 2;
+    20 * (center - 1)
+}
+
+/// Bônus por aproximar os reis.
+fn push_close(a: Square, b: Square) -> i32 {
+    140 - 20 * distance(a, b)
+}
+
+fn is_light(square: Square) -> bool {
+    (LIGHT_SQUARES & BitBoard::from_square(square)) != EMPTY
+}
+
+/// Rei e material de mate contra rei sozinho.
+fn mate_lone_king(board: &impl Query, strong: Color, material: &Material, params: &Params) -> i32 {
+    let strong_king = board.king_square(strong);
+    let weak_king = board.king_square(!strong);
+    let mut score = material.non_pawn(params)
+        + material.pawns as i32 * params.pawn_value
+        + push_to_edge(weak_king)
+        + push_close(strong_king, weak_king);
+
+    let bishops = board.pieces(Piece::Bishop) & board.color_combined(strong);
+    if material.pieces() == 2 && material.bishops == 1 && material.knights == 1 {
+        // KBNK: só há mate no canto da cor do bispo
+        let light = is_light(bishops.to_square());
+        let corners = if light { [Square::A8, Square::H1] } else { [Square::A1, Square::H8] };
+        let corner_distance = corners
+            .iter()
+            .map(|&corner| (file_of(corner) - file_of(weak_king)).abs() + (rank_of(corner) - rank_of(weak_king)).abs())
+            .min()
+            .unwrap();
+        score += 200 - 20 * corner_distance;
+    }
+
+    score + KNOWN_WIN
+}
+
+/// Tem material para forçar o mate contra o rei sozinho?
+fn can_force_mate(board: &impl Query, strong: Color, material: &Material) -> bool {
+    let bishops = board.pieces(Piece::Bishop) & board.color_combined(strong);
+    let bishop_pair = (bishops & LIGHT_SQUARES) != EMPTY && (bishops & !LIGHT_SQUARES) != EMPTY;
+    material.queens > 0
+        || material.rooks > 0
+        || bishop_pair
+        || (material.bishops > 0 && material.knights > 0)
+}
+
+/// Avaliação especializada do ponto de vista de quem joga, quando a
+/// assinatura de material tem uma.
+pub fn specialized(board: &impl Query, params: &Params) -> Option<i32> {
+    let white = Material::of(board, Color::White);
+    let black = Material::of(board, Color::Black);
+
+    let (strong, strong_material, weak_material) = if black.is_lone_king() {
+        (Color::White, white, black)
+    } else if white.is_lone_king() {
+        (Color::Black, black, white)
+    } else {
+        return None;
+    };
+    if !weak_material.is_lone_king() {
+        return None;
+    }
+
+    let score = if can_force_mate(board, strong, &strong_material) {
+        mate_lone_king(board, strong, &strong_material, params)
+    } else if strong_material.pieces() == 0 && strong_material.pawns == 1 {
+        kpk(board, strong, params)
+    } else {
+        return None;
+    };
+
+    Some(if board.side_to_move() == strong { score } else { -score })
+}
+
+/// KPK pela bitbase: vitória conhecida ou empate.
+fn kpk(board: &impl Query, strong: Color, params: &Params) -> i32 {
+    let pawn = board.pieces(Piece::Pawn).to_square();
+    let strong_king = board.king_square(strong);
+    let weak_king = board.king_square(!strong);
+    let strong_to_move = board.side_to_move() == strong;
+
+    if !kpk_probe(strong, strong_king, pawn, weak_king, strong_to_move) {
+        return 0;
+    }
+    let rank = if strong == Color::White { rank_of(pawn) } else { 7 - rank_of(pawn) };
+    KNOWN_WIN + params.pawn_value + rank * 10
+}
+
+/// Fator de escala (em 64 avos) para finais com tendência de empate, visto
+/// pelo lado `strong` que está à frente na avaliação.
+pub fn scale_factor(board: &impl Query, strong: Color, params: &Params) -> i32 {
+    let strong_material = Material::of(board, strong);
+    let weak_material = Material::of(board, !strong);
+
+    // Bispos de cores opostas sem outras peças
+    let bishops = board.pieces(Piece::Bishop);
+    if strong_material.pieces() == 1
+        && weak_material.pieces() == 1
+        && strong_material.bishops == 1
+        && weak_material.bishops == 1
+        && (bishops & LIGHT_SQUARES).popcnt() == 1
+    {
+        let pawn_difference = strong_material.pawns as i32 - weak_material.pawns as i32;
+        return if pawn_difference <= 1 { 16 } else { 32 };
+    }
+
+    // Peão da torre com o bispo que não controla a casa de promoção
+    if strong_material.pieces() == 1 && strong_material.bishops == 1 && strong_material.pawns > 0 {
+        if let Some(scale) = wrong_rook_pawn(board, strong) {
+            return scale;
+        }
+    }
+
+    // Sem peões e com pouca vantagem de material, dificilmente se vence
+    if strong_material.pawns == 0 {
+        let difference = strong_material.non_pawn(params) - weak_material.non_pawn(params);
+        if difference <= params.bishop_value {
+            return if strong_material.non_pawn(params) < params.rook_value {
+                0
+            } else if weak_material.non_pawn(params) <= params.bishop_value {
+                4
+            } else {
+                14
+            };
+        }
+    }
+
+    SCALE_NORMAL
+}
+
+/// Todos os peões numa coluna de torre, bispo da cor errada e rei adversário
+/// perto da casa de promoção: empate.
+fn wrong_rook_pawn(board: &impl Query, strong: Color) -> Option<i32> {
+    let pawns = board.pieces(Piece::Pawn) & board.color_combined(strong);
+    let weak_pawns = board.pieces(Piece::Pawn) & board.color_combined(!strong);
+    let files: Vec<i32> = pawns.map(file_of).collect();
+    let file = files[0];
+    if (file != 0 && file != 7) || files.iter().any(|&f| f != file) || weak_pawns != EMPTY {
+        return None;
+    }
+
+    let promotion_rank = if strong == Color::White { 7 } else { 0 };
+    let promotion = Square::make_square(
+        chess::Rank::from_index(promotion_rank),
+        chess::File::from_index(file as usize),
+    );
+    let bishop = (board.pieces(Piece::Bishop) & board.color_combined(strong)).to_square();
+    if is_light(bishop) != is_light(promotion) && distance(board.king_square(!strong), promotion) <= 1 {
+        Some(0)
+    } else {
+        None
+    }
+}
+
+// Bitbase KPK: para cada lado a mover, rei fraco, rei forte e peão (colunas
+// a-d, fileiras 2-7), se o lado forte vence. Gerada por análise retrógrada
+// com o lado forte sempre de brancas.
+
+const KPK_SIZE: usize = 2 * 24 * 64 * 64;
+
+fn kpk_index(white_to_move: bool, black_king: usize, white_king: usize, pawn: usize) -> usize {
+    let pawn_index = (pawn / 8 - 1) * 4 + pawn % 8;
+    ((usize::from(white_to_move) * 24 + pawn_index) * 64 + white_king) * 64 + black_king
+}
+
+fn king_moves(square: usize) -> impl Iterator<Item = usize> {
+    let (file, rank) = ((square % 8) as i32, (square / 8) as i32);
+    (-1..=1)
+        .flat_map(move |df| (-1..=1).map(move |dr| (file + df, rank + dr)))
+        .filter(move |&(f, r)| (f, r) != (file, rank) && (0..8).contains(&f) && (0..8).contains(&r))
+        .map(|(f, r)| (r * 8 + f) as usize)
+}
+
+fn square_distance(a: usize, b: usize) -> i32 {
+    let (fa, ra, fb, rb) = ((a % 8) as i32, (a / 8) as i32, (b % 8) as i32, (b / 8) as i32);
+    (fa - fb).abs().max((ra - rb).abs())
+}
+
+/// Casas atacadas por um peão branco.
+fn pawn_attacks(pawn: usize) -> impl Iterator<Item = usize> {
+    let file = pawn % 8;
+    [(file > 0).then(|| pawn + 7), (file < 7).then(|| pawn + 9)].into_iter().flatten()
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum KpkResult {
+    Invalid,
+    Unknown,
+    Draw,
+    Win,
+}
+
+fn kpk_initial(white_to_move: bool, black_king: usize, white_king: usize, pawn: usize) -> KpkResult {
+    if white_king == black_king
+        || white_king == pawn
+        || black_king == pawn
+        || square_distance(white_king, black_king) <= 1
+        || (white_to_move && pawn_attacks(pawn).any(|s| s == black_king))
+    {
+        return KpkResult::Invalid;
+    }
+
+    if white_to_move {
+        // Peão na sétima promove com segurança
+        let promotion = pawn + 8;
+        if pawn / 8 == 6
+            && promotion != white_king
+            && promotion != black_king
+            && (square_distance(black_king, promotion) > 1 || square_distance(white_king, promotion) == 1)
+        {
+            return KpkResult::Win;
+        }
+    } else {
+        // Afogamento ou captura do peão indefeso
+        let safe = |s: usize| square_distance(s, white_king) > 1 && !pawn_attacks(pawn).any(|a| a == s);
+        if !king_moves(black_king).any(safe) {
+            return if pawn_attacks(pawn).any(|s| s == black_king) { KpkResult::Unknown } else { KpkResult::Draw };
+        }
+        if square_distance(black_king, pawn) == 1 && square_distance(white_king, pawn) > 1 {
+            return KpkResult::Draw;
+        }
+    }
+
+    KpkResult::Unknown
+}
+
+fn kpk_classify(table: &[KpkResult], white_to_move: bool, black_king: usize, white_king: usize, pawn: usize) -> KpkResult {
+    let mut successors = Vec::new();
+
+    if white_to_move {
+        for to in king_moves(white_king) {
+            if to != pawn && square_distance(to, black_king) > 1 {
+                successors.push(table[kpk_index(false, black_king, to, pawn)]);
+            }
+        }
+        if pawn / 8 < 6 && pawn + 8 != white_king && pawn + 8 != black_king {
+            successors.push(table[kpk_index(false, black_king, white_king, pawn + 8)]);
+            if pawn / 8 == 1 && pawn + 16 != white_king && pawn + 16 != black_king {
+                successors.push(table[kpk_index(false, black_king, white_king, pawn + 16)]);
+            }
+        }
+        if successors.contains(&KpkResult::Win) {
+            KpkResult::Win
+        } else if successors.iter().all(|&r| r == KpkResult::Draw || r == KpkResult::Invalid) {
+            KpkResult::Draw
+        } else {
+            KpkResult::Unknown
+        }
+    } else {
+        for to in king_moves(black_king) {
+            if to != pawn && square_distance(to, white_king) > 1 && !pawn_attacks(pawn).any(|s| s == to) {
+                successors.push(table[kpk_index(true, to, white_king, pawn)]);
+            }
+        }
+        if successors.contains(&KpkResult::Draw) {
+            KpkResult::Draw
+        } else if successors.iter().all(|&r| r == KpkResult::Win || r == KpkResult::Invalid) {
+            KpkResult::Win
+        } else {
+            KpkResult::Unknown
+        }
+    }
+}
+
+fn kpk_generate() -> Vec<bool> {
+    let positions = || {
+        [false, true].into_iter().flat_map(|stm| {
+            (8..56).filter(|p| p % 8 < 4).flat_map(move |pawn| {
+                (0..64).flat_map(move |wk| (0..64).map(move |bk| (stm, bk, wk, pawn)))
+            })
+        })
+    };
+
+    let mut table = vec![KpkResult::Unknown; KPK_SIZE];
+    for (stm, bk, wk, pawn) in positions() {
+        table[kpk_index(stm, bk, wk, pawn)] = kpk_initial(stm, bk, wk, pawn);
+    }
+
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (stm, bk, wk, pawn) in positions() {
+            let index = kpk_index(stm, bk, wk, pawn);
+            if table[index] == KpkResult::Unknown {
+                let result = kpk_classify(&table, stm, bk, wk, pawn);
+                if result != KpkResult::Unknown {
+                    table[index] = result;
+                    changed = true;
+                }
+            }
+        }
+    }
+
+    table.into_iter().map(|r| r == KpkResult::Win).collect()
+}
+
+/// O lado forte vence o KPK? Casas normalizadas para o lado forte de brancas
+/// e o peão nas colunas a-d.
+fn kpk_probe(strong: Color, strong_king: Square, pawn: Square, weak_king: Square, strong_to_move: bool) -> bool {
+    static BITBASE: OnceLock<Vec<bool>> = OnceLock::new();
+    let bitbase = BITBASE.get_or_init(kpk_generate);
+
+    let mut squares = [strong_king.to_index(), pawn.to_index(), weak_king.to_index()];
+    if strong == Color::Black {
+        squares.iter_mut().for_each(|s| *s ^= 56);
+    }
+    if squares[1] % 8 >= 4 {
+        squares.iter_mut().for_each(|s| *s ^= 7);
+    }
+    let [white_king, pawn, black_king] = squares;
+    bitbase[kpk_index(strong_to_move, black_king, white_king, pawn)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eval::evaluate_board;
+    use chess::{Board, ALL_SQUARES};
+    use std::str::FromStr;
+
+    fn sq(name: &str) -> Square {
+        Square::from_str(name).unwrap()
+    }
+
+    fn index(name: &str) -> usize {
+        sq(name).to_index()
+    }
+
+    fn board(fen: &str) -> Board {
+        Board::from_str(fen).unwrap()
+    }
+
+    #[test]
+    fn kpk_initial_positions() {
+        let initial = |stm, bk, wk, pawn| kpk_initial(stm, index(bk), index(wk), index(pawn));
+        // Reis vizinhos, peão atacando o rei com as brancas a mover
+        assert!(initial(true, "d5", "d4", "a2") == KpkResult::Invalid);
+        assert!(initial(true, "e5", "a1", "d4") == KpkResult::Invalid);
+        // Promoção segura na sétima
+        assert!(initial(true, "h1", "a1", "d7") == KpkResult::Win);
+        // Rei defende a casa de promoção, mas o rei forte também
+        assert!(initial(true, "e7", "c7", "d7") == KpkResult::Win);
+        assert!(initial(true, "e7", "a1", "d7") == KpkResult::Unknown);
+        // Afogamento no canto e captura do peão indefeso
+        assert!(initial(false, "a8", "b6", "a7") == KpkResult::Draw);
+        assert!(initial(false, "e5", "a1", "d4") == KpkResult::Draw);
+        assert!(initial(false, "e8", "e1", "e2") == KpkResult::Unknown);
+    }
+
+    #[test]
+    fn kpk_classify_from_successors() {
+        let mut table = vec![KpkResult::Unknown; KPK_SIZE];
+        for stm in [false, true] {
+            for pawn in (8..56).filter(|p| p % 8 < 4) {
+                for wk in 0..64 {
+                    for bk in 0..64 {
+                        table[kpk_index(stm, bk, wk, pawn)] = kpk_initial(stm, bk, wk, pawn);
+                    }
+                }
+            }
+        }
+        // Qualquer lance do rei preto deixa o peão de d7 promover
+        assert!(kpk_classify(&table, false, index("h1"), index("a1"), index("d7")) == KpkResult::Win);
+        // Com as brancas a mover, avançar o peão de d6 ainda não está resolvido
+        assert!(kpk_classify(&table, true, index("h1"), index("a1"), index("d6")) == KpkResult::Unknown);
+    }
+
+    #[test]
+    fn kpk_bitbase_and_probe() {
+        let bitbase = kpk_generate();
+        assert_eq!(bitbase.len(), KPK_SIZE);
+        assert!(bitbase[kpk_index(true, index("h1"), index("a1"), index("d7"))]);
+
+        // (rei forte, peão, rei fraco, forte a mover, vence)
+        let cases = [
+            // Rei na sexta na frente do peão: vence com qualquer lado a mover
+            ("d6", "d5", "d8", true, true),
+            ("d6", "d5", "d8", false, true),
+            // Oposição: quem a tem decide
+            ("e5", "e4", "e7", true, false),
+            ("e5", "e4", "e7", false, true),
+            // Corrida às casas-chave (d4, e4, f4): quem move primeiro chega antes
+            ("e1", "e2", "e8", true, true),
+            ("e1", "e2", "e8", false, false),
+            // Peão da torre com o rei fraco no canto: empate
+            ("c5", "a4", "a8", true, false),
+            ("c5", "a4", "a8", false, false),
+            ("g6", "h5", "h8", true, false),
+            // Peão indefeso capturado
+            ("a1", "d4", "e5", false, false),
+            // Peão que o rei fraco não alcança
+            ("a1", "c5", "h6", true, true),
+        ];
+        for (strong_king, pawn, weak_king, strong_to_move, wins) in cases {
+            let (k, p, w) = (sq(strong_king), sq(pawn), sq(weak_king));
+            let flip = |s: Square| ALL_SQUARES[s.to_index() ^ 56];
+            let mirror = |s: Square| ALL_SQUARES[s.to_index() ^ 7];
+            let case = format!("{} {} {} {}", strong_king, pawn, weak_king, strong_to_move);
+            assert_eq!(kpk_probe(Color::White, k, p, w, strong_to_move), wins, "{}", case);
+            assert_eq!(kpk_probe(Color::White, mirror(k), mirror(p), mirror(w), strong_to_move), wins, "{}", case);
+            assert_eq!(kpk_probe(Color::Black, flip(k), flip(p), flip(w), strong_to_move), wins, "{}", case);
+            assert_eq!(
+                kpk_probe(Color::Black, flip(mirror(k)), flip(mirror(p)), flip(mirror(w)), strong_to_move),
+                wins,
+                "{}",
+                case
+            );
+        }
+    }
+
+    #[test]
+    fn kpk_evaluation_from_both_sides() {
+        let params = Params::default();
+        let win = specialized(&board("3k4/8/3K4/3P4/8/8/8/8 b - - 0 1"), &params).unwrap();
+        assert!(win <= -KNOWN_WIN);
+        let mirrored = specialized(&board("8/8/8/8/3p4/3k4/8/3K4 w - - 0 1"), &params).unwrap();
+        assert_eq!(mirrored, win);
+        assert_eq!(specialized(&board("4k3/8/8/8/8/8/4P3/4K3 b - - 0 1"), &params), Some(0));
+    }
+
+    #[test]
+    fn kbnk_drives_to_the_bishop_corner() {
+        let params = Params::default();
+        let score = |fen: &str| specialized(&board(fen), &params).unwrap();
+        // Bispo de casas escuras: o canto certo é a1 (ou h8), não h1
+        let right_corner = score("8/8/8/1N2B3/8/2K5/8/k7 w - - 0 1");
+        let wrong_corner = score("8/8/8/1N2B3/8/5K2/8/7k w - - 0 1");
+        let center = score("8/8/8/1N1kB3/8/2K5/8/8 w - - 0 1");
+        assert!(right_corner > wrong_corner, "{} {}", right_corner, wrong_corner);
+        assert!(right_corner > center);
+        assert!(wrong_corner > KNOWN_WIN);
+        assert_eq!(score("8/8/8/1N2B3/8/2K5/8/k7 b - - 0 1"), -right_corner);
+    }
+
+    #[test]
+    fn wrong_rook_pawn_scales_to_a_draw() {
+        let params = Params::default();
+        let scale = |fen: &str, strong| scale_factor(&board(fen), strong, &params);
+        // Bispo de d4 não controla a8; o de e4 controla
+        assert_eq!(scale("1k6/8/8/P7/3B4/8/8/4K3 w - - 0 1", Color::White), 0);
+        assert_eq!(scale("1k6/8/8/P7/4B3/8/8/4K3 w - - 0 1", Color::White), SCALE_NORMAL);
+        // Rei fraco longe da casa de promoção
+        assert_eq!(scale("8/8/8/P7/3B4/8/8/4K2k w - - 0 1", Color::White), SCALE_NORMAL);
+        // O mesmo com as pretas: h1 é clara, bispo de e5 é escuro
+        assert_eq!(scale("k7/8/8/4b3/7p/8/8/6K1 w - - 0 1", Color::Black), 0);
+        assert_eq!(evaluate_board(&board("1k6/8/8/P7/3B4/8/8/4K3 w - - 0 1"), &params), 0);
+    }
+}
@@ -0,0 +1,267 @@
+// A sexta geração por trás da interface `Engine`: livro de aberturas, busca
+// e os comandos de depuração (`debug`, `eval`, `probe`, `book`, `params`,
+// `bench`). `debug pv` e `eval pv` também buscam e mostram a variante
+// principal.
+
+use chazz_core::engine::{Engine, Limits, SearchResult};
+use chazz_core::notation::san_line;
+use chazz_core::position::{print_board, Position};
+use chess::{Board, Color, MoveGen};
+use std::io::{self, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::bench::{bench, parse_depth};
+use crate::endgame::{specialized, SCALE_NORMAL};
+use crate::eval::{evaluate_board, evaluate_terms, game_status, PHASE_MAX};
+use crate::nnue::{Network, NnueStack};
+use crate::options::Options;
+use crate::params::Params;
+use crate::rng::Rng;
+use crate::search::{principal_variation, search_game};
+
+/// `setoption name <nome> value <valor>`: `EvalFile` carrega um arquivo de
+/// parâmetros, `NNUEFile` e `UseNNUE` controlam a rede, `SyzygyPath` aponta
+/// as tablebases, `BookFile`, `BookDepth` e `BookBestMove` controlam o livro
+/// de aberturas, `Contempt`, `AsymmetricContempt` e `WeakerOpponent` ajustam
+/// a pontuação dos empates e qualquer outro nome altera o parâmetro
+/// correspondente.
+fn set_option(options: &mut Options, name: &str, value: &str) -> Result<(), String> {
+    let name = match name {
+        "Contempt" => "contempt",
+        "AsymmetricContempt" => "asymmetric_contempt",
+        other => other,
+    };
+
+    if name == "EvalFile" {
+        options.params = Params::load(value)?;
+    } else if name == "NNUEFile" {
+        options.load_network(value)?;
+    } else if name == "SyzygyPath" {
+        options.load_tablebases(value)?;
+    } else if name == "BookFile" {
+        options.load_book(value)?;
+    } else if name == "BookDepth" {
+        // 0 = sem limite
+        let depth: u32 = value.parse().map_err(|_| format!("valor inválido para {}", name))?;
+        options.book_depth = Some(depth).filter(|&d| d > 0);
+    } else if name == "BookBestMove" {
+        options.book_best = value == "true";
+    } else if name == "WeakerOpponent" {
+        options.weaker_opponent = value == "true";
+    } else if name == "UseNNUE" {
+        options.use_nnue = value == "true";
+        if options.use_nnue && options.network.is_none() {
+            eprintln!("Aviso: nenhuma rede carregada, usando a avaliação clássica");
+        }
+    } else if options.params.field_mut(name).is_some_and(|f| f.len() == 1) {
+        let value = value.parse().map_err(|_| format!("valor inválido para {}", name))?;
+        // Só troca os parâmetros se o novo valor estiver dentro da faixa
+        let mut params = options.params.clone();
+        params.field_mut(name).unwrap()[0] = value;
+        params.validate()?;
+        options.params = params;
+    } else {
+        return Err(format!("opção desconhecida: {}", name));
+    }
+    Ok(())
+}
+
+/// Variante principal da busca em SAN, para leitura humana.
+fn print_pv(position: &Position, options: &Options) {
+    let board = &position.board;
+    if let Some((line, score)) = principal_variation(board, options) {
+        println!("Variante principal ({}): {}", score, san_line(board, &line, position.fullmove()));
+    }
+}
+
+/// Comando `book`: lances do livro para a posição, com peso e frequência.
+fn print_book(board: &Board, options: &Options) {
+    let Some(book) = &options.book else {
+        println!("Nenhum livro carregado");
+        return io::stdout().flush().unwrap();
+    };
+    let moves = book.moves(board);
+    if moves.is_empty() {
+        println!("Posição fora do livro ({} entradas)", book.len());
+    }
+    let total: u32 = moves.iter().map(|&(_, weight)| weight as u32).sum();
+    for (chess_move, weight) in moves {
+        let share = if total > 0 { weight as f64 * 100.0 / total as f64 } else { 0.0 };
+        println!("{:<6} peso {:>5} ({:.1}%)", chess_move.to_string(), weight, share);
+    }
+    io::stdout().flush().unwrap();
+}
+
+/// Comando `eval`: tabuleiro, fase da partida e avaliação termo a termo para
+/// cada cor. As últimas linhas aplicam o fator de escala dos finais e, quando
+/// o material tem avaliação própria, a substituem, chegando ao valor de
+/// `evaluate_board`.
+fn print_eval(board: &Board, params: &Params, network: Option<&Network>) {
+    print_board(board);
+    println!();
+
+    let white_view = |score: i32| if board.side_to_move() == Color::White { score } else { -score };
+    let trace = evaluate_terms(board, params);
+    println!("Fase: {}/{} ({} = meio-jogo, 0 = final)", trace.game_phase, PHASE_MAX, PHASE_MAX);
+    println!("{:<14} {:>8} {:>8} {:>8}", "Termo", "Brancas", "Pretas", "Total");
+    for (name, [white, black]) in trace.terms() {
+        println!("{:<14} {:>8} {:>8} {:>8}", name, white, black, white - black);
+    }
+    let (white, black) = (trace.total(Color::White), trace.total(Color::Black));
+    println!("{:<14} {:>8} {:>8} {:>8}", "Soma", white, black, white - black);
+    let scale = format!("{}/{}", trace.scale, SCALE_NORMAL);
+    println!("{:<14} {:>17} {:>8}", "Escala", scale, trace.scaled(Color::White));
+    if let Some(score) = specialized(board, params) {
+        println!("{:<14} {:>17} {:>8}", "Especializada", "", white_view(score));
+    }
+    println!();
+    let score = evaluate_board(board, params);
+    println!("Status: {:?}", game_status(board));
+    println!("Avaliação (brancas): {}", white_view(score));
+    println!("Avaliação (lado a mover): {}", score);
+    if let Some(network) = network {
+        let score = NnueStack::new(network, board).evaluate(board);
+        println!("NNUE, {} neurônios (lado a mover): {}", network.hidden(), score);
+    }
+    io::stdout().flush().unwrap();
+}
+
+pub struct Sixth {
+    pub options: Options,
+    position: Position,
+    rng: Rng,
+}
+
+impl Sixth {
+    pub fn new(options: Options) -> Sixth {
+        let seed = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_nanos() as u64);
+        Sixth { options, position: Position::default(), rng: Rng::new(seed) }
+    }
+}
+
+impl Engine for Sixth {
+    fn name(&self) -> &'static str {
+        "sixth"
+    }
+
+    fn new_game(&mut self) {
+        self.position = Position::default();
+    }
+
+    fn set_position(&mut self, position: &Position) {
+        self.position = position.clone();
+    }
+
+    fn search(&mut self, limits: &Limits) -> SearchResult {
+        let Position { board, ply, ref history } = self.position;
+        if let Some(chess_move) = self.options.book_move(&board, ply, &mut self.rng) {
+            eprintln!("info string lance do livro");
+            return SearchResult { best_move: Some(chess_move), pv: vec![chess_move], ..Default::default() };
+        }
+        search_game(&board, history, &self.options, limits)
+    }
+
+    fn options(&self) -> Vec<(&'static str, String)> {
+        let options = &self.options;
+        vec![
+            ("Contempt", options.params.contempt.to_string()),
+            ("AsymmetricContempt", options.params.asymmetric_contempt.to_string()),
+            ("WeakerOpponent", options.weaker_opponent.to_string()),
+            ("UseNNUE", options.use_nnue.to_string()),
+            ("BookDepth", options.book_depth.unwrap_or(0).to_string()),
+            ("BookBestMove", options.book_best.to_string()),
+        ]
+    }
+
+    fn set_option(&mut self, name: &str, value: &str) -> Result<(), String> {
+        set_option(&mut self.options, name, value)
+    }
+
+    fn command(&mut self, input: &str) -> bool {
+        let options = &self.options;
+        let position = &self.position;
+        let board = &position.board;
+        if input == "debug" || input == "debug pv" {
+            // Comando adicional para debug
+            println!("Avaliação atual: {}", evaluate_board(board, &options.params));
+            println!("Status: {:?}", game_status(board));
+            println!("Lado a mover: {:?}", board.side_to_move());
+            println!("Movimentos legais: {}", MoveGen::new_legal(board).count());
+            if input == "debug pv" {
+                print_pv(position, options);
+            }
+        } else if input == "probe" {
+            // Consulta direta às tablebases
+            match &options.tablebases {
+                Some(tablebases) => match tablebases.probe_wdl(board) {
+                    Some(wdl) => {
+                        println!("WDL: {:?}", wdl);
+                        if let Some(dtz) = tablebases.probe_dtz(board) {
+                            println!("DTZ: {}", dtz);
+                        }
+                        if let Some(chess_move) = tablebases.best_move(board) {
+                            println!("Melhor lance: {}", chess_move);
+                        }
+                    }
+                    None => println!("Posição fora das tablebases"),
+                },
+                None => println!("Nenhuma tablebase carregada"),
+            }
+        } else if input == "book" {
+            print_book(board, options);
+        } else if input == "eval" || input == "eval pv" {
+            print_eval(board, &options.params, options.network.as_ref());
+            if input == "eval pv" {
+                print_pv(position, options);
+            }
+        } else if input == "params" || input == "params toml" {
+            print!("{}", options.params.to_toml());
+        } else if input == "params json" {
+            print!("{}", options.params.to_json());
+        } else if input == "bench" || input.starts_with("bench ") {
+            match parse_depth(input.split_whitespace().nth(1)) {
+                Ok(depth) => {
+                    bench(options, depth);
+                }
+                Err(e) => eprintln!("Erro: {}", e),
+            }
+        } else {
+            return false;
+        }
+        io::stdout().flush().unwrap();
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn setoption_rejects_out_of_range_depth() {
+        let mut sixth = Sixth::new(Options::default());
+        assert!(sixth.set_option("depth", "-1").is_err());
+        assert!(sixth.set_option("endgame_pieces", "-1").is_err());
+        assert!(sixth.set_option("depth", "x").is_err());
+        assert_eq!(sixth.options.params, Params::default());
+
+        sixth.set_option("depth", "2").unwrap();
+        assert_eq!(sixth.options.params.depth, 2);
+        let result = sixth.search(&Limits::default());
+        assert!(result.best_move.is_some());
+        assert_eq!(result.depth, 2);
+    }
+
+    #[test]
+    fn bad_files_are_errors() {
+        let args = |v: &[&str]| v.iter().map(|a| a.to_string()).collect::<Vec<_>>();
+        assert!(Options::from_args(&args(&["--eval-file"])).is_err());
+        assert!(Options::from_args(&args(&["--eval-file", "/nao/existe"])).is_err());
+        assert!(Options::from_args(&args(&["--book-file", "/nao/existe"])).is_err());
+        assert!(Options::from_args(&args(&[])).is_ok());
+
+        let mut sixth = Sixth::new(Options::default());
+        assert!(sixth.set_option("BookFile", "/nao/existe").is_err());
+        assert!(sixth.set_option("BookFile", "<empty>").is_ok());
+    }
+}
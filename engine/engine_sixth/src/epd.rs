@@ -0,0 +1,319 @@
+// Suítes de teste em EPD (WAC, ECM, STS...): cada posição traz o melhor
+// lance (`bm`), os lances a evitar (`am`), a identificação (`id`) e, nas
+// suítes no estilo STS, a pontuação de cada lance (`c0 "Nc3=10, Bf4=3"`).
+//
+// A posição é resolvida se o lance final está em `bm` e fora de `am`. O
+// tempo até a solução é o da primeira iteração a partir da qual a busca não
+// mudou mais para um lance errado; com só `--depth`, a profundidade é fixa e
+// o tempo é o da busca toda. O resumo gravado em `--output` não tem tempos,
+// para que dê para comparar execuções com `diff`.
+
+use chess::{Board, ChessMove};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Write};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use chazz_core::engine::Limits;
+use chazz_core::notation::{parse_move, to_san};
+
+use crate::cli::{parse_search_args, SearchArgs};
+use crate::options::Options;
+use crate::search::search_with;
+
+/// Uma posição da suíte.
+pub struct Entry {
+    pub id: String,
+    pub board: Board,
+    pub best: Vec<ChessMove>,
+    pub avoid: Vec<ChessMove>,
+    /// Pontos de cada lance (`c0`), vazio fora das suítes no estilo STS
+    pub points: Vec<(ChessMove, u32)>,
+}
+
+/// Operações de uma linha EPD: opcode e operandos, com aspas respeitadas.
+fn operations(text: &str) -> Vec<(String, Vec<String>)> {
+    let mut operations = Vec::new();
+    let mut words: Vec<String> = Vec::new();
+    let mut word = String::new();
+    let mut quoted = false;
+
+    for c in text.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            ';' if !quoted => {
+                words.extend(Some(std::mem::take(&mut word)).filter(|w| !w.is_empty()));
+                if !words.is_empty() {
+                    let opcode = words.remove(0);
+                    operations.push((opcode, std::mem::take(&mut words)));
+                }
+            }
+            c if c.is_whitespace() && !quoted => words.extend(Some(std::mem::take(&mut word)).filter(|w| !w.is_empty())),
+            c => word.push(c),
+        }
+    }
+    words.extend(Some(word).filter(|w| !w.is_empty()));
+    if !words.is_empty() {
+        let opcode = words.remove(0);
+        operations.push((opcode, words));
+    }
+    operations
+}
+
+/// `Nc3=10, Bf4=3, Qd2=1`
+fn parse_points(board: &Board, text: &str) -> Result<Vec<(ChessMove, u32)>, String> {
+    text.split(',')
+        .filter(|item| !item.trim().is_empty())
+        .map(|item| {
+            let (san, points) = item.trim().split_once('=').ok_or(format!("c0 inválido: {}", item))?;
+            let points = points.trim().parse().map_err(|_| format!("c0 inválido: {}", item))?;
+            Ok((parse_move(board, san.trim())?, points))
+        })
+        .collect()
+}
+
+impl Entry {
+    /// Linha EPD: os quatro campos da FEN seguidos das operações.
+    pub fn parse(line: &str) -> Result<Entry, String> {
+        let fields: Vec<&str> = line.split_whitespace().take(4).collect();
+        if fields.len() < 4 {
+            return Err("EPD sem os quatro campos da posição".to_string());
+        }
+        let board = Board::from_str(&format!("{} 0 1", fields.join(" "))).map_err(|_| "posição inválida".to_string())?;
+        let rest = line.trim_start();
+        let rest = fields.iter().fold(rest, |rest, field| rest[field.len()..].trim_start());
+
+        let mut entry = Entry { id: String::new(), board, best: Vec::new(), avoid: Vec::new(), points: Vec::new() };
+        let moves = |operands: &[String]| operands.iter().map(|san| parse_move(&board, san)).collect::<Result<Vec<_>, _>>();
+        for (opcode, operands) in operations(rest) {
+            match opcode.as_str() {
+                "id" => entry.id = operands.join(" "),
+                "bm" => entry.best = moves(&operands)?,
+                "am" => entry.avoid = moves(&operands)?,
+                "c0" => {
+                    // Nas suítes STS o `c0` traz a pontuação; em outras é só comentário
+                    if let Ok(points) = parse_points(&board, &operands.join(" ")) {
+                        entry.points = points;
+                    }
+                }
+                _ => {}
+            }
+        }
+        if entry.best.is_empty() && entry.avoid.is_empty() {
+            return Err("posição sem bm nem am".to_string());
+        }
+        Ok(entry)
+    }
+
+    pub fn solved(&self, chess_move: ChessMove) -> bool {
+        (self.best.is_empty() || self.best.contains(&chess_move)) && !self.avoid.contains(&chess_move)
+    }
+
+    /// Pontos do lance, pelo `c0` ou, sem ele, 1 se resolvido.
+    pub fn score(&self, chess_move: ChessMove) -> u32 {
+        if self.points.is_empty() {
+            return u32::from(self.solved(chess_move));
+        }
+        self.points.iter().find(|&&(m, _)| m == chess_move).map_or(0, |&(_, points)| points)
+    }
+
+    pub fn max_score(&self) -> u32 {
+        self.points.iter().map(|&(_, points)| points).max().unwrap_or(1)
+    }
+
+    /// `bm Qg6` e/ou `am Rxb2`, em SAN.
+    fn expected(&self) -> String {
+        let san = |moves: &[ChessMove]| moves.iter().map(|&m| to_san(&self.board, m)).collect::<Vec<_>>().join(" ");
+        let mut parts = Vec::new();
+        if !self.best.is_empty() {
+            parts.push(format!("bm {}", san(&self.best)));
+        }
+        if !self.avoid.is_empty() {
+            parts.push(format!("am {}", san(&self.avoid)));
+        }
+        parts.join("; ")
+    }
+
+    /// Grupo da posição pelo `id` sem o número: "STS(v1.0) Undermine.001" fica
+    /// "STS(v1.0) Undermine".
+    fn group(&self) -> &str {
+        self.id.rsplit_once('.').map_or(&self.id, |(group, _)| group)
+    }
+}
+
+/// Resultado de uma posição.
+struct Outcome {
+    chess_move: Option<ChessMove>,
+    solved: bool,
+    points: u32,
+    /// Tempo e profundidade em que o lance certo foi encontrado de vez
+    found: Option<(Duration, u32)>,
+}
+
+fn run_entry(entry: &Entry, options: &Options, limits: &Limits) -> Outcome {
+    let start = Instant::now();
+    let mut found = None;
+    let result = search_with(&entry.board, &[], options, limits, &mut |iteration| {
+        let right = iteration.best_move.is_some_and(|m| entry.solved(m));
+        match (right, found) {
+            (true, None) => found = Some((start.elapsed(), iteration.depth)),
+            (false, _) => found = None,
+            _ => {}
+        }
+    });
+    let chess_move = result.best_move;
+    let solved = chess_move.is_some_and(|m| entry.solved(m));
+    // A última iteração pode ter sido interrompida com outro lance
+    let found = found.filter(|_| solved).or_else(|| solved.then(|| (start.elapsed(), result.depth)));
+    Outcome { chess_move, solved, points: chess_move.map_or(0, |m| entry.score(m)), found }
+}
+
+/// Soma de uma suíte ou de um grupo.
+#[derive(Default)]
+struct Totals {
+    positions: u32,
+    solved: u32,
+    points: u32,
+    max_points: u32,
+}
+
+impl Totals {
+    fn add(&mut self, entry: &Entry, outcome: &Outcome) {
+        self.positions += 1;
+        self.solved += u32::from(outcome.solved);
+        self.points += outcome.points;
+        self.max_points += entry.max_score();
+    }
+
+    fn describe(&self) -> String {
+        let percent = |part: u32, total: u32| part as f64 * 100.0 / total.max(1) as f64;
+        format!(
+            "{}/{} resolvidas ({:.1}%), {}/{} pontos ({:.1}%)",
+            self.solved,
+            self.positions,
+            percent(self.solved, self.positions),
+            self.points,
+            self.max_points,
+            percent(self.points, self.max_points)
+        )
+    }
+}
+
+/// `epd <arquivo>... [--time <ms>] [--depth <n>] [--output <arquivo>]
+/// [--eval-file <arquivo>] [--nnue-file <arquivo>] [--syzygy-path <diretórios>]`
+pub fn run(args: &[String]) {
+    let SearchArgs { inputs, mut limits, output, .. } = match parse_search_args(args, &[]) {
+        Ok(parsed) => parsed,
+        Err(e) => return eprintln!("Erro: {}", e),
+    };
+    if inputs.is_empty() {
+        return eprintln!("Uso: epd <arquivo>... [--time <ms>] [--depth <n>] [--output <arquivo>] [--eval-file <arquivo>] [--nnue-file <arquivo>] [--syzygy-path <diretórios>]");
+    }
+    let output = output.unwrap_or_else(|| "epd.txt".to_string());
+    if limits.movetime.is_none() && limits.depth.is_none() {
+        limits.movetime = Some(1000);
+    }
+    let options = match Options::from_args(args) {
+        Ok(options) => options,
+        Err(e) => return eprintln!("Erro: {}", e),
+    };
+
+    let mut summary = String::new();
+    let mut overall = Totals::default();
+    let start = Instant::now();
+    for path in &inputs {
+        let text = match fs::read(path) {
+            Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+            Err(e) => return eprintln!("Erro: {}: {}", path, e),
+        };
+        let mut totals = Totals::default();
+        let mut groups: BTreeMap<String, Totals> = BTreeMap::new();
+        summary.push_str(&format!("# {}\n", path));
+
+        for (i, line) in text.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+            let entry = match Entry::parse(line) {
+                Ok(entry) => entry,
+                Err(e) => {
+                    eprintln!("Aviso: {}:{}: {}", path, i + 1, e);
+                    continue;
+                }
+            };
+            let id = if entry.id.is_empty() { format!("{}:{}", path, i + 1) } else { entry.id.clone() };
+            let outcome = run_entry(&entry, &options, &limits);
+            let san = outcome.chess_move.map_or("-".to_string(), |m| to_san(&entry.board, m));
+            let points = if entry.points.is_empty() { String::new() } else { format!(" {}/{}", outcome.points, entry.max_score()) };
+
+            let detail = match outcome.found {
+                Some((time, depth)) => format!("em {} ms (prof. {})", time.as_millis(), depth),
+                None => format!("esperado {}", entry.expected()),
+            };
+            let status = if outcome.solved { "ok" } else { "falhou" };
+            println!("{:<28} {:<7} {:<7}{} {}", id, status, san, points, detail);
+            io::stdout().flush().unwrap();
+
+            summary.push_str(&format!("{} {} {}{}\n", id, status, san, points));
+            totals.add(&entry, &outcome);
+            groups.entry(entry.group().to_string()).or_default().add(&entry, &outcome);
+            overall.add(&entry, &outcome);
+        }
+
+        println!("{}: {}", path, totals.describe());
+        summary.push_str(&format!("Total: {}\n", totals.describe()));
+        if groups.len() > 1 {
+            for (group, group_totals) in &groups {
+                println!("  {}: {}", group, group_totals.describe());
+                summary.push_str(&format!("  {}: {}\n", group, group_totals.describe()));
+            }
+        }
+        summary.push('\n');
+    }
+
+    if inputs.len() > 1 {
+        println!("Geral: {}", overall.describe());
+        summary.push_str(&format!("Geral: {}\n", overall.describe()));
+    }
+    println!("Tempo: {:.1} s", start.elapsed().as_secs_f64());
+    match fs::write(&output, summary) {
+        Ok(()) => println!("Resumo salvo em {}", output),
+        Err(e) => eprintln!("Erro: {}: {}", output, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_bm_am_id_and_c0() {
+        let entry = Entry::parse(
+            "1kr5/3n4/q3p2p/p2n2p1/PppB1P2/5BP1/1P2Q2P/3R2K1 w - - bm f5; id \"STS(v1.0) Undermine.001\"; c0 \"f5=10, Be5+=2, Bf2=3, Bg4=2\";",
+        )
+        .unwrap();
+        assert_eq!(entry.id, "STS(v1.0) Undermine.001");
+        assert_eq!(entry.group(), "STS(v1.0) Undermine");
+        let f5 = ChessMove::from_str("f4f5").unwrap();
+        assert_eq!(entry.best, vec![f5]);
+        assert_eq!(entry.score(f5), 10);
+        assert_eq!(entry.score(ChessMove::from_str("f3g4").unwrap()), 2);
+        assert_eq!(entry.score(ChessMove::from_str("g1f2").unwrap()), 0);
+        assert_eq!(entry.max_score(), 10);
+
+        let entry = Entry::parse("r1b1k2r/ppppnppp/2n2q2/2b5/3NP3/2P1B3/PP3PPP/RN1QKB1R w KQkq - am Nxc6; id \"avoid\";").unwrap();
+        assert!(entry.best.is_empty());
+        assert!(!entry.solved(ChessMove::from_str("d4c6").unwrap()));
+        assert!(entry.solved(ChessMove::from_str("d1d2").unwrap()));
+        assert!(Entry::parse("4k3/8/8/8/8/8/8/4K3 w - -").is_err());
+        assert!(Entry::parse("4k3/8/8/8/8/8/8/4K3 w bm Kd2;").is_err());
+    }
+
+    #[test]
+    fn solves_a_mate_in_one() {
+        let entry = Entry::parse("6k1/5ppp/8/8/8/8/8/R5K1 w - - bm Ra8#; id \"mate\";").unwrap();
+        let limits = Limits { depth: Some(2), ..Default::default() };
+        let outcome = run_entry(&entry, &Options::default(), &limits);
+        assert!(outcome.solved);
+        assert_eq!(outcome.points, 1);
+        assert_eq!(outcome.found.map(|(_, depth)| depth), Some(1));
+    }
+}
@@ -0,0 +1,605 @@
+use chess::{
+    get_adjacent_files, get_bishop_moves, get_file, get_king_moves, get_knight_moves,
+    get_pawn_attacks, get_rank, get_rook_moves, BitBoard, BoardStatus, CastleRights, Color, Piece,
+    Rank, Square, EMPTY,
+};
+
+use chazz_core::board::Query;
+use chazz_core::eval::CENTER_SQUARES;
+
+use crate::endgame;
+use crate::params::Params;
+
+/// Valor material de uma peça em centipeões, segundo os parâmetros.
+pub fn material_value(piece: Piece, params: &Params) -> i32 {
+    match piece {
+        Piece::Pawn => params.pawn_value,
+        Piece::Knight => params.knight_value,
+        Piece::Bishop => params.bishop_value,
+        Piece::Rook => params.rook_value,
+        Piece::Queen => params.queen_value,
+        Piece::King => 0,
+    }
+}
+
+/// Fase com todas as peças (cavalo e bispo valem 1, torre 2, dama 4); 0 é
+/// um final só de peões.
+pub const PHASE_MAX: i32 = 24;
+
+/// Fase da partida pelo material sem peões, de `PHASE_MAX` (meio-jogo) a 0 (final).
+pub fn game_phase(board: &impl Query) -> i32 {
+    let count = |piece| board.pieces(piece).popcnt() as i32;
+    let phase = count(Piece::Knight) + count(Piece::Bishop) + 2 * count(Piece::Rook) + 4 * count(Piece::Queen);
+    phase.min(PHASE_MAX)
+}
+
+/// Valores da peça nas tabelas peça-casa de meio-jogo e de final, na casa
+/// vista pelas brancas (as tabelas começam pela oitava fileira).
+fn pst(piece: Piece, square: Square, color: Color, params: &Params) -> (i32, i32) {
+    let index = relative_square(square, color).to_index() ^ 56;
+    let (middlegame, endgame) = match piece {
+        Piece::Pawn => (&params.pawn_pst, &params.pawn_pst_endgame),
+        Piece::Knight => (&params.knight_pst, &params.knight_pst),
+        Piece::Bishop => (&params.bishop_pst, &params.bishop_pst),
+        Piece::Rook => (&params.rook_pst, &params.rook_pst),
+        Piece::Queen => (&params.queen_pst, &params.queen_pst),
+        Piece::King => (&params.king_pst, &params.king_pst_endgame),
+    };
+    (middlegame[index], endgame[index])
+}
+
+// Casas claras do tabuleiro (a1 é escura)
+const LIGHT_SQUARES: BitBoard = BitBoard(0x55AA_55AA_55AA_55AA);
+
+/// Estado da partida: o do tabuleiro, mais o empate por material insuficiente.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameStatus {
+    Ongoing,
+    Checkmate,
+    Stalemate,
+    InsufficientMaterial,
+}
+
+/// Posição morta: só reis, rei e uma peça menor contra rei, ou só bispos,
+/// todos em casas da mesma cor. Nenhum lado consegue dar mate.
+pub fn insufficient_material(board: &impl Query) -> bool {
+    let heavy = board.pieces(Piece::Pawn) | board.pieces(Piece::Rook) | board.pieces(Piece::Queen);
+    if heavy != EMPTY {
+        return false;
+    }
+
+    let minors = board.pieces(Piece::Knight) | board.pieces(Piece::Bishop);
+    if minors.popcnt() <= 1 {
+        return true;
+    }
+    let bishops = board.pieces(Piece::Bishop);
+    minors == bishops && ((bishops & LIGHT_SQUARES) == EMPTY || (bishops & !LIGHT_SQUARES) == EMPTY)
+}
+
+pub fn game_status(board: &impl Query) -> GameStatus {
+    match board.status() {
+        BoardStatus::Checkmate => GameStatus::Checkmate,
+        BoardStatus::Stalemate => GameStatus::Stalemate,
+        BoardStatus::Ongoing if insufficient_material(board) => GameStatus::InsufficientMaterial,
+        BoardStatus::Ongoing => GameStatus::Ongoing,
+    }
+}
+
+/// Rank do ponto de vista de `color` (0 = primeira fileira do próprio lado).
+fn relative_rank(square: Square, color: Color) -> usize {
+    let rank = square.get_rank().to_index();
+    if color == Color::White {
+        rank
+    } else {
+        7 - rank
+    }
+}
+
+/// Fileiras à frente de `square` na direção em que os peões de `color` avançam.
+fn forward_ranks(square: Square, color: Color) -> BitBoard {
+    let rank = square.get_rank().to_index();
+    let mut mask = EMPTY;
+    for r in 0..8 {
+        if (color == Color::White && r > rank) || (color == Color::Black && r < rank) {
+            mask |= get_rank(Rank::from_index(r));
+        }
+    }
+    mask
+}
+
+/// Casa espelhada verticalmente quando `color` é preto (a2 vira a7).
+fn relative_square(square: Square, color: Color) -> Square {
+    if color == Color::White {
+        square
+    } else {
+        Square::make_square(Rank::from_index(7 - square.get_rank().to_index()), square.get_file())
+    }
+}
+
+/// Casas atacadas por uma peça em `square`, considerando as peças em `occupied`.
+pub fn piece_attacks(piece: Piece, square: Square, color: Color, occupied: BitBoard) -> BitBoard {
+    match piece {
+        Piece::Pawn => get_pawn_attacks(square, color, !EMPTY),
+        Piece::Knight => get_knight_moves(square),
+        Piece::Bishop => get_bishop_moves(square, occupied),
+        Piece::Rook => get_rook_moves(square, occupied),
+        Piece::Queen => get_bishop_moves(square, occupied) | get_rook_moves(square, occupied),
+        Piece::King => get_king_moves(square),
+    }
+}
+
+/// Todas as casas atacadas pelas peças de `color`.
+fn attacked_by(board: &impl Query, color: Color) -> BitBoard {
+    let occupied = board.combined();
+    let mut attacks = EMPTY;
+    for square in board.color_combined(color) {
+        if let Some(piece) = board.piece_on(square) {
+            attacks |= piece_attacks(piece, square, color, occupied);
+        }
+    }
+    attacks
+}
+
+/// Casas atacadas pelos peões de `color`.
+fn pawn_attacks(board: &impl Query, color: Color) -> BitBoard {
+    let mut attacks = EMPTY;
+    for square in board.pieces(Piece::Pawn) & board.color_combined(color) {
+        attacks |= get_pawn_attacks(square, color, !EMPTY);
+    }
+    attacks
+}
+
+/// Mobilidade de `color` somando a tabela de cada cavalo, bispo, torre e dama.
+fn mobility(board: &impl Query, color: Color, params: &Params) -> i32 {
+    let occupied = board.combined();
+    let area = !board.color_combined(color) & !pawn_attacks(board, !color);
+    let ours = board.color_combined(color);
+    let mut score = 0;
+
+    for square in board.pieces(Piece::Knight) & ours {
+        score += params.knight_mobility[(get_knight_moves(square) & area).popcnt() as usize];
+    }
+    for square in board.pieces(Piece::Bishop) & ours {
+        score += params.bishop_mobility[(get_bishop_moves(square, occupied) & area).popcnt() as usize];
+    }
+    for square in board.pieces(Piece::Rook) & ours {
+        score += params.rook_mobility[(get_rook_moves(square, occupied) & area).popcnt() as usize];
+    }
+    for square in board.pieces(Piece::Queen) & ours {
+        let attacks = get_bishop_moves(square, occupied) | get_rook_moves(square, occupied);
+        score += params.queen_mobility[(attacks & area).popcnt() as usize];
+    }
+    score
+}
+
+/// Penalidade das peças de `color` atacadas pelo adversário, maior quando
+/// a peça atacada não tem nenhum defensor (peça pendurada).
+fn threats_against(
+    board: &impl Query,
+    color: Color,
+    defended: BitBoard,
+    attacked: BitBoard,
+    params: &Params,
+) -> i32 {
+    let mut score = 0;
+    let targets = board.color_combined(color) & !board.pieces(Piece::King) & attacked;
+    for square in targets {
+        if let Some(piece) = board.piece_on(square) {
+            let value = material_value(piece, params);
+            score += value * params.threat_weight / 100;
+            if (defended & BitBoard::from_square(square)) == EMPTY {
+                score += value * params.hanging_weight / 100;
+            }
+        }
+    }
+    score
+}
+
+/// Termos específicos de cada peça de `color`: par de bispos, torres e damas
+/// em colunas abertas, torre na sétima, postos avançados, bispo ruim e peças
+/// presas.
+fn piece_terms(board: &impl Query, color: Color, params: &Params) -> i32 {
+    let ours = board.color_combined(color);
+    let our_pawns = board.pieces(Piece::Pawn) & ours;
+    let their_pawns = board.pieces(Piece::Pawn) & board.color_combined(!color);
+    let bishops = board.pieces(Piece::Bishop) & ours;
+    let mut score = 0;
+
+    // Par de bispos
+    if bishops.popcnt() >= 2 {
+        score += params.bishop_pair;
+    }
+
+    // Torres e damas em colunas abertas ou semiabertas
+    for square in (board.pieces(Piece::Rook) | board.pieces(Piece::Queen)) & ours {
+        let file = get_file(square.get_file());
+        let rook = board.piece_on(square) == Some(Piece::Rook);
+        if (file & our_pawns) == EMPTY {
+            score += match ((file & their_pawns) == EMPTY, rook) {
+                (true, true) => params.rook_open_file,
+                (false, true) => params.rook_semi_open_file,
+                (true, false) => params.queen_open_file,
+                (false, false) => params.queen_semi_open_file,
+            };
+        }
+    }
+
+    // Torre na sétima fileira
+    for square in board.pieces(Piece::Rook) & ours {
+        if relative_rank(square, color) == 6 {
+            score += params.rook_seventh;
+        }
+    }
+
+    // Postos avançados: cavalo ou bispo no campo inimigo, apoiado por peão
+    // e fora do alcance dos peões adversários
+    for square in (board.pieces(Piece::Knight) | bishops) & ours {
+        let rank = relative_rank(square, color);
+        if !(3..=5).contains(&rank) {
+            continue;
+        }
+        let supported = get_pawn_attacks(square, !color, our_pawns) != EMPTY;
+        let attackable = (get_adjacent_files(square.get_file())
+            & forward_ranks(square, color)
+            & their_pawns)
+            != EMPTY;
+        if supported && !attackable {
+            score += if board.piece_on(square) == Some(Piece::Knight) {
+                params.knight_outpost
+            } else {
+                params.bishop_outpost
+            };
+        }
+    }
+
+    // Bispo ruim: peões próprios nas casas da mesma cor do bispo
+    for square in bishops {
+        let same_color = if (LIGHT_SQUARES & BitBoard::from_square(square)) != EMPTY {
+            LIGHT_SQUARES
+        } else {
+            !LIGHT_SQUARES
+        };
+        score -= (our_pawns & same_color).popcnt() as i32 * params.bad_bishop_pawn;
+    }
+
+    // Bispo preso em a7/h7 por peão em b6/g6 (a2/h2 e b3/g3 para as pretas)
+    let trapped = [(Square::A7, Square::B6), (Square::H7, Square::G6)];
+    for (bishop_square, pawn_square) in trapped {
+        let bishop_square = relative_square(bishop_square, color);
+        let pawn_square = relative_square(pawn_square, color);
+        if (bishops & BitBoard::from_square(bishop_square)) != EMPTY
+            && (their_pawns & BitBoard::from_square(pawn_square)) != EMPTY
+        {
+            score -= params.trapped_bishop;
+        }
+    }
+
+    // Torre presa pelo próprio rei que perdeu o direito de roque
+    let king = board.king_square(color);
+    if relative_rank(king, color) == 0 && board.castle_rights(color) == CastleRights::NoRights {
+        let king_file = king.get_file().to_index();
+        for square in board.pieces(Piece::Rook) & ours {
+            if relative_rank(square, color) > 1 {
+                continue;
+            }
+            let rook_file = square.get_file().to_index();
+            let kingside = king_file >= 5 && rook_file > king_file;
+            let queenside = king_file <= 2 && rook_file < king_file;
+            if kingside || queenside {
+                score -= params.trapped_rook;
+            }
+        }
+    }
+
+    score
+}
+
+/// Avaliação separada por termo e por cor, usada pelo comando `eval` para
+/// explicar de onde vem a pontuação. Cada termo é indexado por `Color::to_index`.
+/// `pst` usa as tabelas de meio-jogo; `phase` é o quanto a interpolação pela
+/// fase da partida desloca esse valor em direção às tabelas de final.
+#[derive(Clone, Debug, Default)]
+pub struct Trace {
+    pub material: [i32; 2],
+    pub pst: [i32; 2],
+    pub phase: [i32; 2],
+    pub center: [i32; 2],
+    pub mobility: [i32; 2],
+    pub threats: [i32; 2],
+    pub pieces: [i32; 2],
+    pub king_safety: [i32; 2],
+    pub pawns: [i32; 2],
+    pub game_phase: i32, // De PHASE_MAX (meio-jogo) a 0 (final)
+    pub scale: i32,      // Fator de escala dos finais, em SCALE_NORMAL avos
+}
+
+impl Trace {
+    /// Nome e valores de cada termo, na ordem em que são impressos.
+    pub fn terms(&self) -> [(&'static str, [i32; 2]); 9] {
+        [
+            ("Material", self.material),
+            ("PST", self.pst),
+            ("Fase", self.phase),
+            ("Centro", self.center),
+            ("Mobilidade", self.mobility),
+            ("Ameaças", self.threats),
+            ("Peças", self.pieces),
+            ("Rei", self.king_safety),
+            ("Peões", self.pawns),
+        ]
+    }
+
+    pub fn total(&self, color: Color) -> i32 {
+        self.terms().iter().map(|(_, values)| values[color.to_index()]).sum()
+    }
+
+    /// Pontuação do ponto de vista de `color`.
+    pub fn score(&self, color: Color) -> i32 {
+        self.total(color) - self.total(!color)
+    }
+
+    /// Pontuação do ponto de vista de `color` depois do fator de escala.
+    pub fn scaled(&self, color: Color) -> i32 {
+        self.score(color) * self.scale / endgame::SCALE_NORMAL
+    }
+}
+
+/// Calcula todos os termos da avaliação para as duas cores.
+pub fn evaluate_terms(board: &impl Query, params: &Params) -> Trace {
+    let mut trace = Trace { game_phase: game_phase(board), ..Trace::default() };
+    let mut pst_endgame = [0; 2];
+
+    // Material, tabelas peça-casa e controle do centro
+    for square in board.combined() {
+        if let (Some(piece), Some(color)) = (board.piece_on(square), board.color_on(square)) {
+            trace.material[color.to_index()] += material_value(piece, params);
+
+            let (middlegame, endgame) = pst(piece, square, color, params);
+            trace.pst[color.to_index()] += middlegame;
+            pst_endgame[color.to_index()] += endgame;
+
+            // Bônus para controle do centro
+            if CENTER_SQUARES.contains(&square) {
+                trace.center[color.to_index()] += params.center_bonus;
+            }
+        }
+    }
+
+    let attacks = [attacked_by(board, Color::White), attacked_by(board, Color::Black)];
+    for color in [Color::White, Color::Black] {
+        let i = color.to_index();
+
+        // Interpolação das tabelas: no meio-jogo vale a de meio-jogo, e quanto
+        // menos peças, mais perto da de final
+        trace.phase[i] = (pst_endgame[i] - trace.pst[i]) * (PHASE_MAX - trace.game_phase) / PHASE_MAX;
+
+        // Mobilidade por tipo de peça
+        trace.mobility[i] = mobility(board, color, params);
+
+        // Peças atacadas e peças penduradas (atacadas sem defensor)
+        trace.threats[i] = -threats_against(board, color, attacks[i], attacks[1 - i], params);
+
+        // Termos específicos das peças
+        trace.pieces[i] = piece_terms(board, color, params);
+
+        // Desenvolvimento: bônus leve por manter o rei seguro no início
+        let home = if color == Color::White { Square::E1 } else { Square::E8 };
+        if board.king_square(color) == home {
+            trace.king_safety[i] += params.king_home_bonus;
+        }
+
+        // Posição avançada para peões (promover peões)
+        for square in board.pieces(Piece::Pawn) & board.color_combined(color) {
+            trace.pawns[i] += relative_rank(square, color) as i32 * params.pawn_advance;
+        }
+    }
+
+    // Estar em xeque é ruim (só quem joga pode estar)
+    if board.checkers().popcnt() > 0 {
+        trace.king_safety[board.side_to_move().to_index()] -= params.in_check_penalty;
+    }
+
+    // Finais com tendência de empate, vistos pelo lado que está à frente
+    let strong = if trace.score(Color::White) >= 0 { Color::White } else { Color::Black };
+    trace.scale = endgame::scale_factor(board, strong, params);
+    trace
+}
+
+pub fn evaluate_board(board: &impl Query, params: &Params) -> i32 {
+    // Status do jogo
+    match game_status(board) {
+        GameStatus::Checkmate => -30000, // Valor extremamente negativo para xeque-mate
+        GameStatus::Stalemate | GameStatus::InsufficientMaterial => 0, // Empate
+        GameStatus::Ongoing => {
+            // Finais conhecidos têm avaliação própria ou são escalados
+            if let Some(score) = endgame::specialized(board, params) {
+                return score;
+            }
+            evaluate_terms(board, params).scaled(board.side_to_move())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chess::Board;
+    use std::str::FromStr;
+
+    fn dead(fen: &str) -> bool {
+        insufficient_material(&Board::from_str(fen).unwrap())
+    }
+
+    #[test]
+    fn insufficient_material_draws() {
+        assert!(dead("8/8/8/4k3/8/8/8/4K3 w - - 0 1"));
+        // Rei e cavalo, rei e bispo
+        assert!(dead("8/8/8/4k3/8/8/8/4K1N1 w - - 0 1"));
+        assert!(dead("8/8/8/4k3/8/8/8/2B1K3 b - - 0 1"));
+        // Bispos só em casas escuras (c1, e3, g1 e f8), de um lado ou dos dois
+        assert!(dead("5b2/8/8/4k3/8/8/8/2B1K3 w - - 0 1"));
+        assert!(dead("8/8/8/4k3/8/4B3/8/2B1K1B1 w - - 0 1"));
+
+        let board = Board::from_str("5b2/8/8/4k3/8/8/8/2B1K3 w - - 0 1").unwrap();
+        assert_eq!(game_status(&board), GameStatus::InsufficientMaterial);
+        assert_eq!(evaluate_board(&board, &Params::default()), 0);
+    }
+
+    #[test]
+    fn mating_material_is_not_a_draw() {
+        // Dois cavalos ainda podem dar mate se o adversário errar
+        assert!(!dead("8/8/8/4k3/8/8/8/1N2KN2 w - - 0 1"));
+        // Bispos em cores diferentes (c1 escura, c8 clara), cavalo contra bispo
+        assert!(!dead("2b5/8/8/4k3/8/8/8/2B1K3 w - - 0 1"));
+        assert!(!dead("2n5/8/8/4k3/8/8/8/2B1K3 w - - 0 1"));
+        assert!(!dead("8/8/8/4k3/8/8/4P3/4K3 w - - 0 1"));
+        assert!(!dead("8/8/8/4k3/8/8/8/R3K3 w - - 0 1"));
+        let start = Board::default();
+        assert!(!insufficient_material(&start));
+        assert_eq!(game_status(&start), GameStatus::Ongoing);
+    }
+
+    #[test]
+    fn mobility_counts_safe_squares() {
+        let params = Params::default();
+        let white = |fen: &str| mobility(&Board::from_str(fen).unwrap(), Color::White, &params);
+        assert_eq!(white("7k/8/8/8/4N3/8/8/7K w - - 0 1"), params.knight_mobility[8]);
+        assert_eq!(white("7k/8/8/8/8/8/8/N6K w - - 0 1"), params.knight_mobility[2]);
+        // c5 está atacada pelo peão de d6; capturar o próprio peão conta
+        assert_eq!(white("7k/8/3p4/8/4N3/8/8/7K w - - 0 1"), params.knight_mobility[7]);
+        // Casas ocupadas por peças próprias não contam
+        assert_eq!(white("7k/8/8/8/8/8/8/R5NK w - - 0 1"), params.rook_mobility[12] + params.knight_mobility[3]);
+        assert_eq!(white("6k1/8/8/8/3Q4/8/8/7K w - - 0 1"), params.queen_mobility[27]);
+    }
+
+    #[test]
+    fn threats_and_hanging_pieces() {
+        let params = Params { threat_weight: 10, hanging_weight: 30, ..Params::default() };
+        let penalty = |fen: &str| {
+            let board = Board::from_str(fen).unwrap();
+            let attacks = [attacked_by(&board, Color::White), attacked_by(&board, Color::Black)];
+            threats_against(&board, Color::White, attacks[0], attacks[1], &params)
+        };
+        let knight = params.knight_value;
+        // Cavalo atacado pelo peão de d5: pendurado, depois defendido por f3
+        assert_eq!(penalty("7k/8/8/3p4/4N3/8/8/7K w - - 0 1"), knight * 10 / 100 + knight * 30 / 100);
+        assert_eq!(penalty("7k/8/8/3p4/4N3/5P2/8/7K w - - 0 1"), knight * 10 / 100);
+        assert_eq!(penalty("7k/8/8/8/4N3/8/8/7K w - - 0 1"), 0);
+        // O rei em xeque não conta como peça atacada
+        assert_eq!(penalty("4r2k/8/8/8/8/8/8/4K3 w - - 0 1"), 0);
+
+        let trace = evaluate_terms(&Board::from_str("7k/8/8/3p4/4N3/8/8/7K w - - 0 1").unwrap(), &params);
+        assert_eq!(trace.threats[Color::White.to_index()], -(knight * 10 / 100 + knight * 30 / 100));
+    }
+
+    #[test]
+    fn piece_terms_by_pattern() {
+        let p = Params::default();
+        let terms = |fen: &str, color| piece_terms(&Board::from_str(fen).unwrap(), color, &p);
+        let white = |fen: &str| terms(fen, Color::White);
+
+        // Par de bispos (sem peões, nada de bispo ruim)
+        assert_eq!(white("7k/8/8/8/8/8/8/2B1KB2 w - - 0 1"), p.bishop_pair);
+        assert_eq!(white("7k/8/8/8/8/8/8/2B1K3 w - - 0 1"), 0);
+
+        // Torre em coluna aberta, semiaberta e fechada; dama em coluna aberta
+        assert_eq!(white("7k/8/8/8/8/8/8/R3K3 w - - 0 1"), p.rook_open_file);
+        assert_eq!(white("7k/8/p7/8/8/8/8/R3K3 w - - 0 1"), p.rook_semi_open_file);
+        assert_eq!(white("7k/8/8/8/8/8/P7/R3K3 w - - 0 1"), 0);
+        assert_eq!(white("6k1/8/8/8/8/8/8/Q3K3 w - - 0 1"), p.queen_open_file);
+        assert_eq!(white("6k1/8/p7/8/8/8/8/Q3K3 w - - 0 1"), p.queen_semi_open_file);
+
+        // Torre na sétima, dos dois lados do tabuleiro
+        assert_eq!(white("7k/R7/8/8/8/8/8/4K3 w - - 0 1"), p.rook_open_file + p.rook_seventh);
+        assert_eq!(terms("4k3/8/8/8/8/8/r7/7K b - - 0 1", Color::Black), p.rook_open_file + p.rook_seventh);
+
+        // Posto avançado em e5 apoiado por d4; o peão de f7 ainda pode expulsar o cavalo
+        assert_eq!(white("7k/8/8/4N3/3P4/8/8/4K3 w - - 0 1"), p.knight_outpost);
+        assert_eq!(white("7k/5p2/8/4N3/3P4/8/8/4K3 w - - 0 1"), 0);
+        assert_eq!(white("7k/8/8/4N3/8/8/8/4K3 w - - 0 1"), 0);
+
+        // Bispo ruim: d2 é da cor do bispo de c1, e2 não
+        assert_eq!(white("7k/8/8/8/8/8/3PP3/2B1K3 w - - 0 1"), -p.bad_bishop_pawn);
+
+        // Bispo preso em a7 pelo peão de b6
+        assert_eq!(white("7k/B7/1p6/8/8/8/8/4K3 w - - 0 1"), -p.trapped_bishop);
+        assert_eq!(terms("4k3/8/8/8/8/1P6/b7/7K b - - 0 1", Color::Black), -p.trapped_bishop);
+
+        // Torre presa pelo rei que já perdeu o roque
+        assert_eq!(white("7k/8/8/8/8/8/7P/5K1R w - - 0 1"), -p.trapped_rook);
+        assert_eq!(white("7k/8/8/8/8/8/7P/4K2R w K - 0 1"), 0);
+    }
+
+    #[test]
+    fn pst_and_phase_interpolation() {
+        let params = Params::default();
+        let trace = |fen: &str| evaluate_terms(&Board::from_str(fen).unwrap(), &params);
+        let (white, black) = (Color::White.to_index(), Color::Black.to_index());
+
+        // Posição inicial: meio-jogo puro, tabelas simétricas
+        let start = evaluate_terms(&Board::default(), &params);
+        assert_eq!(start.game_phase, PHASE_MAX);
+        assert_eq!(start.phase, [0, 0]);
+        assert_eq!(start.pst[white], start.pst[black]);
+
+        // Só reis e peões: vale a tabela de final. Rei em e4 e peão em e5
+        let end = trace("8/8/8/4P3/4K3/8/8/k7 w - - 0 1");
+        assert_eq!(end.game_phase, 0);
+        let (king, pawn) = (params.king_pst[4 * 8 + 4], params.pawn_pst[3 * 8 + 4]);
+        let (king_end, pawn_end) = (params.king_pst_endgame[4 * 8 + 4], params.pawn_pst_endgame[3 * 8 + 4]);
+        assert_eq!(end.pst[white], king + pawn);
+        assert_eq!(end.pst[white] + end.phase[white], king_end + pawn_end);
+        // Rei preto em a1 é a8 do ponto de vista dele
+        assert_eq!(end.pst[black] + end.phase[black], params.king_pst_endgame[0]);
+
+        // Uma torre de cada lado: 4 de 24, a interpolação anda 20/24 do caminho
+        let rooks = trace("r7/8/8/4P3/4K3/8/k7/7R w - - 0 1");
+        assert_eq!(rooks.game_phase, 4);
+        let expected = (king_end + pawn_end - king - pawn) * 20 / 24;
+        assert_eq!(rooks.phase[white], expected);
+        // Com uma dama de promoção a mais a fase não passa do máximo
+        assert_eq!(game_phase(&Board::from_str("rnbqkbnr/pppppppp/8/8/8/8/QPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap()), PHASE_MAX);
+    }
+
+    #[test]
+    fn king_and_pawn_terms_for_both_colors() {
+        let params = Params::default();
+        let (white, black) = (Color::White.to_index(), Color::Black.to_index());
+        let trace = evaluate_terms(&Board::default(), &params);
+        assert_eq!(trace.king_safety, [params.king_home_bonus; 2]);
+        assert_eq!(trace.pawns, [8 * params.pawn_advance; 2]);
+
+        // Depois de 1. e4 as pretas jogam, mas o peão avançado das brancas conta
+        let board = Board::from_str("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1").unwrap();
+        let trace = evaluate_terms(&board, &params);
+        assert_eq!(trace.pawns[white], 10 * params.pawn_advance);
+        assert_eq!(trace.pawns[black], 8 * params.pawn_advance);
+
+        // Só quem joga pode estar em xeque; o rei fora de casa perde o bônus
+        let board = Board::from_str("4k3/8/8/8/8/8/8/4K2r w - - 0 1").unwrap();
+        let trace = evaluate_terms(&board, &params);
+        assert_eq!(trace.king_safety[white], params.king_home_bonus - params.in_check_penalty);
+        assert_eq!(trace.king_safety[black], params.king_home_bonus);
+        let trace = evaluate_terms(&Board::from_str("3k4/8/8/8/8/8/8/4K2r w - - 0 1").unwrap(), &params);
+        assert_eq!(trace.king_safety[black], 0);
+    }
+
+    #[test]
+    fn scaled_terms_are_the_evaluation() {
+        let params = Params::default();
+        let cases = [
+            ("r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3", endgame::SCALE_NORMAL),
+            // Bispos de cores opostas, um peão a mais
+            ("4k3/8/3b4/8/8/3BP3/8/4K3 b - - 0 1", 16),
+            // Peão da torre e bispo da cor errada
+            ("1k6/8/8/P7/3B4/8/8/4K3 w - - 0 1", 0),
+        ];
+        for (fen, scale) in cases {
+            let board = Board::from_str(fen).unwrap();
+            let trace = evaluate_terms(&board, &params);
+            assert_eq!(trace.scale, scale, "{}", fen);
+            assert_eq!(trace.scaled(board.side_to_move()), evaluate_board(&board, &params), "{}", fen);
+            assert_eq!(trace.scaled(Color::White), -trace.scaled(Color::Black), "{}", fen);
+        }
+    }
+}
@@ -0,0 +1,26 @@
+// Sexta geração da engine como biblioteca, para o binário `engine_sixth` e
+// para o `chazz`, que reúne todas as gerações.
+
+pub mod analyse;
+pub mod bench;
+mod book;
+mod cli;
+pub mod datagen;
+mod endgame;
+mod engine;
+pub mod epd;
+pub mod eval;
+pub mod makebook;
+mod nnue;
+mod options;
+mod params;
+pub mod pgn;
+mod polyglot;
+pub mod puzzles;
+mod rng;
+pub mod search;
+mod syzygy;
+pub mod tune;
+
+pub use engine::Sixth;
+pub use options::Options;
@@ -0,0 +1,279 @@
+// Criação de livros Polyglot a partir de partidas em PGN.
+//
+// Cada lance das primeiras `--plies` jogadas de cada partida aceita soma
+// pontos para quem o jogou: 2 pela vitória, 1 pelo empate, 0 pela derrota.
+// O peso da entrada é a soma desses pontos, então lances frequentes e bem
+// sucedidos pesam mais. Livros passados com `--merge` têm seus pesos somados.
+
+use chess::Color;
+use std::collections::HashMap;
+use std::fs;
+
+use crate::book::{Book, Entry};
+use crate::cli::{number, value};
+use crate::pgn::{parse_games, Game};
+use crate::polyglot::{encode_move, hash};
+
+struct Config {
+    plies: usize,
+    min_rating: u32,
+    results: Vec<String>,
+    min_count: u32,
+}
+
+#[derive(Default)]
+struct Stats {
+    count: u32,
+    points: u64,
+}
+
+/// Partida aceita pelos filtros de rating e resultado?
+fn accepted(game: &Game, config: &Config) -> bool {
+    let Some(result) = game.tag("Result") else {
+        return false;
+    };
+    if !config.results.iter().any(|r| r == result) {
+        return false;
+    }
+    if config.min_rating > 0 {
+        let rating = |tag| game.tag(tag).and_then(|r| r.parse::<u32>().ok()).unwrap_or(0);
+        if rating("WhiteElo") < config.min_rating || rating("BlackElo") < config.min_rating {
+            return false;
+        }
+    }
+    true
+}
+
+/// Soma os lances da partida às estatísticas.
+fn add_game(game: &Game, config: &Config, stats: &mut HashMap<(u64, u16), Stats>) {
+    let mut board = game.start;
+    let white_points = match game.tag("Result") {
+        Some("1-0") => 2,
+        Some("0-1") => 0,
+        _ => 1,
+    };
+
+    for node in game.mainline().take(config.plies) {
+        let chess_move = node.chess_move;
+        let entry = stats.entry((hash(&board), encode_move(&board, chess_move))).or_default();
+        entry.count += 1;
+        entry.points += if board.side_to_move() == Color::White { white_points } else { 2 - white_points };
+        board = board.make_move_new(chess_move);
+    }
+}
+
+/// Peso de cada entrada: os pontos dos lances vistos pelo menos `min_count`
+/// vezes, somados aos pesos das mesmas entradas nos livros de `merges`.
+fn weights(stats: HashMap<(u64, u16), Stats>, min_count: u32, merges: &[Book]) -> HashMap<(u64, u16), u64> {
+    let mut weights: HashMap<(u64, u16), u64> = stats
+        .into_iter()
+        .filter(|(_, s)| s.count >= min_count && s.points > 0)
+        .map(|(key, s)| (key, s.points))
+        .collect();
+    for book in merges {
+        for entry in book.entries() {
+            *weights.entry((entry.key, entry.code)).or_default() += entry.weight as u64;
+        }
+    }
+    weights
+}
+
+/// Entradas do livro. Os pesos do formato têm 16 bits: se algum passar disso,
+/// todos são reduzidos na mesma proporção, sem zerar nenhum.
+fn entries(weights: HashMap<(u64, u16), u64>) -> Vec<Entry> {
+    let max = weights.values().copied().max().unwrap_or(0);
+    let scale = |weight: u64| if max > u16::MAX as u64 { (weight * u16::MAX as u64 / max).max(1) } else { weight };
+    weights
+        .into_iter()
+        .filter(|&(_, weight)| weight > 0)
+        .map(|((key, code), weight)| Entry { key, code, weight: scale(weight) as u16 })
+        .collect()
+}
+
+/// Configuração, arquivo de saída, PGNs e livros a incluir; valor ausente ou
+/// inválido é erro.
+fn parse_args(args: &[String]) -> Result<(Config, String, Vec<String>, Vec<String>), String> {
+    let mut config = Config {
+        plies: 24,
+        min_rating: 0,
+        results: vec!["1-0".into(), "0-1".into(), "1/2-1/2".into()],
+        min_count: 1,
+    };
+    let mut output = String::from("book.bin");
+    let mut inputs = Vec::new();
+    let mut merges = Vec::new();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--output" => output = value(&mut args, arg)?,
+            "--plies" => config.plies = number(&mut args, arg)?,
+            "--min-rating" => config.min_rating = number(&mut args, arg)?,
+            "--result" => config.results = value(&mut args, arg)?.split(',').map(|r| r.trim().to_string()).collect(),
+            "--min-count" => config.min_count = number(&mut args, arg)?,
+            "--merge" => merges.push(value(&mut args, arg)?),
+            other if other.starts_with("--") => return Err(format!("opção desconhecida: {}", other)),
+            path => inputs.push(path.to_string()),
+        }
+    }
+    Ok((config, output, inputs, merges))
+}
+
+/// `makebook <pgn>... [--output <arquivo>] [--plies <n>] [--min-rating <n>]
+/// [--result <1-0,0-1,1/2-1/2>] [--min-count <n>] [--merge <livro>]...`
+pub fn run(args: &[String]) {
+    let (config, output, inputs, merges) = match parse_args(args) {
+        Ok(parsed) => parsed,
+        Err(e) => return eprintln!("Erro: {}", e),
+    };
+    if inputs.is_empty() && merges.is_empty() {
+        return eprintln!("Uso: makebook <pgn>... [--output <arquivo>] [--plies <n>] [--min-rating <n>] [--result <lista>] [--min-count <n>] [--merge <livro>]...");
+    }
+
+    let mut stats: HashMap<(u64, u16), Stats> = HashMap::new();
+    let (mut read, mut used) = (0, 0);
+    for path in &inputs {
+        let text = match fs::read(path) {
+            Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+            Err(e) => return eprintln!("Erro: {}: {}", path, e),
+        };
+        for game in parse_games(&text) {
+            read += 1;
+            let game = match game {
+                Ok(game) => game,
+                Err(e) => {
+                    eprintln!("Aviso: {}: partida {} ignorada: {}", path, read, e);
+                    continue;
+                }
+            };
+            if accepted(&game, &config) {
+                add_game(&game, &config, &mut stats);
+                used += 1;
+            }
+        }
+    }
+    if !inputs.is_empty() {
+        println!("Partidas lidas: {}, usadas: {}", read, used);
+    }
+
+    let mut books = Vec::new();
+    for path in &merges {
+        match Book::load(path) {
+            Ok(book) => {
+                println!("Livro {} incluído: {} entradas", path, book.len());
+                books.push(book);
+            }
+            Err(e) => return eprintln!("Erro: {}", e),
+        }
+    }
+
+    let book = Book::new(entries(weights(stats, config.min_count, &books)));
+    match book.save(&output) {
+        Ok(()) => println!("Livro salvo em {}: {} entradas", output, book.len()),
+        Err(e) => eprintln!("Erro: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chess::{Board, ChessMove};
+    use std::env;
+    use std::str::FromStr;
+
+    const PGN: &str = "[White \"A\"]\n[Black \"B\"]\n[Result \"1-0\"]\n\n1. e4 e5 2. Nf3 1-0\n\n\
+                       [Result \"0-1\"]\n\n1. e4 c5 0-1\n\n\
+                       [Result \"1/2-1/2\"]\n\n1. d4 d5 1/2-1/2\n";
+
+    // Chave Polyglot da posição inicial e código de e2e4 (origem 12, destino 28)
+    const START: u64 = 0x463B_9618_1691_FC9C;
+    const E2E4: u16 = 12 << 6 | 28;
+    // Chave depois de 1. e4 e código de c7c5
+    const AFTER_E4: u64 = 0x823C_9B50_FD11_4196;
+    const C7C5: u16 = 50 << 6 | 34;
+
+    fn config(results: &[&str]) -> Config {
+        Config { plies: 24, min_rating: 0, results: results.iter().map(|r| r.to_string()).collect(), min_count: 1 }
+    }
+
+    fn stats(config: &Config) -> HashMap<(u64, u16), Stats> {
+        let mut stats = HashMap::new();
+        for game in parse_games(PGN) {
+            let game = game.unwrap();
+            if accepted(&game, config) {
+                add_game(&game, config, &mut stats);
+            }
+        }
+        stats
+    }
+
+    #[test]
+    fn points_for_whoever_played_the_move() {
+        let stats = stats(&config(&["1-0", "0-1", "1/2-1/2"]));
+        let e4 = &stats[&(START, E2E4)];
+        // Vitória e derrota das brancas
+        assert_eq!((e4.count, e4.points), (2, 2));
+        assert_eq!(stats[&(AFTER_E4, C7C5)].points, 2);
+        assert_eq!(stats.len(), 6);
+
+        // e5 perdeu: sem pontos, fica fora do livro
+        let weights = weights(stats, 1, &[]);
+        assert_eq!(weights[&(START, E2E4)], 2);
+        assert_eq!(weights.len(), 5);
+        assert!(weights.values().all(|&w| w > 0));
+    }
+
+    #[test]
+    fn filters_by_result_and_count() {
+        let only_wins = stats(&config(&["1-0"]));
+        assert_eq!(only_wins[&(START, E2E4)].count, 1);
+        assert!(!only_wins.contains_key(&(AFTER_E4, C7C5)));
+
+        let weights = weights(stats(&config(&["1-0", "0-1", "1/2-1/2"])), 2, &[]);
+        assert_eq!(weights.into_iter().collect::<Vec<_>>(), [((START, E2E4), 2)]);
+    }
+
+    #[test]
+    fn merged_books_add_their_weights() {
+        let book = Book::new(vec![Entry { key: START, code: E2E4, weight: 5 }, Entry { key: 1, code: 2, weight: 3 }]);
+        let weights = weights(stats(&config(&["1-0"])), 1, &[book]);
+        assert_eq!(weights[&(START, E2E4)], 2 + 5);
+        assert_eq!(weights[&(1, 2)], 3);
+    }
+
+    #[test]
+    fn weights_are_scaled_to_16_bits() {
+        let weights = HashMap::from([((1, 1), 131070), ((2, 2), 65535), ((3, 3), 1)]);
+        let mut scaled: Vec<(u64, u16)> = entries(weights).iter().map(|e| (e.key, e.weight)).collect();
+        scaled.sort();
+        assert_eq!(scaled, [(1, 65535), (2, 32767), (3, 1)]);
+
+        // Abaixo do limite os pesos ficam como estão
+        let small = HashMap::from([((1, 1), 40), ((2, 2), 7)]);
+        let mut kept: Vec<u16> = entries(small).iter().map(|e| e.weight).collect();
+        kept.sort();
+        assert_eq!(kept, [7, 40]);
+    }
+
+    #[test]
+    fn saved_book_plays_the_moves() {
+        let book = Book::new(entries(weights(stats(&config(&["1-0", "0-1", "1/2-1/2"])), 1, &[])));
+        let path = env::temp_dir().join("engine_sixth_makebook_teste.bin");
+        book.save(path.to_str().unwrap()).unwrap();
+        let loaded = Book::load(path.to_str().unwrap()).unwrap();
+        assert_eq!(loaded.len(), 5);
+        let moves = loaded.moves(&Board::default());
+        assert_eq!(moves, [(ChessMove::from_str("e2e4").unwrap(), 2), (ChessMove::from_str("d2d4").unwrap(), 1)]);
+    }
+
+    #[test]
+    fn bad_or_missing_values_are_errors() {
+        let parse = |line: &str| parse_args(&line.split_whitespace().map(String::from).collect::<Vec<_>>()).map(|_| ());
+        assert!(parse("a.pgn --plies 16 --min-rating 2000 --min-count 2 --result 1-0").is_ok());
+        assert_eq!(parse("a.pgn --plies x").unwrap_err(), "valor inválido para --plies: x");
+        assert_eq!(parse("a.pgn --min-rating -1").unwrap_err(), "valor inválido para --min-rating: -1");
+        assert_eq!(parse("a.pgn --min-count").unwrap_err(), "--min-count precisa de um valor");
+        assert_eq!(parse("a.pgn --output").unwrap_err(), "--output precisa de um valor");
+        assert_eq!(parse("a.pgn --livro b.bin").unwrap_err(), "opção desconhecida: --livro");
+    }
+}
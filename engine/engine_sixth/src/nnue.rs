@@ -0,0 +1,357 @@
+// Avaliação por rede neural eficientemente atualizável (NNUE) no formato
+// HalfKP: cada entrada combina a casa do próprio rei com uma peça (exceto
+// reis) e sua casa, sempre do ponto de vista de uma das cores.
+//
+// Arquitetura: 40960 entradas -> H neurônios por perspectiva (acumulador)
+// -> ReLU limitada a [0, QA] -> concatenação [lado a mover, adversário]
+// -> 1 saída. Toda a inferência usa inteiros, sem SIMD.
+//
+// Formato do arquivo (little-endian):
+//   8 bytes   "CHZNNUE1"
+//   u32       H
+//   i16       pesos da primeira camada [40960 * H], agrupados por entrada
+//   i16       vieses da primeira camada [H]
+//   i16       pesos da saída [2 * H]
+//   i32       viés da saída
+
+use chazz_core::board::Query;
+use chess::{ChessMove, Color, File, Piece, Square};
+use std::fs;
+
+use crate::eval::{game_status, GameStatus};
+
+const MAGIC: &[u8; 8] = b"CHZNNUE1";
+const FEATURES: usize = 64 * 10 * 64;
+const QA: i32 = 255;
+const QB: i32 = 64;
+const SCALE: i32 = 400;
+
+pub struct Network {
+    hidden: usize,
+    feature_weights: Vec<i16>,
+    feature_biases: Vec<i16>,
+    output_weights: Vec<i16>,
+    output_bias: i32,
+}
+
+fn read_i16s(bytes: &[u8], offset: &mut usize, count: usize) -> Vec<i16> {
+    let values = bytes[*offset..*offset + count * 2]
+        .chunks_exact(2)
+        .map(|b| i16::from_le_bytes([b[0], b[1]]))
+        .collect();
+    *offset += count * 2;
+    values
+}
+
+impl Network {
+    pub fn load(path: &str) -> Result<Network, String> {
+        let bytes = fs::read(path).map_err(|e| format!("{}: {}", path, e))?;
+        if bytes.len() < 12 || &bytes[..8] != MAGIC {
+            return Err(format!("{}: não é uma rede NNUE válida", path));
+        }
+
+        let hidden = u32::from_le_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]) as usize;
+        let expected = 12 + (FEATURES * hidden + hidden + 2 * hidden) * 2 + 4;
+        if hidden == 0 || bytes.len() != expected {
+            return Err(format!(
+                "{}: tamanho {} não corresponde a {} neurônios ({} bytes esperados)",
+                path,
+                bytes.len(),
+                hidden,
+                expected
+            ));
+        }
+
+        let mut offset = 12;
+        let feature_weights = read_i16s(&bytes, &mut offset, FEATURES * hidden);
+        let feature_biases = read_i16s(&bytes, &mut offset, hidden);
+        let output_weights = read_i16s(&bytes, &mut offset, 2 * hidden);
+        let b = &bytes[offset..offset + 4];
+        let output_bias = i32::from_le_bytes([b[0], b[1], b[2], b[3]]);
+
+        Ok(Network { hidden, feature_weights, feature_biases, output_weights, output_bias })
+    }
+
+    pub fn hidden(&self) -> usize {
+        self.hidden
+    }
+
+    fn add_feature(&self, values: &mut [i16], feature: usize) {
+        let weights = &self.feature_weights[feature * self.hidden..(feature + 1) * self.hidden];
+        for (value, weight) in values.iter_mut().zip(weights) {
+            *value = value.wrapping_add(*weight);
+        }
+    }
+
+    fn remove_feature(&self, values: &mut [i16], feature: usize) {
+        let weights = &self.feature_weights[feature * self.hidden..(feature + 1) * self.hidden];
+        for (value, weight) in values.iter_mut().zip(weights) {
+            *value = value.wrapping_sub(*weight);
+        }
+    }
+
+    /// Recalcula do zero o acumulador de `perspective`.
+    fn refresh(&self, values: &mut [i16], board: &impl Query, perspective: Color) {
+        values.copy_from_slice(&self.feature_biases);
+        let king = board.king_square(perspective);
+        for square in board.combined() & !board.pieces(Piece::King) {
+            if let (Some(piece), Some(color)) = (board.piece_on(square), board.color_on(square)) {
+                self.add_feature(values, feature(perspective, king, piece, color, square));
+            }
+        }
+    }
+
+    /// Saída da rede para o lado a mover, em centipeões.
+    fn evaluate(&self, accumulator: &Accumulator, side_to_move: Color) -> i32 {
+        let us = &accumulator.values[side_to_move.to_index()];
+        let them = &accumulator.values[(!side_to_move).to_index()];
+        let (our_weights, their_weights) = self.output_weights.split_at(self.hidden);
+
+        let mut sum = self.output_bias as i64;
+        for (value, weight) in us.iter().zip(our_weights) {
+            sum += ((*value as i32).clamp(0, QA) * *weight as i32) as i64;
+        }
+        for (value, weight) in them.iter().zip(their_weights) {
+            sum += ((*value as i32).clamp(0, QA) * *weight as i32) as i64;
+        }
+        (sum * SCALE as i64 / (QA * QB) as i64) as i32
+    }
+}
+
+/// Casa vista por `perspective`: as pretas enxergam o tabuleiro espelhado.
+fn orient(square: Square, perspective: Color) -> usize {
+    if perspective == Color::White {
+        square.to_index()
+    } else {
+        square.to_index() ^ 56
+    }
+}
+
+fn feature(perspective: Color, king: Square, piece: Piece, color: Color, square: Square) -> usize {
+    let piece_index = piece.to_index() * 2 + usize::from(color != perspective);
+    orient(king, perspective) * 640 + piece_index * 64 + orient(square, perspective)
+}
+
+#[derive(Clone)]
+struct Accumulator {
+    values: [Vec<i16>; 2],
+}
+
+/// Acumuladores ao longo do caminho da busca: `push` aplica um lance de forma
+/// incremental sobre o topo e `pop` volta para a posição anterior.
+pub struct NnueStack<'a> {
+    network: &'a Network,
+    stack: Vec<Accumulator>,
+    top: usize,
+}
+
+impl<'a> NnueStack<'a> {
+    pub fn new(network: &'a Network, board: &impl Query) -> NnueStack<'a> {
+        let empty = Accumulator { values: [vec![0; network.hidden], vec![0; network.hidden]] };
+        let mut stack = NnueStack { network, stack: vec![empty], top: 0 };
+        for perspective in [Color::White, Color::Black] {
+            network.refresh(&mut stack.stack[0].values[perspective.to_index()], board, perspective);
+        }
+        stack
+    }
+
+    /// Atualiza os acumuladores para `board`, a posição depois do lance `m`,
+    /// que capturou `captured` (`None` também no en passant).
+    pub fn push(&mut self, m: ChessMove, captured: Option<Piece>, board: &impl Query) {
+        if self.top + 1 == self.stack.len() {
+            self.stack.push(self.stack[self.top].clone());
+        } else {
+            let (done, rest) = self.stack.split_at_mut(self.top + 1);
+            for (to, from) in rest[0].values.iter_mut().zip(&done[self.top].values) {
+                to.copy_from_slice(from);
+            }
+        }
+        self.top += 1;
+
+        let source = m.get_source();
+        let dest = m.get_dest();
+        let color = !board.side_to_move();
+        let piece = if m.get_promotion().is_some() { Piece::Pawn } else { board.piece_on(dest).unwrap() };
+
+        // Entradas removidas e adicionadas pelo lance
+        let mut removed = vec![(piece, color, source)];
+        let mut added = vec![(m.get_promotion().unwrap_or(piece), color, dest)];
+        if let Some(captured) = captured {
+            removed.push((captured, !color, dest));
+        } else if piece == Piece::Pawn && source.get_file() != dest.get_file() {
+            // En passant: o peão capturado está ao lado da casa de origem
+            removed.push((Piece::Pawn, !color, Square::make_square(source.get_rank(), dest.get_file())));
+        }
+        if piece == Piece::King && source.get_file().to_index().abs_diff(dest.get_file().to_index()) == 2 {
+            // Roque: a torre também se move
+            let (from, to) = if dest.get_file() == File::G { (File::H, File::F) } else { (File::A, File::D) };
+            removed.push((Piece::Rook, color, Square::make_square(source.get_rank(), from)));
+            added.push((Piece::Rook, color, Square::make_square(source.get_rank(), to)));
+        }
+
+        for perspective in [Color::White, Color::Black] {
+            let values = &mut self.stack[self.top].values[perspective.to_index()];
+            if piece == Piece::King && color == perspective {
+                // No HalfKP todas as entradas dependem do próprio rei
+                self.network.refresh(values, board, perspective);
+                continue;
+            }
+            let king = board.king_square(perspective);
+            for &(p, c, sq) in &removed {
+                if p != Piece::King {
+                    self.network.remove_feature(values, feature(perspective, king, p, c, sq));
+                }
+            }
+            for &(p, c, sq) in &added {
+                if p != Piece::King {
+                    self.network.add_feature(values, feature(perspective, king, p, c, sq));
+                }
+            }
+        }
+    }
+
+    pub fn pop(&mut self) {
+        self.top -= 1;
+    }
+
+    /// Avaliação da posição no topo da pilha, do ponto de vista de quem joga.
+    pub fn evaluate(&self, board: &impl Query) -> i32 {
+        match game_status(board) {
+            GameStatus::Checkmate => -30000,
+            GameStatus::Stalemate | GameStatus::InsufficientMaterial => 0,
+            GameStatus::Ongoing => self.network.evaluate(&self.stack[self.top], board.side_to_move()),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+    use chess::{Board, MoveGen};
+    use std::str::FromStr;
+
+    /// Rede pequena com pesos pseudoaleatórios, só para comparar caminhos.
+    fn network() -> Network {
+        let hidden = 8;
+        let mut rng = Rng::new(31);
+        let mut values = |count: usize, range: usize| -> Vec<i16> {
+            (0..count).map(|_| rng.below(2 * range + 1) as i16 - range as i16).collect()
+        };
+        Network {
+            hidden,
+            feature_weights: values(FEATURES * hidden, 40),
+            feature_biases: values(hidden, 100),
+            output_weights: values(2 * hidden, 64),
+            output_bias: 25,
+        }
+    }
+
+    fn refreshed(network: &Network, board: &impl Query) -> [Vec<i16>; 2] {
+        NnueStack::new(network, board).stack[0].values.clone()
+    }
+
+    /// Mesma posição com as cores trocadas e o tabuleiro espelhado.
+    fn flip(fen: &str) -> Board {
+        let fields: Vec<&str> = fen.split_whitespace().collect();
+        let swap = |text: &str| -> String {
+            text.chars().map(|c| if c.is_uppercase() { c.to_ascii_lowercase() } else { c.to_ascii_uppercase() }).collect()
+        };
+        let placement: Vec<String> = fields[0].split('/').rev().map(swap).collect();
+        let side = if fields[1] == "w" { "b" } else { "w" };
+        let mut castling: Vec<char> = swap(fields[2]).chars().collect();
+        castling.sort_by_key(|c| "KQkq-".find(*c));
+        let en_passant = match fields[3] {
+            "-" => "-".to_string(),
+            square => format!("{}{}", &square[..1], if &square[1..] == "6" { 3 } else { 6 }),
+        };
+        let fen = format!("{} {} {} {} 0 1", placement.join("/"), side, castling.iter().collect::<String>(), en_passant);
+        Board::from_str(&fen).unwrap()
+    }
+
+    // Capturas, roques dos dois lados, en passant, promoções com e sem captura
+    const POSITIONS: [&str; 6] = [
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R b KQkq - 0 1",
+        "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+        "4k3/8/8/8/3pP3/8/8/4K3 b - e3 0 1",
+        "r3k3/1P6/8/8/8/8/6p1/4K2R w K - 0 1",
+        "r3k3/1P6/8/8/8/8/6p1/4K2R b - - 0 1",
+    ];
+
+    /// Como na busca: lances feitos e desfeitos no tabuleiro de `chazz_core`.
+    #[test]
+    fn push_matches_a_full_refresh() {
+        let network = network();
+        for fen in POSITIONS {
+            let mut board = chazz_core::board::Board::from(&Board::from_str(fen).unwrap());
+            let mut stack = NnueStack::new(&network, &board);
+            for &m in board.legal_moves().iter() {
+                let captured = board.piece_on(m.get_dest());
+                board.make_move(m);
+                stack.push(m, captured, &board);
+                assert_eq!(stack.stack[stack.top].values, refreshed(&network, &board), "{} {}", fen, m);
+                assert_eq!(stack.evaluate(&board), NnueStack::new(&network, &board).evaluate(&board));
+
+                // Um nível a mais reaproveita as entradas já alocadas da pilha
+                for &reply in board.legal_moves().iter() {
+                    let captured = board.piece_on(reply.get_dest());
+                    board.make_move(reply);
+                    stack.push(reply, captured, &board);
+                    assert_eq!(stack.stack[stack.top].values, refreshed(&network, &board), "{} {} {}", fen, m, reply);
+                    board.unmake_move();
+                    stack.pop();
+                }
+                board.unmake_move();
+                stack.pop();
+            }
+            assert_eq!(stack.top, 0);
+            assert_eq!(stack.stack[0].values, refreshed(&network, &board), "{}", fen);
+        }
+    }
+
+    #[test]
+    fn special_moves_are_covered() {
+        let moves = |fen: &str| -> Vec<String> {
+            MoveGen::new_legal(&Board::from_str(fen).unwrap()).map(|m| m.to_string()).collect()
+        };
+        assert!(moves(POSITIONS[0]).contains(&"e1g1".to_string()));
+        assert!(moves(POSITIONS[0]).contains(&"e1c1".to_string()));
+        assert!(moves(POSITIONS[1]).contains(&"e8g8".to_string()));
+        assert!(moves(POSITIONS[1]).contains(&"e8c8".to_string()));
+        assert!(moves(POSITIONS[2]).contains(&"e5f6".to_string()));
+        assert!(moves(POSITIONS[3]).contains(&"d4e3".to_string()));
+        assert!(moves(POSITIONS[4]).contains(&"b7a8q".to_string()));
+        assert!(moves(POSITIONS[4]).contains(&"b7b8n".to_string()));
+        assert!(moves(POSITIONS[5]).contains(&"g2h1q".to_string()));
+    }
+
+    #[test]
+    fn evaluation_is_symmetric_under_color_flip() {
+        let network = network();
+        let mut fens = POSITIONS.to_vec();
+        fens.push("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        let mut scores = Vec::new();
+        for fen in fens {
+            let board = Board::from_str(fen).unwrap();
+            let flipped = flip(fen);
+            let score = NnueStack::new(&network, &board).evaluate(&board);
+            assert_eq!(NnueStack::new(&network, &flipped).evaluate(&flipped), score, "{}", fen);
+            scores.push(score);
+
+            // Também depois de um lance aplicado de forma incremental
+            let m = MoveGen::new_legal(&board).next().unwrap();
+            let mirrored = |s: Square| Square::make_square(chess::Rank::from_index(7 - s.get_rank().to_index()), s.get_file());
+            let flipped_move = ChessMove::new(mirrored(m.get_source()), mirrored(m.get_dest()), m.get_promotion());
+            let (after, flipped_after) = (board.make_move_new(m), flipped.make_move_new(flipped_move));
+            let mut stack = NnueStack::new(&network, &board);
+            let mut flipped_stack = NnueStack::new(&network, &flipped);
+            stack.push(m, board.piece_on(m.get_dest()), &after);
+            flipped_stack.push(flipped_move, flipped.piece_on(flipped_move.get_dest()), &flipped_after);
+            assert_eq!(stack.evaluate(&after), flipped_stack.evaluate(&flipped_after), "{} {}", fen, m);
+        }
+        // A rede de teste não devolve uma constante, senão a simetria não provaria nada
+        assert!(scores.iter().any(|&score| score != scores[0]), "{:?}", scores);
+    }
+}
@@ -0,0 +1,103 @@
+use chess::{Board, ChessMove};
+
+use crate::book::Book;
+use crate::nnue::Network;
+use crate::params::Params;
+use crate::rng::Rng;
+use crate::syzygy::Tablebases;
+
+/// Configuração da engine alterada por `setoption` e pela linha de comando.
+#[derive(Default)]
+pub struct Options {
+    pub params: Params,
+    pub network: Option<Network>,
+    pub use_nnue: bool,
+    pub tablebases: Option<Tablebases>,
+    pub weaker_opponent: bool,
+    pub book: Option<Book>,
+    pub book_depth: Option<u32>,
+    pub book_best: bool,
+}
+
+impl Options {
+    /// Opções da linha de comando: `--eval-file`, `--nnue-file` (liga
+    /// `UseNNUE`), `--syzygy-path` e `--book-file`. Arquivo ausente ou que
+    /// não carrega é erro: ninguém quer uma execução inteira com os
+    /// parâmetros errados.
+    pub fn from_args(args: &[String]) -> Result<Options, String> {
+        let mut options = Options::default();
+        let value = |flag: &str, what: &str| -> Result<Option<&String>, String> {
+            match args.iter().position(|a| a == flag) {
+                Some(i) => args.get(i + 1).map(Some).ok_or(format!("{} precisa de {}", flag, what)),
+                None => Ok(None),
+            }
+        };
+
+        // Arquivo de parâmetros passado na linha de comando: --eval-file <arquivo>
+        if let Some(path) = value("--eval-file", "um arquivo")? {
+            options.params = Params::load(path)?;
+        }
+
+        // Rede NNUE passada na linha de comando: --nnue-file <arquivo> (liga UseNNUE)
+        if let Some(path) = value("--nnue-file", "um arquivo")? {
+            options.load_network(path)?;
+            options.use_nnue = true;
+        }
+
+        // Tablebases Syzygy: --syzygy-path <diretórios>
+        if let Some(paths) = value("--syzygy-path", "um diretório")? {
+            options.load_tablebases(paths)?;
+        }
+
+        // Livro de aberturas: --book-file <arquivo>
+        if let Some(path) = value("--book-file", "um arquivo")? {
+            options.load_book(path)?;
+        }
+
+        Ok(options)
+    }
+
+    /// Rede usada na busca: só quando `UseNNUE` está ligado e há rede carregada.
+    pub fn network(&self) -> Option<&Network> {
+        self.network.as_ref().filter(|_| self.use_nnue)
+    }
+
+    /// Pontuação de um empate do ponto de vista do lado da raiz: `contempt`
+    /// sempre, mais `asymmetric_contempt` contra um adversário mais fraco.
+    pub fn draw_score(&self) -> i32 {
+        let asymmetric = if self.weaker_opponent { self.params.asymmetric_contempt } else { 0 };
+        -(self.params.contempt + asymmetric)
+    }
+
+    pub fn load_network(&mut self, path: &str) -> Result<(), String> {
+        self.network = Some(Network::load(path)?);
+        Ok(())
+    }
+
+    /// `SyzygyPath`: diretórios das tablebases; vazio desliga a consulta.
+    pub fn load_tablebases(&mut self, paths: &str) -> Result<(), String> {
+        self.tablebases = match paths {
+            "" | "<empty>" => None,
+            paths => Some(Tablebases::load(paths)?),
+        };
+        Ok(())
+    }
+
+    /// `BookFile`: livro Polyglot; vazio desliga o livro.
+    pub fn load_book(&mut self, path: &str) -> Result<(), String> {
+        self.book = match path {
+            "" | "<empty>" => None,
+            path => Some(Book::load(path)?),
+        };
+        Ok(())
+    }
+
+    /// Lance do livro para a posição, se `ply` (meios-lances desde o início da
+    /// partida) ainda estiver dentro de `BookDepth`.
+    pub fn book_move(&self, board: &Board, ply: u32, rng: &mut Rng) -> Option<ChessMove> {
+        if self.book_depth.is_some_and(|depth| ply >= depth) {
+            return None;
+        }
+        self.book.as_ref()?.choose(board, self.book_best, rng)
+    }
+}
@@ -0,0 +1,391 @@
+use std::fs;
+
+// Gera a struct `Params` com os valores padrão e o acesso por nome a cada
+// campo. Escalares e tabelas são expostos como fatias para que leitura,
+// escrita e ajuste automático tratem todos os parâmetros da mesma forma.
+macro_rules! params {
+    (
+        scalars { $($scalar:ident: $scalar_default:expr,)* }
+        tables { $($table:ident: [$len:expr] = $table_default:expr,)* }
+    ) => {
+        /// Todos os pesos da avaliação e da busca.
+        #[derive(Clone, Debug, PartialEq)]
+        pub struct Params {
+            $(pub $scalar: i32,)*
+            $(pub $table: [i32; $len],)*
+        }
+
+        impl Default for Params {
+            fn default() -> Self {
+                Params {
+                    $($scalar: $scalar_default,)*
+                    $($table: $table_default,)*
+                }
+            }
+        }
+
+        impl Params {
+            /// Nome e valores de cada parâmetro, na ordem de declaração.
+            pub fn fields(&self) -> Vec<(&'static str, &[i32])> {
+                vec![
+                    $((stringify!($scalar), std::slice::from_ref(&self.$scalar)),)*
+                    $((stringify!($table), &self.$table[..]),)*
+                ]
+            }
+
+            /// Valores de um parâmetro pelo nome, para alteração.
+            pub fn field_mut(&mut self, name: &str) -> Option<&mut [i32]> {
+                match name {
+                    $(stringify!($scalar) => Some(std::slice::from_mut(&mut self.$scalar)),)*
+                    $(stringify!($table) => Some(&mut self.$table[..]),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+params! {
+    scalars {
+        // Material (centipeões)
+        pawn_value: 100,
+        knight_value: 300,
+        bishop_value: 300,
+        rook_value: 500,
+        queen_value: 900,
+
+        // Termos gerais
+        center_bonus: 20,
+        in_check_penalty: 50,
+        king_home_bonus: 10,
+        pawn_advance: 5,
+
+        // Ameaças, em porcentagem do valor da peça atacada
+        threat_weight: 10,
+        hanging_weight: 20,
+
+        // Termos específicos das peças
+        bishop_pair: 30,
+        rook_open_file: 25,
+        rook_semi_open_file: 12,
+        queen_open_file: 8,
+        queen_semi_open_file: 4,
+        rook_seventh: 20,
+        knight_outpost: 20,
+        bishop_outpost: 10,
+        bad_bishop_pawn: 3,
+        trapped_bishop: 100,
+        trapped_rook: 50,
+
+        // Busca
+        depth: 3,
+        endgame_depth: 4,
+        endgame_pieces: 10,
+        contempt: 10,
+        asymmetric_contempt: 40,
+        order_mate: 50000,
+        order_victim: 100,
+        order_attacker: 10,
+        order_promotion: 90,
+        order_check: 300,
+        order_center: 50,
+    }
+    tables {
+        knight_mobility: [9] = [-30, -20, -5, 0, 5, 10, 15, 18, 20],
+        bishop_mobility: [14] = [-25, -10, 5, 10, 15, 20, 24, 28, 30, 33, 35, 37, 39, 40],
+        rook_mobility: [15] = [-20, -10, -5, -2, 0, 3, 6, 10, 13, 16, 18, 20, 22, 24, 25],
+        queen_mobility: [28] = [
+            -15, -10, -5, -3, 0, 2, 4, 6, 8, 10, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 22,
+            23, 23, 24, 24, 25, 25,
+        ],
+
+        // Tabelas peça-casa do ponto de vista das brancas, escritas como se vê
+        // o tabuleiro: a primeira linha é a oitava fileira. Peões e rei têm
+        // uma tabela própria para o final; nas outras peças a mesma tabela
+        // vale para as duas fases.
+        pawn_pst: [64] = [
+             0,  0,   0,   0,   0,   0,  0,  0,
+            50, 50,  50,  50,  50,  50, 50, 50,
+            10, 10,  20,  30,  30,  20, 10, 10,
+             5,  5,  10,  25,  25,  10,  5,  5,
+             0,  0,   0,  20,  20,   0,  0,  0,
+             5, -5, -10,   0,   0, -10, -5,  5,
+             5, 10,  10, -20, -20,  10, 10,  5,
+             0,  0,   0,   0,   0,   0,  0,  0,
+        ],
+        knight_pst: [64] = [
+            -50, -40, -30, -30, -30, -30, -40, -50,
+            -40, -20,   0,   0,   0,   0, -20, -40,
+            -30,   0,  10,  15,  15,  10,   0, -30,
+            -30,   5,  15,  20,  20,  15,   5, -30,
+            -30,   0,  15,  20,  20,  15,   0, -30,
+            -30,   5,  10,  15,  15,  10,   5, -30,
+            -40, -20,   0,   5,   5,   0, -20, -40,
+            -50, -40, -30, -30, -30, -30, -40, -50,
+        ],
+        bishop_pst: [64] = [
+            -20, -10, -10, -10, -10, -10, -10, -20,
+            -10,   0,   0,   0,   0,   0,   0, -10,
+            -10,   0,   5,  10,  10,   5,   0, -10,
+            -10,   5,   5,  10,  10,   5,   5, -10,
+            -10,   0,  10,  10,  10,  10,   0, -10,
+            -10,  10,  10,  10,  10,  10,  10, -10,
+            -10,   5,   0,   0,   0,   0,   5, -10,
+            -20, -10, -10, -10, -10, -10, -10, -20,
+        ],
+        rook_pst: [64] = [
+             0,  0,  0,  0,  0,  0,  0,  0,
+             5, 10, 10, 10, 10, 10, 10,  5,
+            -5,  0,  0,  0,  0,  0,  0, -5,
+            -5,  0,  0,  0,  0,  0,  0, -5,
+            -5,  0,  0,  0,  0,  0,  0, -5,
+            -5,  0,  0,  0,  0,  0,  0, -5,
+            -5,  0,  0,  0,  0,  0,  0, -5,
+             0,  0,  0,  5,  5,  0,  0,  0,
+        ],
+        queen_pst: [64] = [
+            -20, -10, -10, -5, -5, -10, -10, -20,
+            -10,   0,   0,  0,  0,   0,   0, -10,
+            -10,   0,   5,  5,  5,   5,   0, -10,
+             -5,   0,   5,  5,  5,   5,   0,  -5,
+              0,   0,   5,  5,  5,   5,   0,  -5,
+            -10,   5,   5,  5,  5,   5,   0, -10,
+            -10,   0,   5,  0,  0,   0,   0, -10,
+            -20, -10, -10, -5, -5, -10, -10, -20,
+        ],
+        king_pst: [64] = [
+            -30, -40, -40, -50, -50, -40, -40, -30,
+            -30, -40, -40, -50, -50, -40, -40, -30,
+            -30, -40, -40, -50, -50, -40, -40, -30,
+            -30, -40, -40, -50, -50, -40, -40, -30,
+            -20, -30, -30, -40, -40, -30, -30, -20,
+            -10, -20, -20, -20, -20, -20, -20, -10,
+             20,  20,   0,   0,   0,   0,  20,  20,
+             20,  30,  10,   0,   0,  10,  30,  20,
+        ],
+        pawn_pst_endgame: [64] = [
+             0,  0,  0,  0,  0,  0,  0,  0,
+            80, 80, 80, 80, 80, 80, 80, 80,
+            50, 50, 50, 50, 50, 50, 50, 50,
+            30, 30, 30, 30, 30, 30, 30, 30,
+            20, 20, 20, 20, 20, 20, 20, 20,
+            10, 10, 10, 10, 10, 10, 10, 10,
+            10, 10, 10, 10, 10, 10, 10, 10,
+             0,  0,  0,  0,  0,  0,  0,  0,
+        ],
+        king_pst_endgame: [64] = [
+            -50, -40, -30, -20, -20, -30, -40, -50,
+            -30, -20, -10,   0,   0, -10, -20, -30,
+            -30, -10,  20,  30,  30,  20, -10, -30,
+            -30, -10,  30,  40,  40,  30, -10, -30,
+            -30, -10,  30,  40,  40,  30, -10, -30,
+            -30, -10,  20,  30,  30,  20, -10, -30,
+            -30, -30,   0,   0,   0,   0, -30, -30,
+            -50, -30, -30, -30, -30, -30, -30, -50,
+        ],
+    }
+}
+
+/// Parâmetros que controlam a busca e não entram na avaliação estática.
+pub const SEARCH_PARAMS: &[&str] = &[
+    "depth",
+    "endgame_depth",
+    "endgame_pieces",
+    "contempt",
+    "asymmetric_contempt",
+    "order_mate",
+    "order_victim",
+    "order_attacker",
+    "order_promotion",
+    "order_check",
+    "order_center",
+];
+
+/// Faixas aceitas para os parâmetros que a busca usa como contagem:
+/// profundidades de pelo menos 1 e número de peças entre 0 e 32.
+const RANGES: &[(&str, i32, i32)] = &[("depth", 1, 64), ("endgame_depth", 1, 64), ("endgame_pieces", 0, 32)];
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Key(String),
+    Int(i32),
+    Symbol(char),
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() || c == ',' {
+            chars.next();
+        } else if c == '#' {
+            // Comentário TOML até o fim da linha
+            while chars.next().is_some_and(|c| c != '\n') {}
+        } else if c == '"' {
+            chars.next();
+            let key: String = chars.by_ref().take_while(|&c| c != '"').collect();
+            tokens.push(Token::Key(key));
+        } else if c == '-' || c.is_ascii_digit() {
+            let mut number = String::new();
+            number.push(c);
+            chars.next();
+            while let Some(&d) = chars.peek().filter(|d| d.is_ascii_digit()) {
+                number.push(d);
+                chars.next();
+            }
+            let value = number.parse().map_err(|_| format!("número inválido: {}", number))?;
+            tokens.push(Token::Int(value));
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let mut key = String::new();
+            while let Some(&d) = chars.peek().filter(|d| d.is_ascii_alphanumeric() || **d == '_') {
+                key.push(d);
+                chars.next();
+            }
+            tokens.push(Token::Key(key));
+        } else if "=:[]{}".contains(c) {
+            tokens.push(Token::Symbol(c));
+            chars.next();
+        } else {
+            return Err(format!("caractere inesperado: {}", c));
+        }
+    }
+
+    Ok(tokens)
+}
+
+impl Params {
+    /// Lê parâmetros em TOML (`chave = valor`) ou JSON (`{"chave": valor}`).
+    /// Parâmetros ausentes mantêm o valor padrão.
+    pub fn parse(text: &str) -> Result<Params, String> {
+        let mut params = Params::default();
+        let mut tokens = tokenize(text)?.into_iter().peekable();
+        let json = tokens.peek() == Some(&Token::Symbol('{'));
+        if json {
+            tokens.next();
+        }
+
+        while let Some(token) = tokens.next() {
+            let key = match token {
+                Token::Key(key) => key,
+                Token::Symbol('}') if json => break,
+                other => return Err(format!("esperado nome de parâmetro, encontrado {:?}", other)),
+            };
+            match tokens.next() {
+                Some(Token::Symbol('=')) | Some(Token::Symbol(':')) => {}
+                _ => return Err(format!("esperado '=' ou ':' depois de {}", key)),
+            }
+
+            let mut values = Vec::new();
+            match tokens.next() {
+                Some(Token::Int(value)) => values.push(value),
+                Some(Token::Symbol('[')) => loop {
+                    match tokens.next() {
+                        Some(Token::Int(value)) => values.push(value),
+                        Some(Token::Symbol(']')) => break,
+                        _ => return Err(format!("tabela mal formada em {}", key)),
+                    }
+                },
+                _ => return Err(format!("valor inválido para {}", key)),
+            }
+
+            let field = params
+                .field_mut(&key)
+                .ok_or_else(|| format!("parâmetro desconhecido: {}", key))?;
+            if field.len() != values.len() {
+                return Err(format!(
+                    "{} espera {} valores, encontrados {}",
+                    key,
+                    field.len(),
+                    values.len()
+                ));
+            }
+            field.copy_from_slice(&values);
+        }
+
+        params.validate()?;
+        Ok(params)
+    }
+
+    /// Confere as faixas de RANGES.
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, values) in self.fields() {
+            if let Some(&(_, min, max)) = RANGES.iter().find(|(range, _, _)| *range == name) {
+                if let Some(value) = values.iter().find(|value| !(min..=max).contains(*value)) {
+                    return Err(format!("{} fora da faixa {}..={}: {}", name, min, max, value));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn load(path: &str) -> Result<Params, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+        Params::parse(&text)
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        fs::write(path, self.to_toml()).map_err(|e| format!("{}: {}", path, e))
+    }
+
+    pub fn to_toml(&self) -> String {
+        let mut out = String::new();
+        for (name, values) in self.fields() {
+            out.push_str(&format!("{} = {}\n", name, format_values(values)));
+        }
+        out
+    }
+
+    pub fn to_json(&self) -> String {
+        let fields: Vec<String> = self
+            .fields()
+            .into_iter()
+            .map(|(name, values)| format!("  \"{}\": {}", name, format_values(values)))
+            .collect();
+        format!("{{\n{}\n}}\n", fields.join(",\n"))
+    }
+}
+
+fn format_values(values: &[i32]) -> String {
+    if values.len() == 1 {
+        values[0].to_string()
+    } else {
+        let items: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+        format!("[{}]", items.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toml_and_json_round_trip() {
+        let mut params = Params { knight_value: 315, contempt: -5, ..Params::default() };
+        params.rook_mobility[3] = 7;
+        assert_eq!(Params::parse(&params.to_toml()).unwrap(), params);
+        assert_eq!(Params::parse(&params.to_json()).unwrap(), params);
+        // Parâmetros ausentes ficam com o padrão; comentários são ignorados
+        let partial = Params::parse("# só o bispo\nbishop_value = 330\n").unwrap();
+        assert_eq!(partial, Params { bishop_value: 330, ..Params::default() });
+    }
+
+    #[test]
+    fn rejects_bad_input() {
+        assert!(Params::parse("unknown_param = 3").is_err());
+        assert!(Params::parse("knight_mobility = [1, 2, 3]").is_err());
+        assert!(Params::parse("knight_value 300").is_err());
+        assert!(Params::parse("knight_value = [1, 2").is_err());
+        assert!(Params::parse("{\"knight_value\": ?}").is_err());
+        assert!(Params::parse("knight_value = 99999999999").is_err());
+    }
+
+    #[test]
+    fn rejects_out_of_range_search_params() {
+        assert!(Params::parse("depth = -1").is_err());
+        assert!(Params::parse("depth = 0").is_err());
+        assert!(Params::parse("endgame_depth = 0").is_err());
+        assert!(Params::parse("endgame_pieces = -3").is_err());
+        assert!(Params::parse("depth = 5\nendgame_pieces = 0").is_ok());
+    }
+}
@@ -0,0 +1,558 @@
+// Partidas em PGN: leitura de etiquetas, lances em SAN, comentários, NAGs e
+// variantes para uma árvore de lances, e escrita de volta em PGN.
+//
+// Cada nó guarda a posição antes do lance e o lance; `children[0]` continua
+// a linha e os demais filhos são variantes (alternativas a `children[0]`).
+
+use chess::{Board, ChessMove, Color};
+use std::fs;
+use std::io::{self, Write};
+use std::str::FromStr;
+
+use chazz_core::notation::{parse_move, to_san};
+
+pub struct Node {
+    pub board: Board,
+    pub chess_move: ChessMove,
+    pub nags: Vec<u8>,
+    /// Comentário antes do lance (início de partida ou de variante)
+    pub comment_before: Option<String>,
+    pub comment: Option<String>,
+    pub children: Vec<Node>,
+}
+
+pub struct Game {
+    pub tags: Vec<(String, String)>,
+    pub start: Board,
+    /// Número do lance da posição inicial (campo da FEN)
+    pub start_move: u32,
+    pub children: Vec<Node>,
+    pub result: String,
+}
+
+impl Game {
+    pub fn new(start: Board) -> Game {
+        Game { tags: Vec::new(), start, start_move: 1, children: Vec::new(), result: "*".to_string() }
+    }
+
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags.iter().find(|(tag, _)| tag == name).map(|(_, value)| value.as_str())
+    }
+
+    /// Nós da linha principal, do primeiro lance ao último.
+    pub fn mainline(&self) -> impl Iterator<Item = &Node> {
+        std::iter::successors(self.children.first(), |node| node.children.first())
+    }
+}
+
+impl Node {
+    pub fn new(board: Board, chess_move: ChessMove) -> Node {
+        Node { board, chess_move, nags: Vec::new(), comment_before: None, comment: None, children: Vec::new() }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Tag(String, String),
+    Comment(String),
+    Nag(u8),
+    Open,
+    Close,
+    Result(String),
+    San(String),
+}
+
+/// `[Nome "valor"]`
+fn parse_tag(line: &str) -> Option<(String, String)> {
+    let inner = line.strip_prefix('[')?.strip_suffix(']')?;
+    let (name, value) = inner.split_once(char::is_whitespace)?;
+    let value = value.trim().strip_prefix('"')?.strip_suffix('"')?;
+    Some((name.to_string(), value.replace("\\\"", "\"").replace("\\\\", "\\")))
+}
+
+/// Anotações de sufixo e seus NAGs equivalentes.
+fn suffix_nag(suffix: &str) -> Option<u8> {
+    match suffix {
+        "!" => Some(1),
+        "?" => Some(2),
+        "!!" => Some(3),
+        "??" => Some(4),
+        "!?" => Some(5),
+        "?!" => Some(6),
+        _ => None,
+    }
+}
+
+fn tokenize(text: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    let mut line_start = true;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => line_start = true,
+            // Linhas de escape
+            '%' if line_start => {
+                chars.by_ref().take_while(|&c| c != '\n').for_each(drop);
+            }
+            '[' => {
+                let tag: String = chars.by_ref().take_while(|&c| c != ']').collect();
+                if let Some((name, value)) = parse_tag(&format!("[{}]", tag)) {
+                    tokens.push(Token::Tag(name, value));
+                }
+                line_start = false;
+            }
+            '{' => {
+                let comment: String = chars.by_ref().take_while(|&c| c != '}').collect();
+                tokens.push(Token::Comment(comment.split_whitespace().collect::<Vec<_>>().join(" ")));
+                line_start = false;
+            }
+            ';' => {
+                let comment: String = chars.by_ref().take_while(|&c| c != '\n').collect();
+                tokens.push(Token::Comment(comment.trim().to_string()));
+                line_start = true;
+            }
+            '(' => tokens.push(Token::Open),
+            ')' => tokens.push(Token::Close),
+            c if c.is_whitespace() => {}
+            c => {
+                let mut word = c.to_string();
+                while let Some(&next) = chars.peek() {
+                    if next.is_whitespace() || "{}();[".contains(next) {
+                        break;
+                    }
+                    word.push(next);
+                    chars.next();
+                }
+                line_start = false;
+                push_word(&mut tokens, &word);
+            }
+        }
+    }
+    tokens
+}
+
+fn push_word(tokens: &mut Vec<Token>, word: &str) {
+    if matches!(word, "1-0" | "0-1" | "1/2-1/2" | "*") {
+        return tokens.push(Token::Result(word.to_string()));
+    }
+    if let Some(nag) = word.strip_prefix('$') {
+        if let Ok(nag) = nag.parse() {
+            tokens.push(Token::Nag(nag));
+        }
+        return;
+    }
+    if let Some(nag) = suffix_nag(word) {
+        return tokens.push(Token::Nag(nag));
+    }
+
+    // Número do lance ("12." ou "12...") colado ou não ao lance
+    let word = match word.rfind('.') {
+        Some(i) if word[..i].chars().all(|c| c.is_ascii_digit() || c == '.') => &word[i + 1..],
+        _ if word.chars().all(|c| c.is_ascii_digit()) => "",
+        _ => word,
+    };
+    let san = word.trim_end_matches(['!', '?']);
+    if !san.is_empty() {
+        tokens.push(Token::San(san.to_string()));
+        if let Some(nag) = suffix_nag(&word[san.len()..]) {
+            tokens.push(Token::Nag(nag));
+        }
+    }
+}
+
+/// Linha lida antes de virar árvore: cada lance com suas variantes.
+struct Step {
+    node: Node,
+    variations: Vec<Vec<Step>>,
+}
+
+/// Transforma a linha em filhos: o primeiro lance continua e suas variantes
+/// viram irmãos.
+fn build(line: Vec<Step>) -> Vec<Node> {
+    let mut children = Vec::new();
+    for step in line.into_iter().rev() {
+        let mut node = step.node;
+        node.children = children;
+        let mut siblings = vec![node];
+        for variation in step.variations {
+            siblings.extend(build(variation));
+        }
+        children = siblings;
+    }
+    children
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    /// Lê lances a partir de `board` até `)`, resultado, etiqueta ou fim.
+    fn line(&mut self, mut board: Board) -> Result<Vec<Step>, String> {
+        let mut line: Vec<Step> = Vec::new();
+        let mut pending_comment: Option<String> = None;
+
+        loop {
+            match self.peek() {
+                None | Some(Token::Close) | Some(Token::Result(_)) | Some(Token::Tag(..)) => break,
+                Some(Token::Open) => {
+                    self.pos += 1;
+                    let Some(last) = line.last_mut() else {
+                        return Err("variante antes de qualquer lance".to_string());
+                    };
+                    let variation = self.line(last.node.board)?;
+                    if self.peek() == Some(&Token::Close) {
+                        self.pos += 1;
+                    }
+                    if !variation.is_empty() {
+                        last.variations.push(variation);
+                    }
+                }
+                Some(Token::Comment(comment)) => {
+                    let comment = comment.clone();
+                    self.pos += 1;
+                    match line.last_mut() {
+                        Some(last) if last.variations.is_empty() => {
+                            let text = last.node.comment.take().map_or(comment.clone(), |c| format!("{} {}", c, comment));
+                            last.node.comment = Some(text);
+                        }
+                        _ => pending_comment = Some(comment),
+                    }
+                }
+                Some(Token::Nag(nag)) => {
+                    let nag = *nag;
+                    self.pos += 1;
+                    if let Some(last) = line.last_mut() {
+                        last.node.nags.push(nag);
+                    }
+                }
+                Some(Token::San(san)) => {
+                    let chess_move = parse_move(&board, san)?;
+                    self.pos += 1;
+                    let mut node = Node::new(board, chess_move);
+                    node.comment_before = pending_comment.take();
+                    board = board.make_move_new(chess_move);
+                    line.push(Step { node, variations: Vec::new() });
+                }
+            }
+        }
+        Ok(line)
+    }
+
+    fn game(&mut self) -> Option<Result<Game, String>> {
+        let mut tags = Vec::new();
+        while let Some(Token::Tag(name, value)) = self.peek() {
+            tags.push((name.clone(), value.clone()));
+            self.pos += 1;
+        }
+        if tags.is_empty() && self.peek().is_none() {
+            return None;
+        }
+
+        let mut game = Game::new(Board::default());
+        game.tags = tags;
+        if let Some(fen) = game.tag("FEN").map(str::to_string) {
+            match Board::from_str(&fen) {
+                Ok(board) => game.start = board,
+                Err(_) => return Some(Err(self.skip_game(format!("FEN inválida: {}", fen)))),
+            }
+            game.start_move = fen.split_whitespace().nth(5).and_then(|n| n.parse().ok()).unwrap_or(1);
+        }
+
+        match self.line(game.start) {
+            Ok(line) => game.children = build(line),
+            Err(e) => return Some(Err(self.skip_game(e))),
+        }
+        // Parênteses sobrando e o resultado encerram a partida
+        while self.peek() == Some(&Token::Close) {
+            self.pos += 1;
+        }
+        if let Some(Token::Result(result)) = self.peek() {
+            game.result = result.clone();
+            self.pos += 1;
+        } else if let Some(result) = game.tag("Result") {
+            game.result = result.to_string();
+        }
+        Some(Ok(game))
+    }
+
+    /// Pula o resto da partida com erro e devolve a mensagem.
+    fn skip_game(&mut self, error: String) -> String {
+        while let Some(token) = self.tokens.get(self.pos) {
+            self.pos += 1;
+            if matches!(token, Token::Result(_)) {
+                break;
+            }
+            if matches!(self.peek(), Some(Token::Tag(..))) && !matches!(token, Token::Tag(..)) {
+                break;
+            }
+        }
+        error
+    }
+}
+
+/// Todas as partidas do texto, na ordem; as que têm erro vêm como `Err`.
+pub fn parse_games(text: &str) -> Vec<Result<Game, String>> {
+    let mut parser = Parser { tokens: tokenize(text), pos: 0 };
+    std::iter::from_fn(|| parser.game()).collect()
+}
+
+/// Quebra as palavras em linhas de até 80 caracteres, com os parênteses das
+/// variantes colados aos lances.
+fn wrap(words: &[String]) -> String {
+    let mut merged: Vec<String> = Vec::new();
+    let mut open = 0;
+    for word in words {
+        match word.as_str() {
+            "(" => open += 1,
+            ")" => merged.last_mut().unwrap().push(')'),
+            _ => {
+                merged.push(format!("{}{}", "(".repeat(open), word));
+                open = 0;
+            }
+        }
+    }
+
+    let mut text = String::new();
+    let mut width = 0;
+    for word in &merged {
+        if width > 0 && width + 1 + word.len() > 80 {
+            text.push('\n');
+            width = 0;
+        } else if width > 0 {
+            text.push(' ');
+            width += 1;
+        }
+        text.push_str(word);
+        width += word.len();
+    }
+    text
+}
+
+struct Writer {
+    words: Vec<String>,
+    start_move: u32,
+    start_black: bool,
+}
+
+impl Writer {
+    fn move_number(&self, ply: u32) -> u32 {
+        self.start_move + (ply + u32::from(self.start_black)) / 2
+    }
+
+    /// Um lance, com número quando é das brancas ou quando a linha recomeça.
+    fn node(&mut self, node: &Node, ply: u32, show_number: bool) {
+        if let Some(comment) = &node.comment_before {
+            self.words.push(format!("{{{}}}", comment));
+        }
+        let white = node.board.side_to_move() == Color::White;
+        if white {
+            self.words.push(format!("{}.", self.move_number(ply)));
+        } else if show_number || node.comment_before.is_some() {
+            self.words.push(format!("{}...", self.move_number(ply)));
+        }
+        self.words.push(to_san(&node.board, node.chess_move));
+        for nag in &node.nags {
+            self.words.push(format!("${}", nag));
+        }
+        if let Some(comment) = &node.comment {
+            self.words.push(format!("{{{}}}", comment));
+        }
+    }
+
+    /// Filhos de uma posição: a continuação, suas variantes e o resto da linha.
+    fn children(&mut self, mut children: &[Node], mut ply: u32, mut show_number: bool) {
+        while let Some(main) = children.first() {
+            self.node(main, ply, show_number);
+            show_number = main.comment.is_some();
+            for variation in &children[1..] {
+                self.words.push("(".to_string());
+                self.node(variation, ply, true);
+                self.children(&variation.children, ply + 1, variation.comment.is_some());
+                self.words.push(")".to_string());
+                show_number = true;
+            }
+            children = &main.children;
+            ply += 1;
+        }
+    }
+}
+
+/// Partida em PGN: etiquetas, linha em branco, lances e resultado.
+pub fn write_game(game: &Game) -> String {
+    let mut text = String::new();
+    for (name, value) in &game.tags {
+        text.push_str(&format!("[{} \"{}\"]\n", name, value.replace('\\', "\\\\").replace('"', "\\\"")));
+    }
+    text.push('\n');
+
+    let mut writer = Writer {
+        words: Vec::new(),
+        start_move: game.start_move,
+        start_black: game.start.side_to_move() == Color::Black,
+    };
+    writer.children(&game.children, 0, true);
+    writer.words.push(game.result.clone());
+    text.push_str(&wrap(&writer.words));
+    text.push_str("\n\n");
+    text
+}
+
+/// `pgn <arquivo>... [--output <arquivo>]`: lê as partidas, aponta as que
+/// têm erro e reescreve as demais em PGN normalizado (stdout por padrão).
+pub fn run(args: &[String]) {
+    let mut inputs = Vec::new();
+    let mut output = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--output" => output = args.next().cloned(),
+            other if other.starts_with("--") => return eprintln!("Erro: opção desconhecida: {}", other),
+            path => inputs.push(path.to_string()),
+        }
+    }
+    if inputs.is_empty() {
+        return eprintln!("Uso: pgn <arquivo>... [--output <arquivo>]");
+    }
+
+    let mut text = String::new();
+    let (mut read, mut failed) = (0, 0);
+    for path in &inputs {
+        let contents = match fs::read(path) {
+            Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+            Err(e) => return eprintln!("Erro: {}: {}", path, e),
+        };
+        for game in parse_games(&contents) {
+            read += 1;
+            match game {
+                Ok(game) => text.push_str(&write_game(&game)),
+                Err(e) => {
+                    failed += 1;
+                    eprintln!("Aviso: {}: partida {} ignorada: {}", path, read, e);
+                }
+            }
+        }
+    }
+
+    match output {
+        Some(path) => match fs::write(&path, &text) {
+            Ok(()) => eprintln!("{} partidas salvas em {}", read - failed, path),
+            Err(e) => eprintln!("Erro: {}: {}", path, e),
+        },
+        None => {
+            print!("{}", text);
+            io::stdout().flush().unwrap();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_one(text: &str) -> Game {
+        let mut games = parse_games(text);
+        assert_eq!(games.len(), 1);
+        games.remove(0).unwrap()
+    }
+
+    fn mainline_san(game: &Game) -> Vec<String> {
+        game.mainline().map(|node| to_san(&node.board, node.chess_move)).collect()
+    }
+
+    /// Ler, escrever e ler de novo dá a mesma partida e o mesmo texto.
+    fn round_trip(text: &str) -> Game {
+        let game = parse_one(text);
+        let written = write_game(&game);
+        let again = parse_one(&written);
+        assert_eq!(mainline_san(&game), mainline_san(&again));
+        assert_eq!(written, write_game(&again));
+        again
+    }
+
+    #[test]
+    fn castling_both_sides() {
+        let game = round_trip(
+            "[Event \"Roques\"]\n\n1. d4 d5 2. Nc3 Nf6 3. Bf4 e6 4. Qd2 Be7 5. O-O-O O-O 6. e3 c5 *",
+        );
+        let san = mainline_san(&game);
+        assert_eq!(san[8], "O-O-O");
+        assert_eq!(san[9], "O-O");
+    }
+
+    #[test]
+    fn promotions_with_capture_and_check() {
+        let text = "[FEN \"1n2k3/P7/8/8/8/8/8/4K3 w - - 0 40\"]\n\n40. axb8=Q+ Kd7 41. Qb5+ *";
+        let game = round_trip(text);
+        assert_eq!(mainline_san(&game), ["axb8=Q+", "Kd7", "Qb5+"]);
+        assert!(write_game(&game).contains("40. axb8=Q+ Kd7 41. Qb5+ *"));
+
+        // Subpromoção, com e sem "="
+        let game = round_trip("[FEN \"8/5P1k/8/8/8/8/8/4K3 w - - 0 1\"]\n\n1. f8N+ *");
+        assert_eq!(mainline_san(&game), ["f8=N+"]);
+    }
+
+    #[test]
+    fn ambiguous_knights() {
+        // Cavalos em b1 e f3 chegam a d2: desambigua pela coluna
+        let fen = "4k3/8/8/8/8/5N2/8/1N2K3 w - - 0 1";
+        let game = round_trip(&format!("[FEN \"{}\"]\n\n1. Nbd2 Kd7 2. Nd4 *", fen));
+        assert_eq!(mainline_san(&game), ["Nbd2", "Kd7", "Nd4"]);
+        let game = round_trip(&format!("[FEN \"{}\"]\n\n1. Nfd2 *", fen));
+        assert_eq!(mainline_san(&game), ["Nfd2"]);
+
+        // Mesma coluna: desambigua pela fileira
+        let fen = "4k3/8/8/8/8/5N2/8/5NK1 w - - 0 1";
+        let game = round_trip(&format!("[FEN \"{}\"]\n\n1. N1d2 *", fen));
+        assert_eq!(mainline_san(&game), ["N1d2"]);
+
+        // Três cavalos: coluna e fileira
+        let fen = "4k3/8/8/8/8/5N2/7K/1N3N2 w - - 0 1";
+        let game = round_trip(&format!("[FEN \"{}\"]\n\n1. Nf1d2 *", fen));
+        assert_eq!(mainline_san(&game), ["Nf1d2"]);
+        let game = round_trip(&format!("[FEN \"{}\"]\n\n1. N3d2 *", fen));
+        assert_eq!(mainline_san(&game), ["N3d2"]);
+    }
+
+    #[test]
+    fn comments_nags_and_variations() {
+        let text = "[Event \"Teste\"]\n[Result \"1-0\"]\n\n\
+            {Início} 1. e4 $1 e5 {simétrico} 2. Nf3 (2. f4!? exf4 (2... d5) 3. Nf3) 2... Nc6 \
+            ; comentário de linha\n3. Bb5 a6?! 1-0\n";
+        let game = round_trip(text);
+        assert_eq!(game.result, "1-0");
+        assert_eq!(mainline_san(&game), ["e4", "e5", "Nf3", "Nc6", "Bb5", "a6"]);
+
+        let first = &game.children[0];
+        assert_eq!(first.comment_before.as_deref(), Some("Início"));
+        assert_eq!(first.nags, [1]);
+        let e5 = &first.children[0];
+        assert_eq!(e5.comment.as_deref(), Some("simétrico"));
+
+        // Variante 2. f4 com a sub-variante 2... d5
+        assert_eq!(e5.children.len(), 2);
+        let f4 = &e5.children[1];
+        assert_eq!(to_san(&f4.board, f4.chess_move), "f4");
+        assert_eq!(f4.nags, [5]);
+        assert_eq!(f4.children.len(), 2);
+
+        let written = write_game(&game);
+        assert!(written.replace('\n', " ").contains("2. Nf3 (2. f4 $5 exf4 (2... d5) 3. Nf3) 2... Nc6 {comentário de linha}"));
+    }
+
+    #[test]
+    fn several_games_and_errors() {
+        let text = "[Event \"A\"]\n\n1. e4 e5 1-0\n\n[Event \"B\"]\n\n1. e4 Ke7?? 2. Qh5 0-1\n\n[Event \"C\"]\n\n1. d4 *\n";
+        let games = parse_games(text);
+        assert_eq!(games.len(), 3);
+        assert!(games[0].is_ok());
+        assert!(games[1].is_err());
+        assert_eq!(games[2].as_ref().unwrap().tag("Event"), Some("C"));
+    }
+}
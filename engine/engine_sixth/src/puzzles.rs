@@ -0,0 +1,499 @@
+// Quebra-cabeças a partir de partidas: depois de um erro, o adversário
+// costuma ter um lance claramente melhor que todos os outros. As partidas
+// passam pela análise (`analyse`) e as posições depois de erros e erros
+// graves são as candidatas: só posições que aconteceram na partida, então
+// uma tática que ficou numa variante nunca jogada não é encontrada, nem uma
+// que os dois lados deixaram passar sem que a análise marcasse erro. Em cada
+// candidata, cada lance legal ganha uma busca própria; a posição vira
+// quebra-cabeça quando o melhor lance garante pelo menos MIN_ADVANTAGE e
+// qualquer alternativa derruba a chance de vitória em MIN_GAP pontos ou mais.
+//
+// A solução segue com a melhor resposta da busca para o adversário e
+// continua enquanto o lance seguinte de quem resolve também for único. Um
+// quebra-cabeça de mate só vale se a linha terminar em mate.
+//
+// Os temas (com os nomes do Lichess) são heurísticos: mate em N, garfo (a
+// peça que acabou de mover ataca duas peças que valem mais que ela, estão
+// sem defesa ou são o rei), peça solta (o primeiro lance captura uma peça
+// sem defensores), promoção, o tamanho da solução e a vantagem conquistada.
+
+use chess::{BitBoard, Board, ChessMove, Color, MoveGen, Piece, Square, EMPTY};
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, Write};
+
+use chazz_core::engine::Limits;
+use chazz_core::notation::san_line;
+use chazz_core::position::{fen_halfmove, fen_with_counters, is_irreversible};
+
+use crate::analyse::{review, win_percent, Judgement, MAX_CP};
+use crate::cli::{parse_search_args, SearchArgs};
+use crate::eval::{evaluate_board, game_status, material_value, piece_attacks, GameStatus};
+use crate::options::Options;
+use crate::params::Params;
+use crate::pgn::{parse_games, Game};
+use crate::search::search;
+
+/// Pontuação de mate da busca, que não guarda a distância até o mate.
+const MATE: i32 = 30000;
+/// Vantagem mínima, em centipeões, que o primeiro lance precisa garantir.
+const MIN_ADVANTAGE: i32 = 200;
+/// A partir daqui o tema é `crushing`; abaixo, `advantage`.
+const CRUSHING: i32 = 600;
+/// Queda mínima da chance de vitória (0 a 100) com o segundo melhor lance.
+const MIN_GAP: f64 = 25.0;
+const MAX_SOLVER_MOVES: usize = 6;
+const DEFAULT_DEPTH: u32 = 4;
+
+/// Pontuação de cada lance legal do ponto de vista de quem joga, do melhor
+/// para o pior. Cada filho é buscado com um lance a menos de profundidade; o
+/// mate imediato vale mais que um mate forçado mais longo.
+fn score_moves(board: &Board, options: &Options, limits: &Limits) -> Vec<(ChessMove, i32)> {
+    let child_limits = Limits { depth: limits.depth.map(|depth| depth.saturating_sub(1).max(1)), ..*limits };
+    let mut scores: Vec<(ChessMove, i32)> = MoveGen::new_legal(board)
+        .map(|chess_move| {
+            let child = board.make_move_new(chess_move);
+            let score = match game_status(&child) {
+                GameStatus::Checkmate => MATE + 1,
+                GameStatus::Ongoing => -search(&child, options, &child_limits).score,
+                _ => -evaluate_board(&child, &options.params),
+            };
+            (chess_move, score)
+        })
+        .collect();
+    scores.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+    scores
+}
+
+/// O melhor lance, se for o único bom: um mate que nenhum outro lance iguala
+/// ou uma chance de vitória pelo menos MIN_GAP acima da do segundo melhor.
+fn unique_best(scores: &[(ChessMove, i32)]) -> Option<(ChessMove, i32)> {
+    let &(best, score) = scores.first()?;
+    let Some(&(_, second)) = scores.get(1) else {
+        return Some((best, score));
+    };
+    let unique = if score >= MATE {
+        second < score
+    } else {
+        win_percent(score.clamp(-MAX_CP, MAX_CP)) - win_percent(second.clamp(-MAX_CP, MAX_CP)) >= MIN_GAP
+    };
+    unique.then_some((best, score))
+}
+
+/// Solução verificada: os lances de quem resolve são únicos em cada passo.
+#[derive(Debug)]
+pub struct Solution {
+    /// Lances alternados de quem resolve e do adversário, terminando em quem resolve
+    pub moves: Vec<ChessMove>,
+    /// Pontuação do primeiro lance para quem resolve
+    pub score: i32,
+    /// Lances de quem resolve até o mate, quando a solução termina em mate
+    pub mate: Option<usize>,
+}
+
+/// Solução a partir de `board`, se a posição for um quebra-cabeça.
+pub fn solve(board: &Board, options: &Options, limits: &Limits) -> Option<Solution> {
+    let scores = score_moves(board, options, limits);
+    // Lance forçado não é quebra-cabeça
+    if scores.len() < 2 {
+        return None;
+    }
+    let (first, score) = unique_best(&scores)?;
+    if score < MIN_ADVANTAGE {
+        return None;
+    }
+
+    let mut moves = vec![first];
+    let mut position = board.make_move_new(first);
+    while moves.len() < MAX_SOLVER_MOVES * 2 - 1 && game_status(&position) == GameStatus::Ongoing {
+        // A resposta só entra se o lance seguinte também for único
+        let Some(reply) = search(&position, options, limits).best_move else {
+            break;
+        };
+        let after_reply = position.make_move_new(reply);
+        if game_status(&after_reply) != GameStatus::Ongoing {
+            break;
+        }
+        let Some((next, _)) = unique_best(&score_moves(&after_reply, options, limits)) else {
+            break;
+        };
+        moves.extend([reply, next]);
+        position = after_reply.make_move_new(next);
+    }
+
+    let mated = game_status(&position) == GameStatus::Checkmate;
+    // Um mate forçado interrompido no meio não tem solução única
+    if score >= MATE && !mated {
+        return None;
+    }
+    Some(Solution { mate: mated.then(|| moves.len().div_ceil(2)), moves, score })
+}
+
+/// Número de peças de `color` que atacam `square`, só com as peças em `occupied`.
+fn attackers(board: &Board, square: Square, color: Color, occupied: BitBoard) -> usize {
+    (*board.color_combined(color) & occupied)
+        .filter(|&from| {
+            board
+                .piece_on(from)
+                .is_some_and(|piece| piece_attacks(piece, from, color, occupied) & BitBoard::from_square(square) != EMPTY)
+        })
+        .count()
+}
+
+/// O lance captura uma peça que ninguém defende.
+fn captures_hanging_piece(board: &Board, chess_move: ChessMove) -> bool {
+    let target = chess_move.get_dest();
+    let Some(victim) = board.piece_on(target) else {
+        return false;
+    };
+    // Sem a peça que captura, para contar defensores em raio-x
+    let occupied = *board.combined() ^ BitBoard::from_square(chess_move.get_source());
+    victim != Piece::King && attackers(board, target, !board.side_to_move(), occupied) == 0
+}
+
+/// Depois do lance, a peça em `square` ataca duas ou mais peças que valem
+/// mais que ela, estão sem defesa ou são o rei. Peões não contam como alvo.
+fn is_fork(board: &Board, square: Square, params: &Params) -> bool {
+    let Some(piece) = board.piece_on(square) else {
+        return false;
+    };
+    let color = !board.side_to_move();
+    let occupied = *board.combined();
+    let targets = piece_attacks(piece, square, color, occupied) & board.color_combined(!color);
+    let count = targets
+        .filter(|&target| {
+            let victim = board.piece_on(target).unwrap();
+            victim == Piece::King
+                || (victim != Piece::Pawn
+                    && ((piece != Piece::King && material_value(victim, params) > material_value(piece, params))
+                        || attackers(board, target, !color, occupied) == 0))
+        })
+        .count();
+    count >= 2
+}
+
+/// Temas da solução a partir de `board`.
+pub fn themes(board: &Board, solution: &Solution, params: &Params) -> Vec<String> {
+    let mut themes = Vec::new();
+    match solution.mate {
+        Some(moves) => themes.extend(["mate".to_string(), format!("mateIn{}", moves)]),
+        None if solution.score >= CRUSHING => themes.push("crushing".to_string()),
+        None => themes.push("advantage".to_string()),
+    }
+
+    let mut position = *board;
+    for (i, &chess_move) in solution.moves.iter().enumerate() {
+        let next = position.make_move_new(chess_move);
+        // Só os lances de quem resolve; o lance que dá mate não é garfo
+        if i % 2 == 0 {
+            let mut found = Vec::new();
+            if i == 0 && captures_hanging_piece(&position, chess_move) {
+                found.push("hangingPiece");
+            }
+            if next.status() != chess::BoardStatus::Checkmate && is_fork(&next, chess_move.get_dest(), params) {
+                found.push("fork");
+            }
+            if chess_move.get_promotion().is_some() {
+                found.push("promotion");
+            }
+            for theme in found {
+                if !themes.iter().any(|existing| existing == theme) {
+                    themes.push(theme.to_string());
+                }
+            }
+        }
+        position = next;
+    }
+
+    let length = match solution.moves.len().div_ceil(2) {
+        1 => "oneMove",
+        2 => "short",
+        3 => "long",
+        _ => "veryLong",
+    };
+    themes.push(length.to_string());
+    themes
+}
+
+pub struct Puzzle {
+    pub board: Board,
+    /// Contadores da FEN na partida: meios-lances da regra dos 50 e número do lance
+    pub halfmove: u32,
+    pub fullmove: u32,
+    pub solution: Solution,
+    /// Solução em SAN, com os números dos lances da partida
+    pub san: String,
+    pub themes: Vec<String>,
+    pub source: String,
+}
+
+impl Puzzle {
+    /// Identificador pela posição, o mesmo em todas as extrações.
+    pub fn id(&self) -> String {
+        format!("{:08x}", self.board.get_hash() >> 32)
+    }
+
+    /// FEN com os contadores da partida.
+    pub fn fen(&self) -> String {
+        fen_with_counters(&self.board, self.halfmove, self.fullmove)
+    }
+
+    fn uci_moves(&self) -> Vec<String> {
+        self.solution.moves.iter().map(|chess_move| chess_move.to_string()).collect()
+    }
+}
+
+/// Quebra-cabeças de uma partida: as posições da linha principal depois de
+/// erros e erros graves em que o adversário ficou com pelo menos
+/// MIN_ADVANTAGE e tem solução única.
+pub fn find_puzzles(game: &Game, options: &Options, limits: &Limits, source: &str) -> Vec<Puzzle> {
+    let reviews = review(game, options, limits);
+    let start_black = u32::from(game.start.side_to_move() == Color::Black);
+    let mut puzzles = Vec::new();
+    let mut halfmove = game.tag("FEN").map_or(0, fen_halfmove);
+    for (ply, (node, review)) in game.mainline().zip(&reviews).enumerate() {
+        halfmove = if is_irreversible(&node.board, node.chess_move) { 0 } else { halfmove + 1 };
+        let mistake = matches!(review.judgement, Some(Judgement::Mistake | Judgement::Blunder));
+        // `after` é a pontuação de quem errou; a do adversário é a oposta
+        if !mistake || -review.after < MIN_ADVANTAGE {
+            continue;
+        }
+        let board = node.board.make_move_new(node.chess_move);
+        if let Some(solution) = solve(&board, options, limits) {
+            let fullmove = game.start_move + (ply as u32 + 1 + start_black) / 2;
+            puzzles.push(Puzzle {
+                board,
+                halfmove,
+                fullmove,
+                san: san_line(&board, &solution.moves, fullmove),
+                themes: themes(&board, &solution, &options.params),
+                solution,
+                source: source.to_string(),
+            });
+        }
+    }
+    puzzles
+}
+
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+/// Uma linha por quebra-cabeça: lances em UCI e temas separados por espaço,
+/// `score` em centipeões ou `mate` em lances, o outro vazio.
+pub fn to_csv(puzzles: &[Puzzle]) -> String {
+    let mut out = String::from("id,fen,moves,san,score,mate,themes,source\n");
+    for puzzle in puzzles {
+        let (score, mate) = match puzzle.solution.mate {
+            Some(moves) => (String::new(), moves.to_string()),
+            None => (puzzle.solution.score.to_string(), String::new()),
+        };
+        let fields = [
+            puzzle.id(),
+            puzzle.fen(),
+            puzzle.uci_moves().join(" "),
+            puzzle.san.clone(),
+            score,
+            mate,
+            puzzle.themes.join(" "),
+            puzzle.source.clone(),
+        ];
+        let fields: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        out.push_str(&fields.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+fn json_string(text: &str) -> String {
+    let mut out = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn json_list(items: &[String]) -> String {
+    let items: Vec<String> = items.iter().map(|item| json_string(item)).collect();
+    format!("[{}]", items.join(", "))
+}
+
+/// Lista de objetos com os mesmos campos do CSV; `score` ou `mate` é `null`.
+pub fn to_json(puzzles: &[Puzzle]) -> String {
+    let objects: Vec<String> = puzzles
+        .iter()
+        .map(|puzzle| {
+            let (score, mate) = match puzzle.solution.mate {
+                Some(moves) => ("null".to_string(), moves.to_string()),
+                None => (puzzle.solution.score.to_string(), "null".to_string()),
+            };
+            format!(
+                "  {{\"id\": {}, \"fen\": {}, \"moves\": {}, \"san\": {}, \"score\": {}, \"mate\": {}, \"themes\": {}, \"source\": {}}}",
+                json_string(&puzzle.id()),
+                json_string(&puzzle.fen()),
+                json_list(&puzzle.uci_moves()),
+                json_string(&puzzle.san),
+                score,
+                mate,
+                json_list(&puzzle.themes),
+                json_string(&puzzle.source)
+            )
+        })
+        .collect();
+    if objects.is_empty() {
+        return "[]\n".to_string();
+    }
+    format!("[\n{}\n]\n", objects.join(",\n"))
+}
+
+/// `puzzles <pgn>... [--time <ms>] [--depth <n>] [--output <arquivo>]
+/// [--format csv|json] [--eval-file <arquivo>] [--nnue-file <arquivo>]
+/// [--syzygy-path <diretórios>]`: quebra-cabeças em stdout (ou no arquivo),
+/// em CSV ou, com `--format json` ou saída `.json`, em JSON. `--time` vale
+/// para cada lance avaliado; sem limites, profundidade DEFAULT_DEPTH.
+pub fn run(args: &[String]) {
+    let parsed = match parse_search_args(args, &["--format"]) {
+        Ok(parsed) => parsed,
+        Err(e) => return eprintln!("Erro: {}", e),
+    };
+    let json = match parsed.extra("--format") {
+        Some("json") => true,
+        Some("csv") => false,
+        Some(other) => return eprintln!("Erro: formato desconhecido: {}", other),
+        None => parsed.output.as_ref().is_some_and(|path| path.ends_with(".json")),
+    };
+    let SearchArgs { inputs, mut limits, output, .. } = parsed;
+    if inputs.is_empty() {
+        return eprintln!("Uso: puzzles <pgn>... [--time <ms>] [--depth <n>] [--output <arquivo>] [--format csv|json] [--eval-file <arquivo>] [--nnue-file <arquivo>] [--syzygy-path <diretórios>]");
+    }
+    if limits.movetime.is_none() && limits.depth.is_none() {
+        limits.depth = Some(DEFAULT_DEPTH);
+    }
+    // Como na análise, o empate vale zero para os dois lados
+    let mut options = match Options::from_args(args) {
+        Ok(options) => options,
+        Err(e) => return eprintln!("Erro: {}", e),
+    };
+    options.params.contempt = 0;
+
+    let mut puzzles = Vec::new();
+    let mut seen = HashSet::new();
+    let mut read = 0;
+    for path in &inputs {
+        let contents = match fs::read(path) {
+            Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+            Err(e) => return eprintln!("Erro: {}: {}", path, e),
+        };
+        for game in parse_games(&contents) {
+            read += 1;
+            let game = match game {
+                Ok(game) => game,
+                Err(e) => {
+                    eprintln!("Aviso: {}: partida {} ignorada: {}", path, read, e);
+                    continue;
+                }
+            };
+            let players = format!("{} x {}", game.tag("White").unwrap_or("?"), game.tag("Black").unwrap_or("?"));
+            let source = format!("{} ({} #{})", players, path, read);
+            let found = find_puzzles(&game, &options, &limits, &source);
+            // A mesma posição em várias partidas vira um quebra-cabeça só
+            let new: Vec<Puzzle> = found.into_iter().filter(|puzzle| seen.insert(puzzle.board.get_hash())).collect();
+            eprintln!("Partida {}: {}, {} quebra-cabeças", read, players, new.len());
+            for puzzle in &new {
+                eprintln!("  {} {} [{}]", puzzle.id(), puzzle.san, puzzle.themes.join(" "));
+            }
+            puzzles.extend(new);
+        }
+    }
+    eprintln!("{} quebra-cabeças em {} partidas", puzzles.len(), read);
+
+    let text = if json { to_json(&puzzles) } else { to_csv(&puzzles) };
+    match output {
+        Some(path) => match fs::write(&path, &text) {
+            Ok(()) => eprintln!("Quebra-cabeças salvos em {}", path),
+            Err(e) => eprintln!("Erro: {}: {}", path, e),
+        },
+        None => {
+            print!("{}", text);
+            io::stdout().flush().unwrap();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn board(fen: &str) -> Board {
+        Board::from_str(fen).unwrap()
+    }
+
+    fn limits(depth: u32) -> Limits {
+        Limits { depth: Some(depth), ..Default::default() }
+    }
+
+    fn solution_uci(solution: &Solution) -> Vec<String> {
+        solution.moves.iter().map(|chess_move| chess_move.to_string()).collect()
+    }
+
+    #[test]
+    fn back_rank_mate_in_one() {
+        let start = board("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1");
+        let options = Options::default();
+        let solution = solve(&start, &options, &limits(3)).unwrap();
+        assert_eq!(solution_uci(&solution), ["a1a8"]);
+        assert_eq!(solution.mate, Some(1));
+        assert_eq!(themes(&start, &solution, &options.params), ["mate", "mateIn1", "oneMove"]);
+    }
+
+    #[test]
+    fn knight_fork_wins_the_rook() {
+        let start = board("r3k3/8/8/1N6/8/8/PP6/4K3 w - - 0 1");
+        let options = Options::default();
+        let solution = solve(&start, &options, &limits(3)).unwrap();
+        assert_eq!(solution.moves[0].to_string(), "b5c7");
+        assert_eq!(solution.mate, None);
+        let found = themes(&start, &solution, &options.params);
+        assert!(found.contains(&"fork".to_string()), "{:?}", found);
+    }
+
+    #[test]
+    fn hanging_queen_and_quiet_positions() {
+        let start = board("4k3/8/8/3q4/8/8/3R4/4K3 w - - 0 1");
+        let options = Options::default();
+        let solution = solve(&start, &options, &limits(3)).unwrap();
+        assert_eq!(solution.moves[0].to_string(), "d2d5");
+        assert!(themes(&start, &solution, &options.params).contains(&"hangingPiece".to_string()));
+        // Na posição inicial nenhum lance se destaca
+        assert!(solve(&Board::default(), &options, &limits(2)).is_none());
+    }
+
+    #[test]
+    fn puzzle_after_the_blunder_and_export() {
+        let text = "[White \"A\"]\n[Black \"B\"]\n\n1. e4 e5 2. Qh5 Nc6 3. Bc4 Nf6 4. Qxf7# 1-0";
+        let game = parse_games(text).remove(0).unwrap();
+        let puzzles = find_puzzles(&game, &Options::default(), &limits(2), "A x B, teste");
+        let mate = puzzles.iter().find(|puzzle| puzzle.solution.mate == Some(1)).unwrap();
+        assert_eq!(mate.fen(), "r1bqkb1r/pppp1ppp/2n2n2/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - 4 4");
+        assert_eq!(mate.san, "4. Qxf7#");
+
+        let csv = to_csv(&puzzles);
+        assert!(csv.starts_with("id,fen,moves,san,score,mate,themes,source\n"));
+        assert!(csv.contains(",h5f7,4. Qxf7#,,1,mate mateIn1 oneMove,\"A x B, teste\""), "{}", csv);
+        let json = to_json(&puzzles);
+        assert!(json.contains("\"moves\": [\"h5f7\"], \"san\": \"4. Qxf7#\", \"score\": null, \"mate\": 1"), "{}", json);
+        assert_eq!(json_string("a \"b\"\n"), "\"a \\\"b\\\"\\n\"");
+    }
+}
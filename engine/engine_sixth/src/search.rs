@@ -0,0 +1,478 @@
+// Busca da sexta geração. A interface recebe posições do crate chess, mas a
+// árvore é percorrida no tabuleiro de `chazz_core`, fazendo e desfazendo os
+// lances no lugar em vez de copiar o tabuleiro a cada nó.
+
+use chess::{BitBoard, BoardStatus, ChessMove, EMPTY};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use chazz_core::board::{Board, MoveList};
+use chazz_core::engine::{Limits, SearchResult};
+use chazz_core::eval::{piece_value, CENTER_SQUARES};
+
+use crate::eval::{evaluate_board, game_status, GameStatus};
+use crate::nnue::NnueStack;
+use crate::options::Options;
+use crate::params::Params;
+use crate::syzygy::{wdl_score, Tablebases};
+
+/// Estado de uma busca: o tabuleiro em que os lances são feitos e desfeitos,
+/// parâmetros, contagem de repetições no caminho atual (semeada com as
+/// posições anteriores da partida),
+/// pontuação dos empates, variante principal por distância da raiz, limites de
+/// nós e de tempo, tablebases e, quando a NNUE está ativa, os acumuladores da rede.
+struct Search<'a> {
+    board: Board,
+    params: &'a Params,
+    draw_score: i32,
+    nnue: Option<NnueStack<'a>>,
+    tablebases: Option<&'a Tablebases>,
+    repetitions: HashMap<u64, i32>,
+    ply: usize,
+    pv: Vec<Vec<ChessMove>>,
+    nodes: u64,
+    node_limit: u64,
+    deadline: Option<Instant>,
+    stopped: bool,
+}
+
+impl<'a> Search<'a> {
+    /// Busca a partir de `board`; as posições de `history` já aconteceram na
+    /// partida, e voltar a qualquer uma delas conta como repetição.
+    fn new(board: Board, history: &[u64], options: &'a Options) -> Search<'a> {
+        let mut repetitions = HashMap::new();
+        for &hash in history {
+            *repetitions.entry(hash).or_insert(0) += 1;
+        }
+        Search {
+            nnue: options.network().map(|network| NnueStack::new(network, &board)),
+            board,
+            params: &options.params,
+            draw_score: options.draw_score(),
+            tablebases: options.tablebases.as_ref(),
+            repetitions,
+            ply: 0,
+            pv: Vec::new(),
+            nodes: 0,
+            node_limit: u64::MAX,
+            deadline: None,
+            stopped: false,
+        }
+    }
+
+    /// Avaliação do ponto de vista de quem joga, pela rede ou pela avaliação clássica.
+    fn evaluate(&self) -> i32 {
+        match &self.nnue {
+            Some(nnue) => nnue.evaluate(&self.board),
+            None => evaluate_board(&self.board, self.params),
+        }
+    }
+
+    /// Faz o lance e, com a NNUE ativa, atualiza os acumuladores.
+    fn make_move(&mut self, chess_move: ChessMove) {
+        let captured = self.board.piece_on(chess_move.get_dest());
+        self.board.make_move(chess_move);
+        if let Some(nnue) = &mut self.nnue {
+            nnue.push(chess_move, captured, &self.board);
+        }
+        self.ply += 1;
+    }
+
+    fn unmake_move(&mut self) {
+        self.ply -= 1;
+        self.board.unmake_move();
+        if let Some(nnue) = &mut self.nnue {
+            nnue.pop();
+        }
+    }
+
+    /// Nova melhor linha em `ply`: o lance seguido da linha do filho.
+    fn update_pv(&mut self, ply: usize, chess_move: ChessMove) {
+        let (line, child) = self.pv.split_at_mut(ply + 1);
+        let line = &mut line[ply];
+        line.clear();
+        line.push(chess_move);
+        line.extend_from_slice(&child[0]);
+    }
+
+    fn alpha_beta(&mut self, depth: i32, mut alpha: i32, mut beta: i32, maximizing: bool) -> i32 {
+        let params = self.params;
+        self.nodes += 1;
+        // O relógio só é consultado a cada 1024 nós
+        let out_of_time = self.nodes.is_multiple_of(1024) && self.deadline.is_some_and(|deadline| Instant::now() >= deadline);
+        if self.nodes >= self.node_limit || out_of_time {
+            self.stopped = true;
+            return 0;
+        }
+        let hash = self.board.get_hash();
+        let ply = self.ply;
+        if self.pv.len() <= ply + 1 {
+            self.pv.resize(ply + 2, Vec::new());
+        }
+        self.pv[ply].clear();
+
+        // Empates valem `draw_score` para o lado da raiz, quem quer que jogue
+        let status = game_status(&self.board);
+        let count = self.repetitions.get(&hash).cloned().unwrap_or(0);
+        if count >= 1 || status == GameStatus::Stalemate || status == GameStatus::InsufficientMaterial {
+            return self.draw_score;
+        }
+
+        // Verificar fim de jogo ou profundidade máxima
+        if depth <= 0 || status != GameStatus::Ongoing {
+            return if maximizing { self.evaluate() } else { -self.evaluate() };
+        }
+
+        // Posição nas tablebases: o resultado teórico encerra a busca
+        if let Some(wdl) = self.tablebases.and_then(|tablebases| tablebases.probe_wdl(&self.board)) {
+            let score = wdl_score(wdl);
+            if score == 0 {
+                return self.draw_score;
+            }
+            return if maximizing { score } else { -score };
+        }
+
+        self.repetitions.insert(hash, count + 1);
+
+        let moves = order_moves(&mut self.board, params);
+
+        let mut best_eval = if maximizing { -100000 } else { 100000 };
+
+        for &chess_move in moves.iter() {
+            self.make_move(chess_move);
+            let eval = self.alpha_beta(depth - 1, alpha, beta, !maximizing);
+            self.unmake_move();
+            if self.stopped {
+                break;
+            }
+
+            if (maximizing && eval > best_eval) || (!maximizing && eval < best_eval) {
+                self.update_pv(ply, chess_move);
+            }
+            if maximizing {
+                best_eval = best_eval.max(eval);
+                alpha = alpha.max(eval);
+            } else {
+                best_eval = best_eval.min(eval);
+                beta = beta.min(eval);
+            }
+
+            if beta <= alpha {
+                break; // Poda alfa-beta
+            }
+        }
+
+        // Restaurar contagem de repetições
+        self.repetitions.insert(hash, count);
+
+        best_eval
+    }
+}
+
+/// Lances legais do melhor para o pior segundo a ordenação heurística. Mates
+/// e xeques são vistos fazendo e desfazendo cada lance em `board`.
+pub fn order_moves(board: &mut Board, params: &Params) -> MoveList {
+    let mut moves = board.legal_moves();
+
+    // Ordenação mais sofisticada de movimentos
+    moves.sort_by_cached_key(|m| {
+        let mut score = 0;
+
+        // 1. Capturas (ordenadas pelo valor da peça capturada - valor da peça que captura)
+        if let Some(captured) = board.piece_on(m.get_dest()) {
+            let moving_piece = board.piece_on(m.get_source()).unwrap();
+            score += piece_value(captured) * params.order_victim - piece_value(moving_piece) * params.order_attacker;
+        }
+
+        // 2. Promoções
+        if let Some(promotion) = m.get_promotion() {
+            score += piece_value(promotion) * params.order_promotion;
+        }
+
+        // 3. Movimentos para o centro
+        if CENTER_SQUARES.contains(&m.get_dest()) {
+            score += params.order_center;
+        }
+
+        board.make_move(*m);
+        // 4. Xeque-mate (prioridade máxima) e xeques
+        if board.checkers().popcnt() > 0 {
+            score = if board.status() == BoardStatus::Checkmate { params.order_mate } else { score + params.order_check };
+        }
+        board.unmake_move();
+
+        score
+    });
+
+    moves.reverse(); // Priorizar maiores valores
+    moves
+}
+
+/// Profundidade adaptativa com base no estágio do jogo.
+fn search_depth(board: &chess::Board, params: &Params) -> i32 {
+    let piece_count = board.combined().popcnt() as usize;
+
+    // Ajustar profundidade com base no número de peças
+    // Menos peças = jogo mais avançado = pode buscar mais profundo
+    if piece_count < params.endgame_pieces as usize {
+        params.endgame_depth // Fim de jogo
+    } else {
+        params.depth // Meio de jogo e abertura
+    }
+}
+
+/// Lance que dá xeque-mate imediatamente, se houver.
+fn mate_in_one(board: &mut Board) -> Option<ChessMove> {
+    board.legal_moves().iter().copied().find(|&m| {
+        board.make_move(m);
+        let mate = board.status() == BoardStatus::Checkmate;
+        board.unmake_move();
+        mate
+    })
+}
+
+impl Search<'_> {
+    /// Raiz da busca com profundidade fixa: melhor lance e sua avaliação do
+    /// ponto de vista de quem joga; a variante principal fica em `pv[0]`. Se
+    /// o limite de nós estourar, devolve o melhor lance encontrado até ali.
+    fn root(&mut self, depth: i32) -> Option<(ChessMove, i32)> {
+        let mut best = None;
+        let mut best_value = -100000;
+        self.pv.resize(self.pv.len().max(2), Vec::new());
+        self.pv[0].clear();
+        // Voltar à posição da raiz também é repetição
+        let hash = self.board.get_hash();
+        let count = self.repetitions.get(&hash).cloned().unwrap_or(0);
+        self.repetitions.insert(hash, count + 1);
+
+        for &chess_move in self.board.legal_moves().iter() {
+            self.make_move(chess_move);
+            let eval = self.alpha_beta(depth - 1, -100000, 100000, false);
+            self.unmake_move();
+            if self.stopped {
+                break;
+            }
+
+            // Debug: descomentar para ver avaliações
+            // eprintln!("Move: {}, Eval: {}", chess_move, eval);
+
+            if eval > best_value {
+                best_value = eval;
+                best = Some((chess_move, eval));
+                self.update_pv(0, chess_move);
+            }
+        }
+
+        self.repetitions.insert(hash, count);
+        best
+    }
+}
+
+/// Lance da raiz pelas tablebases, com a pontuação correspondente.
+fn tablebase_move(board: &chess::Board, options: &Options) -> Option<(ChessMove, i32)> {
+    let tablebases = options.tablebases.as_ref()?;
+    let chess_move = tablebases.best_move(board)?;
+    let wdl = tablebases.probe_wdl(board)?;
+    Some((chess_move, wdl_score(wdl)))
+}
+
+/// Busca dentro dos limites de `go`: com `nodes` ou `movetime`, aprofundamento
+/// iterativo até gastar o limite (e até `depth`, se houver); senão,
+/// profundidade fixa, `depth` ou a adaptativa. Com a NNUE ativa, as folhas
+/// são avaliadas pela rede.
+pub fn search(board: &chess::Board, options: &Options, limits: &Limits) -> SearchResult {
+    search_with(board, &[], options, limits, &mut |_| {})
+}
+
+/// `search` numa partida em andamento: `history` são os hashes das posições
+/// anteriores desde o último lance irreversível (`Position::history`), e o
+/// contempt decide entre buscar e evitar a repetição delas.
+pub fn search_game(board: &chess::Board, history: &[u64], options: &Options, limits: &Limits) -> SearchResult {
+    search_with(board, history, options, limits, &mut |_| {})
+}
+
+/// `search_game`, chamando `on_iteration` com o resultado de cada iteração
+/// completa (e uma vez só nos atalhos do mate em um e das tablebases).
+pub fn search_with(
+    board: &chess::Board,
+    history: &[u64],
+    options: &Options,
+    limits: &Limits,
+    on_iteration: &mut dyn FnMut(&SearchResult),
+) -> SearchResult {
+    let mut tree = Board::from(board);
+
+    // Verificação especial para xeque-mate em um movimento
+    if let Some(chess_move) = mate_in_one(&mut tree) {
+        let result = SearchResult { best_move: Some(chess_move), score: 30000, depth: 1, pv: vec![chess_move], ..Default::default() };
+        on_iteration(&result);
+        return result;
+    }
+
+    // Nas tablebases, a DTZ escolhe o lance que faz progresso
+    if let Some((chess_move, score)) = tablebase_move(board, options) {
+        let result = SearchResult { best_move: Some(chess_move), score, pv: vec![chess_move], ..Default::default() };
+        on_iteration(&result);
+        return result;
+    }
+
+    let mut search = Search::new(tree, history, options);
+    let depths = if limits.nodes.is_some() || limits.movetime.is_some() {
+        search.node_limit = limits.nodes.unwrap_or(u64::MAX);
+        search.deadline = limits.movetime.map(|ms| Instant::now() + Duration::from_millis(ms));
+        1..=limits.depth.unwrap_or(64).max(1)
+    } else {
+        let depth = limits.depth.unwrap_or_else(|| search_depth(board, &options.params).max(1) as u32).max(1);
+        depth..=depth
+    };
+
+    let mut result = SearchResult::default();
+    for depth in depths {
+        let found = search.root(depth as i32);
+        // Uma iteração interrompida só serve se ainda não houver nenhuma completa
+        if let Some((chess_move, score)) = found.filter(|_| !search.stopped || result.best_move.is_none()) {
+            result = SearchResult { best_move: Some(chess_move), score, depth, nodes: search.nodes, pv: search.pv[0].clone() };
+            if !search.stopped {
+                on_iteration(&result);
+            }
+        }
+        if search.stopped {
+            break;
+        }
+    }
+    result.nodes = search.nodes;
+    result
+}
+
+/// Variante principal da busca que `go` sem limites faria, com a avaliação do
+/// ponto de vista de quem joga.
+pub fn principal_variation(board: &chess::Board, options: &Options) -> Option<(Vec<ChessMove>, i32)> {
+    let result = search(board, options, &Limits::default());
+    result.best_move.map(|_| (result.pv, result.score))
+}
+
+/// Busca por aprofundamento iterativo até gastar `max_nodes` nós. Devolve o
+/// lance da última iteração completa e sua avaliação do ponto de vista de
+/// quem joga. A contagem é por busca, então o resultado é determinístico.
+pub fn search_nodes(board: &chess::Board, options: &Options, max_nodes: u64) -> Option<(ChessMove, i32)> {
+    let result = search(board, options, &Limits { nodes: Some(max_nodes), ..Default::default() });
+    result.best_move.map(|chess_move| (chess_move, result.score))
+}
+
+/// Busca de quiescência: só capturas, com a avaliação estática como
+/// alternativa ("stand pat"). Pontuação do ponto de vista de quem joga.
+pub fn quiescence(board: &mut Board, mut alpha: i32, beta: i32, params: &Params) -> i32 {
+    let stand_pat = evaluate_board(board, params);
+    if game_status(board) != GameStatus::Ongoing || stand_pat >= beta {
+        return stand_pat;
+    }
+    alpha = alpha.max(stand_pat);
+
+    let enemies = board.color_combined(!board.side_to_move());
+    let mut captures: Vec<ChessMove> = board
+        .legal_moves()
+        .iter()
+        .copied()
+        .filter(|m| enemies & BitBoard::from_square(m.get_dest()) != EMPTY)
+        .collect();
+
+    // Vítima mais valiosa primeiro, atacante menos valioso primeiro
+    captures.sort_by_key(|m| {
+        let victim = board.piece_on(m.get_dest()).map_or(0, piece_value);
+        let attacker = board.piece_on(m.get_source()).map_or(0, piece_value);
+        -(victim * params.order_victim - attacker * params.order_attacker)
+    });
+
+    for capture in captures {
+        board.make_move(capture);
+        let score = -quiescence(board, -beta, -alpha, params);
+        board.unmake_move();
+        if score >= beta {
+            return score;
+        }
+        alpha = alpha.max(score);
+    }
+
+    alpha
+}
+
+/// Posição tranquila: sem xeque e sem capturas que mudem a avaliação estática.
+pub fn is_quiet(board: &chess::Board, params: &Params) -> bool {
+    board.checkers().popcnt() == 0
+        && game_status(board) == GameStatus::Ongoing
+        && quiescence(&mut Board::from(board), -100000, 100000, params) == evaluate_board(board, params)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::Sixth;
+    use chazz_core::engine::Engine;
+    use chazz_core::position::parse_position;
+    use std::str::FromStr;
+
+    /// Lance da sexta geração depois de `position <fen> moves <moves>`: as
+    /// posições da lista de lances são o histórico da partida.
+    fn choice_after(fen: &str, moves: &str, options: Options) -> ChessMove {
+        let position = parse_position(&format!("position {} moves {}", fen, moves), false).unwrap();
+        let mut sixth = Sixth::new(options);
+        sixth.set_position(&position);
+        sixth.search(&Limits { depth: Some(3), ..Default::default() }).best_move.unwrap()
+    }
+
+    fn contempt(contempt: i32) -> Options {
+        let mut options = Options::default();
+        options.params.contempt = contempt;
+        options
+    }
+
+    #[test]
+    fn avoids_repetition_when_ahead() {
+        // Brancas com dama a mais; Nf3 repetiria a posição depois do primeiro Nf3
+        let fen = "4k3/8/8/8/8/8/8/3QK1N1 w - - 0 1";
+        let repeated = ChessMove::from_str("g1f3").unwrap();
+        assert_ne!(choice_after(fen, "g1f3 e8f8 f3g1 f8e8", contempt(10)), repeated);
+    }
+
+    #[test]
+    fn seeks_repetition_when_behind() {
+        // Brancas com um cavalo contra a dama: o empate é o melhor resultado
+        let fen = "3qk3/8/8/8/8/8/8/4K1N1 w - - 0 1";
+        let repeated = ChessMove::from_str("g1f3").unwrap();
+        assert_eq!(choice_after(fen, "g1f3 e8f8 f3g1 f8e8", contempt(10)), repeated);
+        // Sem o histórico, Nf3 não tem nada de especial
+        assert_ne!(choice_after(fen, "", contempt(10)), repeated);
+    }
+
+    #[test]
+    fn large_contempt_refuses_the_draw() {
+        // Com contempt maior que a desvantagem, o empate vale menos que seguir jogando
+        let fen = "3qk3/8/8/8/8/8/8/4K1N1 w - - 0 1";
+        let repeated = ChessMove::from_str("g1f3").unwrap();
+        assert_ne!(choice_after(fen, "g1f3 e8f8 f3g1 f8e8", contempt(2000)), repeated);
+    }
+
+    #[test]
+    fn depth_zero_still_returns_a_move() {
+        let options = Options::default();
+        let board = chess::Board::default();
+        for limits in [
+            Limits { depth: Some(0), ..Default::default() },
+            Limits { depth: Some(0), nodes: Some(2000), ..Default::default() },
+            Limits { depth: Some(0), movetime: Some(50), ..Default::default() },
+        ] {
+            let result = search(&board, &options, &limits);
+            assert!(result.best_move.is_some(), "{:?}", limits);
+            assert_eq!(result.depth, 1);
+        }
+    }
+
+    #[test]
+    fn asymmetric_contempt_only_against_weaker_opponents() {
+        let mut options = contempt(10);
+        options.params.asymmetric_contempt = 40;
+        assert_eq!(options.draw_score(), -10);
+        options.weaker_opponent = true;
+        assert_eq!(options.draw_score(), -50);
+    }
+}
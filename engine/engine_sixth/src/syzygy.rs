@@ -0,0 +1,217 @@
+// Consulta às tablebases Syzygy. A leitura dos arquivos fica com o crate
+// shakmaty-syzygy; aqui só convertemos a posição (do crate chess ou do
+// tabuleiro da busca) para o formato dele, via FEN, e os resultados de volta.
+//
+// As posições do crate chess não guardam o contador de 50 lances, então
+// toda consulta WDL assume que a posição veio logo depois de uma captura ou
+// lance de peão.
+
+use chazz_core::board::Query;
+use chess::{Board, CastleRights, ChessMove, Color, ALL_SQUARES};
+use shakmaty::fen::Fen;
+use shakmaty::{CastlingMode, Chess};
+use shakmaty_syzygy::{Tablebase, Wdl};
+use std::env;
+use std::str::FromStr;
+
+// Vitória pela tablebase: abaixo do mate (30000) para que um mate real
+// encontrado pela busca continue preferido
+pub const TB_WIN: i32 = 25000;
+
+pub struct Tablebases {
+    tablebase: Tablebase<Chess>,
+}
+
+impl Tablebases {
+    /// Carrega as tabelas de um ou mais diretórios, separados como no PATH.
+    pub fn load(paths: &str) -> Result<Tablebases, String> {
+        let mut tablebase = Tablebase::new();
+        let mut count = 0;
+        for path in env::split_paths(paths) {
+            count += tablebase
+                .add_directory(&path)
+                .map_err(|e| format!("{}: {}", path.display(), e))?;
+        }
+        if count == 0 {
+            return Err(format!("nenhuma tabela Syzygy em {}", paths));
+        }
+        Ok(Tablebases { tablebase })
+    }
+
+    pub fn max_pieces(&self) -> usize {
+        self.tablebase.max_pieces()
+    }
+
+    /// Posição convertida para o shakmaty, se puder estar nas tabelas.
+    fn position(&self, board: &impl Query) -> Option<Chess> {
+        let castling = [Color::White, Color::Black]
+            .iter()
+            .any(|&color| board.castle_rights(color) != CastleRights::NoRights);
+        if castling || board.combined().popcnt() as usize > self.max_pieces() {
+            return None;
+        }
+        let fen = Fen::from_ascii(fen(board).as_bytes()).ok()?;
+        fen.into_position(CastlingMode::Standard).ok()
+    }
+
+    /// Resultado teórico para o lado a mover.
+    pub fn probe_wdl(&self, board: &impl Query) -> Option<Wdl> {
+        let position = self.position(board)?;
+        self.tablebase.probe_wdl_after_zeroing(&position).ok()
+    }
+
+    /// Distância até zerar o contador de 50 lances (captura ou lance de peão),
+    /// negativa quando o lado a mover perde.
+    pub fn probe_dtz(&self, board: &Board) -> Option<i32> {
+        let position = self.position(board)?;
+        let dtz = self.tablebase.probe_dtz(&position).ok()?;
+        Some(dtz.ignore_rounding().0)
+    }
+
+    /// Lance da raiz escolhido pela DTZ: preserva o resultado teórico e faz
+    /// progresso em direção ao mate.
+    pub fn best_move(&self, board: &Board) -> Option<ChessMove> {
+        let position = self.position(board)?;
+        let (best, _) = self.tablebase.best_move(&position).ok()??;
+        ChessMove::from_str(&best.to_uci(CastlingMode::Standard).to_string()).ok()
+    }
+}
+
+/// FEN de uma posição sem direito de roque, com os contadores zerados.
+fn fen(board: &impl Query) -> String {
+    let mut placement = String::new();
+    for rank in (0..8).rev() {
+        let mut empty = 0;
+        for file in 0..8 {
+            let square = ALL_SQUARES[rank * 8 + file];
+            match (board.piece_on(square), board.color_on(square)) {
+                (Some(piece), Some(color)) => {
+                    if empty > 0 {
+                        placement.push_str(&empty.to_string());
+                        empty = 0;
+                    }
+                    placement.push_str(&piece.to_string(color));
+                }
+                _ => empty += 1,
+            }
+        }
+        if empty > 0 {
+            placement.push_str(&empty.to_string());
+        }
+        if rank > 0 {
+            placement.push('/');
+        }
+    }
+    // O en passant do FEN é a casa atrás do peão que avançou duas casas
+    let (side, behind) = match board.side_to_move() {
+        Color::White => ("w", 8),
+        Color::Black => ("b", -8),
+    };
+    let en_passant = board
+        .en_passant()
+        .map_or("-".to_string(), |square| ALL_SQUARES[(square.to_index() as i32 + behind) as usize].to_string());
+    format!("{} {} - {} 0 1", placement, side, en_passant)
+}
+
+/// Pontuação da busca para um resultado da tablebase, do ponto de vista de
+/// quem joga. Vitórias e derrotas anuladas pela regra dos 50 lances contam
+/// como empate.
+pub fn wdl_score(wdl: Wdl) -> i32 {
+    match wdl {
+        Wdl::Win => TB_WIN,
+        Wdl::Loss => -TB_WIN,
+        Wdl::CursedWin | Wdl::Draw | Wdl::BlessedLoss => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::options::Options;
+    use crate::search::search;
+    use chazz_core::engine::Limits;
+    use chess::BoardStatus;
+
+    const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/syzygy");
+
+    /// Tabelas de teste; sem elas o teste falha, em vez de passar sem testar.
+    fn fixtures() -> Tablebases {
+        Tablebases::load(FIXTURES).unwrap_or_else(|e| {
+            panic!("tabelas Syzygy ausentes ({}): veja {}/README.md", e, FIXTURES)
+        })
+    }
+
+    fn board(fen: &str) -> Board {
+        Board::from_str(fen).unwrap()
+    }
+
+    #[test]
+    fn fen_of_both_boards() {
+        for fen in ["4k3/8/8/8/3pP3/8/8/4K3 b - e3 0 1", "8/8/8/4k3/8/8/8/3QK3 w - - 0 1", "8/8/8/8/8/4K3/1p6/k7 w - - 0 1"] {
+            let position = board(fen);
+            assert_eq!(super::fen(&position), fen);
+            assert_eq!(super::fen(&chazz_core::board::Board::from(&position)), fen);
+        }
+    }
+
+    #[test]
+    fn empty_directory_is_an_error() {
+        let dir = env::temp_dir().join("engine_sixth_syzygy_vazio");
+        std::fs::create_dir_all(&dir).unwrap();
+        assert!(Tablebases::load(dir.to_str().unwrap()).is_err());
+    }
+
+    #[test]
+    fn wdl_of_basic_endings() {
+        let tablebases = fixtures();
+        let cases = [
+            ("8/8/8/4k3/8/8/8/3QK3 w - - 0 1", Wdl::Win),
+            ("8/8/8/4k3/8/8/8/3QK3 b - - 0 1", Wdl::Loss),
+            ("8/8/8/4k3/8/8/8/3RK3 w - - 0 1", Wdl::Win),
+            ("8/8/8/4k3/8/8/8/3BK3 w - - 0 1", Wdl::Draw),
+            ("8/8/8/4k3/8/8/8/3NK3 w - - 0 1", Wdl::Draw),
+            // Rei adversário na frente do peão: empate; rei na sexta com oposição: vitória
+            ("4k3/8/8/8/8/8/4P3/4K3 b - - 0 1", Wdl::Draw),
+            ("4k3/8/4K3/4P3/8/8/8/8 w - - 0 1", Wdl::Win),
+        ];
+        for (fen, expected) in cases {
+            assert_eq!(tablebases.probe_wdl(&board(fen)), Some(expected), "{}", fen);
+        }
+    }
+
+    #[test]
+    fn positions_outside_the_tables() {
+        let tablebases = fixtures();
+        // Direito de roque e peças demais nunca são consultados
+        assert_eq!(tablebases.probe_wdl(&board("4k3/8/8/8/8/8/8/R3K3 w Q - 0 1")), None);
+        assert_eq!(tablebases.probe_wdl(&Board::default()), None);
+    }
+
+    #[test]
+    fn dtz_move_keeps_the_win() {
+        let tablebases = fixtures();
+        let position = board("8/8/8/4k3/8/8/8/3RK3 w - - 0 1");
+        let chess_move = tablebases.best_move(&position).unwrap();
+        let after = position.make_move_new(chess_move);
+        assert_eq!(tablebases.probe_wdl(&after), Some(Wdl::Loss));
+        assert!(tablebases.probe_dtz(&position).unwrap() > 0);
+    }
+
+    #[test]
+    fn engine_converts_krk() {
+        let tablebases = fixtures();
+        let options = Options { tablebases: Some(tablebases), ..Options::default() };
+        let mut position = board("8/8/8/4k3/8/8/8/3RK3 w - - 0 1");
+
+        for _ in 0..100 {
+            if position.status() != BoardStatus::Ongoing {
+                break;
+            }
+            let chess_move = search(&position, &options, &Limits::default()).best_move.unwrap();
+            position = position.make_move_new(chess_move);
+        }
+
+        assert_eq!(position.status(), BoardStatus::Checkmate);
+        assert_eq!(position.side_to_move(), Color::Black);
+    }
+}
@@ -0,0 +1,278 @@
+// Ajuste automático dos parâmetros da avaliação pelo método de Texel:
+// minimiza o erro quadrático entre o resultado das partidas e a avaliação
+// convertida em expectativa de pontos por uma sigmoide.
+
+use chess::{Board, Color};
+use std::fs;
+use std::str::FromStr;
+use std::thread;
+
+use crate::cli::{number, value};
+use crate::eval::evaluate_board;
+use crate::params::{Params, SEARCH_PARAMS};
+use crate::search::is_quiet;
+
+struct Sample {
+    board: Board,
+    result: f64, // Do ponto de vista das brancas: 1.0, 0.5 ou 0.0
+}
+
+/// Resultado da partida escrito na linha: `1-0`, `0-1`, `1/2-1/2` (com ou
+/// sem aspas) ou `[1.0]`, `[0.5]`, `[0.0]`.
+fn parse_result(token: &str) -> Option<f64> {
+    let token = token.trim_matches(|c| "\"[];".contains(c));
+    match token {
+        "1-0" | "1.0" | "1" => Some(1.0),
+        "0-1" | "0.0" | "0" => Some(0.0),
+        "1/2-1/2" | "0.5" => Some(0.5),
+        _ => None,
+    }
+}
+
+/// Lê uma linha `<FEN> <resultado>`; a FEN pode ter só os 4 primeiros campos.
+fn parse_sample(line: &str) -> Option<Sample> {
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    if tokens.len() < 5 {
+        return None;
+    }
+    let board = Board::from_str(&tokens[..4].join(" ")).ok()?;
+    let result = tokens[4..].iter().rev().find_map(|t| parse_result(t))?;
+    Some(Sample { board, result })
+}
+
+fn sigmoid(k: f64, score: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf(-k * score / 400.0))
+}
+
+/// Erro quadrático médio das previsões, dividindo as amostras entre threads.
+fn error(samples: &[Sample], params: &Params, k: f64) -> f64 {
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+    let chunk = samples.len().div_ceil(threads).max(1);
+
+    let total: f64 = thread::scope(|scope| {
+        let handles: Vec<_> = samples
+            .chunks(chunk)
+            .map(|part| {
+                scope.spawn(move || {
+                    part.iter()
+                        .map(|sample| {
+                            let mut score = evaluate_board(&sample.board, params) as f64;
+                            if sample.board.side_to_move() == Color::Black {
+                                score = -score;
+                            }
+                            (sample.result - sigmoid(k, score)).powi(2)
+                        })
+                        .sum::<f64>()
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).sum()
+    });
+
+    total / samples.len() as f64
+}
+
+/// Constante de escala da sigmoide que melhor explica os resultados com os
+/// parâmetros atuais (busca ternária, o erro é unimodal em K).
+fn fit_k(samples: &[Sample], params: &Params) -> f64 {
+    let (mut low, mut high) = (0.0, 5.0);
+    for _ in 0..60 {
+        let a = low + (high - low) / 3.0;
+        let b = high - (high - low) / 3.0;
+        if error(samples, params, a) < error(samples, params, b) {
+            high = b;
+        } else {
+            low = a;
+        }
+    }
+    (low + high) / 2.0
+}
+
+/// Busca local: tenta somar e subtrair `step` de cada valor e mantém o que
+/// reduzir o erro. Quando uma passada não melhora nada, o passo cai pela metade.
+fn local_search(
+    samples: &[Sample],
+    params: &mut Params,
+    k: f64,
+    mut step: i32,
+    max_passes: usize,
+    output: &str,
+) {
+    let tunable: Vec<(&'static str, usize)> = params
+        .fields()
+        .into_iter()
+        .filter(|(name, _)| !SEARCH_PARAMS.contains(name))
+        .flat_map(|(name, values)| (0..values.len()).map(move |i| (name, i)))
+        .collect();
+
+    let mut best = error(samples, params, k);
+    println!("Erro inicial: {:.6}", best);
+
+    for pass in 1..=max_passes {
+        let mut improved = false;
+
+        for &(name, i) in &tunable {
+            for delta in [step, -step] {
+                params.field_mut(name).unwrap()[i] += delta;
+                let candidate = error(samples, params, k);
+                if candidate < best {
+                    best = candidate;
+                    improved = true;
+                    break;
+                }
+                params.field_mut(name).unwrap()[i] -= delta;
+            }
+        }
+
+        println!("Passada {}: passo {}, erro {:.6}", pass, step, best);
+        if let Err(e) = params.save(output) {
+            eprintln!("Erro: {}", e);
+        }
+
+        if !improved {
+            if step == 1 {
+                break;
+            }
+            step /= 2;
+        }
+    }
+}
+
+struct Args {
+    input: Option<String>,
+    params: Params,
+    output: String,
+    step: i32,
+    passes: usize,
+    filter: bool,
+}
+
+/// Opções do `tune`; valor ausente ou inválido e opção desconhecida são erro.
+fn parse_args(args: &[String]) -> Result<Args, String> {
+    let mut parsed = Args {
+        input: None,
+        params: Params::default(),
+        output: String::from("tuned.toml"),
+        step: 8,
+        passes: 100,
+        filter: true,
+    };
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--eval-file" => parsed.params = Params::load(&value(&mut args, arg)?)?,
+            "--output" => parsed.output = value(&mut args, arg)?,
+            "--step" => parsed.step = number(&mut args, arg)?,
+            "--passes" => parsed.passes = number(&mut args, arg)?,
+            "--no-filter" => parsed.filter = false,
+            other if other.starts_with("--") => return Err(format!("opção desconhecida: {}", other)),
+            path => parsed.input = Some(path.to_string()),
+        }
+    }
+    Ok(parsed)
+}
+
+/// `tune <posições> [--eval-file <base>] [--output <arquivo>] [--step <n>]
+/// [--passes <n>] [--no-filter]`
+pub fn run(args: &[String]) {
+    let Args { input, mut params, output, step, passes, filter } = match parse_args(args) {
+        Ok(parsed) => parsed,
+        Err(e) => return eprintln!("Erro: {}", e),
+    };
+    let Some(input) = input else {
+        return eprintln!("Uso: tune <posições> [--eval-file <base>] [--output <arquivo>] [--step <n>] [--passes <n>] [--no-filter]");
+    };
+    let text = match fs::read_to_string(&input) {
+        Ok(text) => text,
+        Err(e) => return eprintln!("Erro: {}: {}", input, e),
+    };
+
+    let mut samples: Vec<Sample> = text.lines().filter_map(parse_sample).collect();
+    println!("Posições lidas: {}", samples.len());
+
+    // Descarta posições em que a quiescência muda a avaliação estática
+    if filter {
+        samples.retain(|sample| is_quiet(&sample.board, &params));
+        println!("Posições tranquilas: {}", samples.len());
+    }
+    if samples.is_empty() {
+        return eprintln!("Erro: nenhuma posição para ajustar");
+    }
+
+    let k = fit_k(&samples, &params);
+    println!("K = {:.4}", k);
+
+    local_search(&samples, &mut params, k, step.max(1), passes, &output);
+    println!("Parâmetros salvos em {}", output);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    // Brancas com um cavalo a mais, pretas a mover
+    const KNIGHT_UP: &str = "rnbqkb1r/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b KQkq -";
+
+    fn samples(lines: &[&str]) -> Vec<Sample> {
+        lines.iter().map(|line| parse_sample(line).unwrap()).collect()
+    }
+
+    #[test]
+    fn reads_positions_and_results() {
+        let sample = parse_sample("8/8/8/4k3/8/8/4P3/4K3 w - - 0 1 \"1-0\";").unwrap();
+        assert_eq!(sample.board.side_to_move(), Color::White);
+        assert_eq!(sample.result, 1.0);
+        assert_eq!(parse_sample(&format!("{} [0.5]", KNIGHT_UP)).unwrap().result, 0.5);
+        assert_eq!(parse_sample(&format!("{} 0-1", KNIGHT_UP)).unwrap().result, 0.0);
+        assert!(parse_sample(&format!("{} ?", KNIGHT_UP)).is_none());
+        assert!(parse_sample("8/8/8 w - 1-0").is_none());
+    }
+
+    #[test]
+    fn fits_k_to_the_expected_score() {
+        // Mesma posição uma vez ganha e uma empatada: o melhor K leva a
+        // sigmoide a 0.75, ou seja 10^(-K·s/400) = 1/3
+        let samples = samples(&[&format!("{} 1-0", KNIGHT_UP), &format!("{} 1/2-1/2", KNIGHT_UP)]);
+        let params = Params::default();
+        let white = -evaluate_board(&samples[0].board, &params) as f64;
+        assert!(white > 0.0);
+        let expected = 400.0 * 3f64.log10() / white;
+        let k = fit_k(&samples, &params);
+        assert!((k - expected).abs() < 1e-3, "K = {}, esperado {}", k, expected);
+        assert!((sigmoid(k, white) - 0.75).abs() < 1e-3);
+        assert!(error(&samples, &params, k) <= error(&samples, &params, k * 1.5));
+        assert!(error(&samples, &params, k) <= error(&samples, &params, k / 1.5));
+    }
+
+    #[test]
+    fn local_search_lowers_the_error_and_keeps_search_params() {
+        // O cavalo a mais só rende empates: a avaliação está otimista demais
+        let samples = samples(&[&format!("{} 1/2-1/2", KNIGHT_UP), "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 1/2-1/2"]);
+        let mut params = Params::default();
+        let k = 1.0;
+        let before = error(&samples, &params, k);
+        let output = env::temp_dir().join("engine_sixth_tune_teste.toml");
+        local_search(&samples, &mut params, k, 8, 1, output.to_str().unwrap());
+
+        assert!(error(&samples, &params, k) < before);
+        let default = Params::default();
+        for name in SEARCH_PARAMS {
+            assert_eq!(params.field_mut(name).unwrap(), default.clone().field_mut(name).unwrap(), "{}", name);
+        }
+        // A passada fica salva no arquivo de saída
+        assert_eq!(Params::load(output.to_str().unwrap()).unwrap().fields(), params.fields());
+    }
+
+    #[test]
+    fn bad_values_and_unknown_options_are_errors() {
+        let parse = |line: &str| parse_args(&line.split_whitespace().map(String::from).collect::<Vec<_>>());
+        let parsed = parse("data.txt --step 4 --passes 3 --no-filter").unwrap();
+        assert_eq!((parsed.input.as_deref(), parsed.step, parsed.passes, parsed.filter), (Some("data.txt"), 4, 3, false));
+        assert_eq!(parse("data.txt --step x").err().unwrap(), "valor inválido para --step: x");
+        assert_eq!(parse("data.txt --passes").err().unwrap(), "--passes precisa de um valor");
+        assert_eq!(parse("data.txt --output").err().unwrap(), "--output precisa de um valor");
+        assert_eq!(parse("data.txt --steps 4").err().unwrap(), "opção desconhecida: --steps");
+    }
+}
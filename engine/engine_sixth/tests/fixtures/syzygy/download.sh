@@ -0,0 +1,12 @@
+#!/bin/sh -e
+# Baixa para este diretório as tabelas Syzygy oficiais dos finais usados
+# pelos testes de src/syzygy.rs (WDL e DTZ de cada um), no lugar das geradas
+# pelo exemplo syzygy_fixtures.
+
+cd "$(dirname "$0")"
+BASE=https://tablebase.lichess.ovh/tables/standard
+
+for table in KQvK KRvK KBvK KNvK KPvK; do
+    curl -fsS -o "$table.rtbw" "$BASE/3-4-5-wdl/$table.rtbw"
+    curl -fsS -o "$table.rtbz" "$BASE/3-4-5-dtz/$table.rtbz"
+done
@@ -0,0 +1,81 @@
+// Terceira geração: minimax com poda alfa-beta sobre o material.
+
+use chazz_core::engine::{Engine, Limits, SearchResult};
+use chazz_core::eval::material;
+use chazz_core::position::Position;
+use chess::{Board, ChessMove, MoveGen};
+
+const MAX_DEPTH: u8 = 4; 
+
+
+fn minimax(board: &Board, depth: u8, mut alpha: i32, mut beta: i32, maximizing: bool) -> i32 {
+    if depth == 0 {
+        return material(board);
+    }
+
+    let movegen = MoveGen::new_legal(board);
+    if maximizing {
+        let mut max_eval = -9999;
+        for m in movegen {
+            let new_board = board.make_move_new(m);
+            let eval = minimax(&new_board, depth - 1, alpha, beta, false);
+            max_eval = max_eval.max(eval);
+            alpha = alpha.max(eval);
+            if beta <= alpha {
+                break;
+            }
+        }
+        max_eval
+    } else {
+        let mut min_eval = 9999;
+        for m in movegen {
+            let new_board = board.make_move_new(m);
+            let eval = minimax(&new_board, depth - 1, alpha, beta, true);
+            min_eval = min_eval.min(eval);
+            beta = beta.min(eval);
+            if beta <= alpha {
+                break;
+            }
+        }
+        min_eval
+    }
+}
+
+
+fn best_move(board: &Board, depth: u8) -> Option<(ChessMove, i32)> {
+    let mut best_move = None;
+    let mut best_value = -9999;
+    let movegen = MoveGen::new_legal(board);
+    
+    for m in movegen {
+        let new_board = board.make_move_new(m);
+        let eval = minimax(&new_board, depth, -10000, 10000, false);
+        
+        if eval > best_value {
+            best_value = eval;
+            best_move = Some((m, eval));
+        }
+    }
+    best_move
+}
+
+#[derive(Default)]
+pub struct Third {
+    board: Board,
+}
+
+impl Engine for Third {
+    fn name(&self) -> &'static str {
+        "third"
+    }
+
+    fn set_position(&mut self, position: &Position) {
+        self.board = position.board;
+    }
+
+    fn search(&mut self, limits: &Limits) -> SearchResult {
+        let depth = limits.depth.map_or(MAX_DEPTH, |depth| depth.min(u8::MAX as u32) as u8);
+        let (best_move, score) = best_move(&self.board, depth).unzip();
+        SearchResult { best_move, score: score.unwrap_or(0), depth: depth as u32 + 1, ..Default::default() }
+    }
+}
@@ -0,0 +1,432 @@
+// Uma partida entre duas engines, com o árbitro que aplica as regras e as
+// adjudicações: mate, afogamento, material insuficiente, repetição tripla,
+// regra dos 50 lances, abandono por material, limite de lances e tempo.
+//
+// O abandono é decidido pelo material (em peões, pela `material` do core) e
+// não pela avaliação das engines, que cada geração dá numa escala diferente.
+
+use chess::{Board, BoardStatus, ChessMove, Color, Piece};
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use chazz_core::eval::material;
+use chazz_core::position::{fen_halfmove, is_irreversible, parse_position, Position};
+use engine_sixth::eval::insufficient_material;
+use engine_sixth::pgn::{Game, Node};
+
+use crate::process::{EngineProcess, Reply};
+
+/// Aberturas usadas sem `--openings`: linhas principais, cada uma jogada
+/// com as duas cores.
+pub const OPENINGS: [&str; 8] = [
+    "startpos moves e2e4 e7e5 g1f3 b8c6 f1b5 a7a6",
+    "startpos moves e2e4 c7c5 g1f3 d7d6 d2d4 c5d4 f3d4 g8f6 b1c3",
+    "startpos moves d2d4 d7d5 c2c4 e7e6 b1c3 g8f6",
+    "startpos moves d2d4 g8f6 c2c4 g7g6 b1c3 f8g7 e2e4 d7d6",
+    "startpos moves e2e4 e7e6 d2d4 d7d5 b1c3",
+    "startpos moves e2e4 c7c6 d2d4 d7d5 e4e5",
+    "startpos moves c2c4 e7e5 b1c3 g8f6",
+    "startpos moves e2e4 e7e5 g1f3 b8c6 f1c4 f8c5",
+];
+
+/// Tempo de cada lado para a partida inteira, mais o incremento por lance.
+#[derive(Clone, Copy)]
+pub struct TimeControl {
+    pub base: Duration,
+    pub increment: Duration,
+}
+
+impl TimeControl {
+    /// `<base>[+<incremento>]`, em segundos.
+    pub fn parse(text: &str) -> Result<TimeControl, String> {
+        let invalid = || format!("controle de tempo inválido: {}", text);
+        let seconds = |s: &str| s.parse::<f64>().ok().filter(|s| *s >= 0.0).map(Duration::from_secs_f64).ok_or_else(invalid);
+        let (base, increment) = text.split_once('+').unwrap_or((text, "0"));
+        Ok(TimeControl { base: seconds(base)?, increment: seconds(increment)? })
+    }
+}
+
+/// Formato da etiqueta `TimeControl` do PGN.
+impl fmt::Display for TimeControl {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.base.as_secs_f64())?;
+        if !self.increment.is_zero() {
+            write!(f, "+{}", self.increment.as_secs_f64())?;
+        }
+        Ok(())
+    }
+}
+
+/// Lances que o relógio ainda precisa cobrir, na divisão do tempo restante.
+const MOVES_TO_GO: u32 = 30;
+/// Folga para a comunicação com a engine, descontada do tempo restante.
+const OVERHEAD: Duration = Duration::from_millis(50);
+
+/// Tempo para o próximo lance: uma fatia do relógio mais três quartos do
+/// incremento, sem passar do que resta menos a folga.
+pub fn allot(remaining: Duration, increment: Duration) -> Duration {
+    let budget = remaining / MOVES_TO_GO + increment * 3 / 4;
+    budget.min(remaining.saturating_sub(OVERHEAD)).max(Duration::from_millis(1))
+}
+
+pub struct Settings {
+    pub time_control: Option<TimeControl>,
+    /// Limites repassados a cada `go`; vazio com relógio, `movetime` pelo `allot`
+    pub limits: String,
+    /// Abandono: lances seguidos com `resign_material` peões a menos (0 desliga)
+    pub resign_moves: u32,
+    pub resign_material: i32,
+    /// Empate depois de tantos lances de cada lado (0 desliga)
+    pub max_moves: u32,
+}
+
+/// Posição de partida: `<FEN | startpos> [moves <lance>...]`, como no `position`.
+pub struct Opening {
+    pub text: String,
+    pub start: Position,
+    pub moves: Vec<ChessMove>,
+}
+
+impl Opening {
+    pub fn parse(text: &str) -> Result<Opening, String> {
+        let text = text.trim();
+        let (fen, moves) = text.split_once("moves").unwrap_or((text, ""));
+        let start = parse_position(&format!("position {}", fen), false)?;
+        let mut board = start.board;
+        let mut line = Vec::new();
+        for word in moves.split_whitespace() {
+            let chess_move = chazz_core::notation::parse_move(&board, word)?;
+            board = board.make_move_new(chess_move);
+            line.push(chess_move);
+        }
+        if board.status() != BoardStatus::Ongoing {
+            return Err(format!("abertura já terminada: {}", text));
+        }
+        Ok(Opening { text: fen.trim().to_string(), start, moves: line })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Termination {
+    Checkmate,
+    Stalemate,
+    InsufficientMaterial,
+    Repetition,
+    FiftyMoves,
+    Resignation,
+    MaxMoves,
+    Time,
+    IllegalMove,
+    Disconnected,
+}
+
+impl Termination {
+    pub fn describe(self) -> &'static str {
+        match self {
+            Termination::Checkmate => "xeque-mate",
+            Termination::Stalemate => "afogamento",
+            Termination::InsufficientMaterial => "material insuficiente",
+            Termination::Repetition => "repetição tripla",
+            Termination::FiftyMoves => "regra dos 50 lances",
+            Termination::Resignation => "abandono por material",
+            Termination::MaxMoves => "limite de lances",
+            Termination::Time => "tempo esgotado",
+            Termination::IllegalMove => "lance ilegal",
+            Termination::Disconnected => "engine encerrada",
+        }
+    }
+
+    /// Valor da etiqueta `Termination` do PGN.
+    fn tag(self) -> &'static str {
+        match self {
+            Termination::Resignation | Termination::MaxMoves => "adjudication",
+            Termination::Time => "time forfeit",
+            Termination::IllegalMove => "rules infraction",
+            Termination::Disconnected => "abandoned",
+            _ => "normal",
+        }
+    }
+}
+
+/// Resultado de uma partida: o vencedor (`None` no empate) e o motivo.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Outcome {
+    pub winner: Option<Color>,
+    pub termination: Termination,
+}
+
+impl Outcome {
+    pub fn result(&self) -> &'static str {
+        match self.winner {
+            Some(Color::White) => "1-0",
+            Some(Color::Black) => "0-1",
+            None => "1/2-1/2",
+        }
+    }
+}
+
+/// Lado com material para dar mate: algum peão, torre ou dama, ou duas
+/// peças menores.
+fn can_mate(board: &Board, color: Color) -> bool {
+    let ours = *board.color_combined(color);
+    let heavy = *board.pieces(Piece::Pawn) | *board.pieces(Piece::Rook) | *board.pieces(Piece::Queen);
+    let minors = *board.pieces(Piece::Knight) | *board.pieces(Piece::Bishop);
+    (heavy & ours).popcnt() > 0 || (minors & ours).popcnt() >= 2
+}
+
+/// Estado da partida do ponto de vista do árbitro.
+pub struct Arbiter {
+    pub board: Board,
+    hashes: Vec<u64>,
+    halfmove: u32,
+    plies: u32,
+    /// Lado em desvantagem de material e há quantos meios-lances seguidos
+    losing: Option<(Color, u32)>,
+}
+
+impl Arbiter {
+    /// Árbitro a partir de `board`, com o contador dos 50 lances da FEN.
+    pub fn new(board: Board, halfmove: u32) -> Arbiter {
+        Arbiter { board, hashes: vec![board.get_hash()], halfmove, plies: 0, losing: None }
+    }
+
+    pub fn play(&mut self, chess_move: ChessMove, settings: &Settings) {
+        let irreversible = is_irreversible(&self.board, chess_move);
+        self.board = self.board.make_move_new(chess_move);
+        self.halfmove = if irreversible { 0 } else { self.halfmove + 1 };
+        self.plies += 1;
+        self.hashes.push(self.board.get_hash());
+
+        let balance = material(&self.board);
+        self.losing = if settings.resign_moves > 0 && balance.abs() >= settings.resign_material {
+            // `material` é do ponto de vista de quem joga
+            let mover = self.board.side_to_move();
+            let loser = if balance < 0 { mover } else { !mover };
+            match self.losing {
+                Some((color, plies)) if color == loser => Some((loser, plies + 1)),
+                _ => Some((loser, 1)),
+            }
+        } else {
+            None
+        };
+    }
+
+    /// Fim da partida pelas regras ou por adjudicação, se for o caso.
+    pub fn verdict(&self, settings: &Settings) -> Option<Outcome> {
+        let board = &self.board;
+        let outcome = |winner, termination| Some(Outcome { winner, termination });
+        match board.status() {
+            BoardStatus::Checkmate => return outcome(Some(!board.side_to_move()), Termination::Checkmate),
+            BoardStatus::Stalemate => return outcome(None, Termination::Stalemate),
+            BoardStatus::Ongoing => {}
+        }
+        if insufficient_material(board) {
+            return outcome(None, Termination::InsufficientMaterial);
+        }
+        if self.hashes.iter().filter(|&&hash| hash == board.get_hash()).count() >= 3 {
+            return outcome(None, Termination::Repetition);
+        }
+        if self.halfmove >= 100 {
+            return outcome(None, Termination::FiftyMoves);
+        }
+        if let Some((loser, plies)) = self.losing {
+            if plies >= settings.resign_moves * 2 {
+                return outcome(Some(!loser), Termination::Resignation);
+            }
+        }
+        if settings.max_moves > 0 && self.plies >= settings.max_moves * 2 {
+            return outcome(None, Termination::MaxMoves);
+        }
+        None
+    }
+
+    /// Derrota de `color` por tempo, ou empate se o adversário não tiver
+    /// material para dar mate.
+    fn time_forfeit(&self, color: Color) -> Outcome {
+        let winner = Some(!color).filter(|&winner| can_mate(&self.board, winner));
+        Outcome { winner, termination: Termination::Time }
+    }
+}
+
+/// Partida jogada: a abertura, os lances das engines e o resultado.
+pub struct Played {
+    pub moves: Vec<ChessMove>,
+    pub outcome: Outcome,
+}
+
+/// Joga a partida a partir de `opening`; `engines[0]` fica com as brancas.
+pub fn play(opening: &Opening, engines: [&mut EngineProcess; 2], settings: &Settings) -> Played {
+    let mut arbiter = Arbiter::new(opening.start.board, fen_halfmove(&opening.text));
+    let mut moves = Vec::new();
+    for &chess_move in &opening.moves {
+        arbiter.play(chess_move, settings);
+        moves.push(chess_move);
+    }
+
+    let [white, black] = engines;
+    white.send("newgame");
+    black.send("newgame");
+    let mut clocks = [settings.time_control.map(|tc| tc.base); 2];
+
+    loop {
+        if let Some(outcome) = arbiter.verdict(settings) {
+            return Played { moves, outcome };
+        }
+
+        let side = arbiter.board.side_to_move();
+        let engine = if side == Color::White { &mut *white } else { &mut *black };
+        let line: Vec<String> = moves.iter().map(ChessMove::to_string).collect();
+        let position = format!("position {} moves {}", opening.text, line.join(" "));
+
+        let clock = &mut clocks[side.to_index()];
+        // Sem limites em `--go`, a engine recebe o tempo do lance pelo relógio
+        let limits = match (*clock, settings.time_control) {
+            (Some(remaining), Some(tc)) if settings.limits.trim().is_empty() => {
+                format!("movetime {}", allot(remaining, tc.increment).as_millis())
+            }
+            _ => settings.limits.clone(),
+        };
+        let start = Instant::now();
+        let reply = engine.go(position.trim(), &limits, *clock);
+        let elapsed = start.elapsed();
+        let lost = |termination| Played { moves: moves.clone(), outcome: Outcome { winner: Some(!side), termination } };
+
+        let chess_move = match reply {
+            Reply::Move(chess_move) if arbiter.board.legal(chess_move) => chess_move,
+            Reply::Move(chess_move) => {
+                eprintln!("Aviso: {} jogou um lance ilegal: {}", engine.name, chess_move);
+                return lost(Termination::IllegalMove);
+            }
+            Reply::Timeout => return Played { outcome: arbiter.time_forfeit(side), moves },
+            Reply::Disconnected => return lost(Termination::Disconnected),
+        };
+        if let Some(remaining) = clock {
+            if elapsed > *remaining {
+                return Played { outcome: arbiter.time_forfeit(side), moves };
+            }
+            *remaining = *remaining - elapsed + settings.time_control.map_or(Duration::ZERO, |tc| tc.increment);
+        }
+
+        arbiter.play(chess_move, settings);
+        moves.push(chess_move);
+    }
+}
+
+/// A partida em PGN, com o motivo do fim como comentário do último lance.
+pub fn to_pgn(played: &Played, opening: &Opening, tags: Vec<(String, String)>) -> Game {
+    let mut game = Game::new(opening.start.board);
+    game.start_move = opening.start.fullmove();
+    game.tags = tags;
+    if opening.text != "startpos" {
+        game.tags.push(("SetUp".to_string(), "1".to_string()));
+        game.tags.push(("FEN".to_string(), opening.text.clone()));
+    }
+    game.tags.push(("Termination".to_string(), played.outcome.termination.tag().to_string()));
+    game.result = played.outcome.result().to_string();
+
+    let mut boards = Vec::new();
+    let mut board = opening.start.board;
+    for &chess_move in &played.moves {
+        boards.push(board);
+        board = board.make_move_new(chess_move);
+    }
+
+    // A linha principal é uma lista encadeada pelo primeiro filho
+    let mut children = Vec::new();
+    for (i, (&board, &chess_move)) in boards.iter().zip(&played.moves).enumerate().rev() {
+        let mut node = Node::new(board, chess_move);
+        if i + 1 == played.moves.len() {
+            node.comment = Some(played.outcome.termination.describe().to_string());
+        } else if i + 1 == opening.moves.len() {
+            node.comment = Some("fim da abertura".to_string());
+        }
+        node.children = children;
+        children = vec![node];
+    }
+    game.children = children;
+    game
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn settings() -> Settings {
+        Settings { time_control: None, limits: String::new(), resign_moves: 2, resign_material: 5, max_moves: 0 }
+    }
+
+    fn play_line(arbiter: &mut Arbiter, line: &str) {
+        for word in line.split_whitespace() {
+            arbiter.play(ChessMove::from_str(word).unwrap(), &settings());
+        }
+    }
+
+    #[test]
+    fn builtin_openings_are_legal() {
+        for text in OPENINGS {
+            Opening::parse(text).unwrap_or_else(|e| panic!("{}: {}", text, e));
+        }
+    }
+
+    #[test]
+    fn time_control_round_trip() {
+        let tc = TimeControl::parse("10+0.1").unwrap();
+        assert_eq!(tc.base, Duration::from_secs(10));
+        assert_eq!(tc.to_string(), "10+0.1");
+        assert_eq!(TimeControl::parse("60").unwrap().to_string(), "60");
+        assert!(TimeControl::parse("dez").is_err());
+    }
+
+    #[test]
+    fn rules_end_the_game() {
+        let mut arbiter = Arbiter::new(Board::default(), 0);
+        play_line(&mut arbiter, "f2f3 e7e5 g2g4 d8h4");
+        assert_eq!(arbiter.verdict(&settings()), Some(Outcome { winner: Some(Color::Black), termination: Termination::Checkmate }));
+
+        let mut arbiter = Arbiter::new(Board::default(), 0);
+        play_line(&mut arbiter, "g1f3 g8f6 f3g1 f6g8 g1f3 g8f6 f3g1");
+        assert_eq!(arbiter.verdict(&settings()), None);
+        play_line(&mut arbiter, "f6g8");
+        assert_eq!(arbiter.verdict(&settings()).map(|o| o.termination), Some(Termination::Repetition));
+    }
+
+    #[test]
+    fn fifty_moves_count_from_the_fen() {
+        let opening = Opening::parse("4k3/8/8/8/8/8/8/R3K3 w - - 98 80").unwrap();
+        let mut arbiter = Arbiter::new(opening.start.board, fen_halfmove(&opening.text));
+        play_line(&mut arbiter, "a1a2");
+        assert_eq!(arbiter.verdict(&settings()), None);
+        play_line(&mut arbiter, "e8d8");
+        assert_eq!(arbiter.verdict(&settings()).map(|o| o.termination), Some(Termination::FiftyMoves));
+    }
+
+    #[test]
+    fn move_time_from_the_clock() {
+        let ms = Duration::from_millis;
+        assert_eq!(allot(ms(30000), Duration::ZERO), ms(1000));
+        assert_eq!(allot(ms(3000), ms(100)), ms(175));
+        // Perto do fim, nunca mais que o restante menos a folga
+        assert_eq!(allot(ms(100), ms(1000)), ms(50));
+        assert_eq!(allot(ms(10), Duration::ZERO), ms(1));
+    }
+
+    #[test]
+    fn material_deficit_resigns() {
+        // Brancas com uma dama a mais: depois de dois lances de cada lado, as pretas abandonam
+        let board = Board::from_str("4k3/8/8/8/8/8/8/3QK3 w - - 0 1").unwrap();
+        let mut arbiter = Arbiter::new(board, 0);
+        play_line(&mut arbiter, "e1e2 e8e7 e2e1");
+        assert_eq!(arbiter.verdict(&settings()), None);
+        play_line(&mut arbiter, "e7e8");
+        assert_eq!(arbiter.verdict(&settings()), Some(Outcome { winner: Some(Color::White), termination: Termination::Resignation }));
+    }
+
+    #[test]
+    fn time_forfeit_against_bare_king_is_a_draw() {
+        let board = Board::from_str("4k3/8/8/8/8/8/8/3QK3 b - - 0 1").unwrap();
+        let arbiter = Arbiter::new(board, 0);
+        assert_eq!(arbiter.time_forfeit(Color::Black).winner, Some(Color::White));
+        assert_eq!(arbiter.time_forfeit(Color::White).winner, None);
+    }
+}
@@ -0,0 +1,269 @@
+// Torneio entre duas engines quaisquer que falem o protocolo pela entrada
+// padrão: cada abertura é jogada duas vezes, trocando as cores. As partidas
+// vão para um PGN à medida que terminam e, no fim, sai o placar.
+//
+// O protocolo não tem relógio, só `depth`, `nodes` e `movetime`. Com `--tc`
+// e sem `--go`, cada lance vai com um `movetime` tirado do tempo restante e
+// do incremento. Com `--go`, os limites são repassados como estão. Em
+// qualquer caso o relógio é imposto de fora: quem passar do tempo perde.
+//
+// Com `--sprt`, o torneio para assim que o teste aceitar uma das hipóteses;
+// `--games` passa a ser o máximo de partidas.
+
+mod game;
+mod process;
+mod stats;
+
+use chess::Color;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use chazz_core::protocol::parse_go;
+use engine_sixth::pgn::write_game;
+
+use game::{play, to_pgn, Opening, Settings, Termination, TimeControl, OPENINGS};
+use process::EngineProcess;
+use stats::{Pentanomial, Score, Sprt};
+
+const USAGE: &str = "Uso: match <engine> <engine> [--games <n>] [--openings <arquivo>] [--tc <base>[+<inc>]] \
+[--go <limites>] [--resign <lances> <peões>] [--max-moves <n>] [--pgn <arquivo>] \
+[--sprt <elo0> <elo1>] [--alpha <a>] [--beta <b>]\n\
+Cada engine é `[nome=]comando [argumentos]`, entre aspas se tiver espaços.";
+
+/// `[nome=]comando`: sem nome, vale o nome do programa com os argumentos.
+fn engine_spec(spec: &str) -> (String, String) {
+    match spec.split_once('=').filter(|(name, _)| !name.contains(char::is_whitespace)) {
+        Some((name, command)) => (name.to_string(), command.to_string()),
+        None => {
+            let mut words = spec.split_whitespace();
+            let program = words.next().unwrap_or_default();
+            let program = Path::new(program).file_stem().map_or(program.into(), |s| s.to_string_lossy());
+            let name = std::iter::once(program.as_ref()).chain(words).collect::<Vec<_>>().join(" ");
+            (name, spec.to_string())
+        }
+    }
+}
+
+/// Aberturas do arquivo, uma por linha; linhas vazias e com `#` são ignoradas.
+fn load_openings(path: &str) -> Result<Vec<Opening>, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+    let openings = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| Opening::parse(line).map_err(|e| format!("{}: {}", path, e)))
+        .collect::<Result<Vec<_>, _>>()?;
+    if openings.is_empty() {
+        return Err(format!("{}: nenhuma abertura", path));
+    }
+    Ok(openings)
+}
+
+/// Data de hoje no formato do PGN (`AAAA.MM.DD`).
+fn today() -> String {
+    let days = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs() / 86400) as i64;
+    // Dias desde 1970-01-01 para a data civil (algoritmo de Howard Hinnant)
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{:04}.{:02}.{:02}", year, month, day)
+}
+
+struct Config {
+    engines: Vec<(String, String)>,
+    games: Option<u32>,
+    openings: Vec<Opening>,
+    settings: Settings,
+    pgn: String,
+    sprt: Option<Sprt>,
+}
+
+fn parse_args(args: &[String]) -> Result<Config, String> {
+    let mut config = Config {
+        engines: Vec::new(),
+        games: None,
+        openings: Vec::new(),
+        settings: Settings { time_control: None, limits: String::new(), resign_moves: 10, resign_material: 9, max_moves: 200 },
+        pgn: "match.pgn".to_string(),
+        sprt: None,
+    };
+    let (mut alpha, mut beta) = (0.05, 0.05);
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().cloned().ok_or(format!("{} precisa de um valor", arg));
+        let real = |text: String| text.parse::<f64>().map_err(|_| format!("valor inválido para {}: {}", arg, text));
+        let number = |text: String| text.parse::<u32>().map_err(|_| format!("valor inválido para {}: {}", arg, text));
+        match arg.as_str() {
+            "--games" => config.games = Some(number(value()?)?),
+            "--openings" => config.openings = load_openings(&value()?)?,
+            "--tc" => config.settings.time_control = Some(TimeControl::parse(&value()?)?),
+            "--go" => {
+                let limits = value()?;
+                parse_go(&format!("go {}", limits))?;
+                config.settings.limits = limits;
+            }
+            "--resign" => {
+                config.settings.resign_moves = number(value()?)?;
+                config.settings.resign_material = number(value()?)? as i32;
+            }
+            "--max-moves" => config.settings.max_moves = number(value()?)?,
+            "--pgn" => config.pgn = value()?,
+            "--sprt" => {
+                let (elo0, elo1) = (real(value()?)?, real(value()?)?);
+                config.sprt = Some(Sprt { elo0, elo1, alpha, beta });
+            }
+            "--alpha" => alpha = real(value()?)?,
+            "--beta" => beta = real(value()?)?,
+            other if other.starts_with("--") => return Err(format!("opção desconhecida: {}", other)),
+            spec => config.engines.push(engine_spec(spec)),
+        }
+    }
+
+    if config.engines.len() != 2 {
+        return Err(USAGE.to_string());
+    }
+    if let Some(sprt) = &mut config.sprt {
+        if !(0.0..1.0).contains(&alpha) || !(0.0..1.0).contains(&beta) || sprt.elo1 <= sprt.elo0 {
+            return Err("o SPRT precisa de elo0 < elo1 e de alpha e beta entre 0 e 1".to_string());
+        }
+        (sprt.alpha, sprt.beta) = (alpha, beta);
+    }
+    if config.openings.is_empty() {
+        config.openings = OPENINGS.iter().map(|text| Opening::parse(text)).collect::<Result<_, _>>()?;
+    }
+    Ok(config)
+}
+
+fn run(config: Config) -> Result<(), String> {
+    let [(name_a, command_a), (name_b, command_b)] = <[_; 2]>::try_from(config.engines).unwrap();
+    let mut engines = [EngineProcess::spawn(&name_a, &command_a)?, EngineProcess::spawn(&name_b, &command_b)?];
+    let total = config.games.unwrap_or(config.openings.len() as u32 * 2);
+    let mut pgn = File::create(&config.pgn).map_err(|e| format!("{}: {}", config.pgn, e))?;
+    let date = today();
+
+    // Placar da primeira engine, no total e com cada cor
+    let mut score = Score::default();
+    let mut by_color = [Score::default(); 2];
+    let mut terminations: HashMap<Termination, u32> = HashMap::new();
+    // Pares da mesma abertura com as cores trocadas; `first` é a primeira metade
+    let mut pairs = Pentanomial::default();
+    let mut first = None;
+    let mut decision = None;
+
+    for round in 0..total {
+        // Engine que caiu volta antes da próxima partida
+        for (engine, command) in engines.iter_mut().zip([&command_a, &command_b]) {
+            if !engine.alive() {
+                *engine = EngineProcess::spawn(&engine.name.clone(), command)?;
+            }
+        }
+
+        let opening = &config.openings[(round / 2) as usize % config.openings.len()];
+        let a_color = if round % 2 == 0 { Color::White } else { Color::Black };
+        let [a, b] = &mut engines;
+        let (white, black) = if a_color == Color::White { (a, b) } else { (b, a) };
+        let played = play(opening, [&mut *white, &mut *black], &config.settings);
+
+        let outcome = played.outcome;
+        score.add(outcome.winner, a_color);
+        by_color[a_color.to_index()].add(outcome.winner, a_color);
+        *terminations.entry(outcome.termination).or_default() += 1;
+        println!(
+            "Partida {}/{}: {} - {} {} ({}) | {} {}",
+            round + 1,
+            total,
+            white.name,
+            black.name,
+            outcome.result(),
+            outcome.termination.describe(),
+            name_a,
+            score
+        );
+
+        let mut tags = vec![
+            ("Event".to_string(), format!("{} x {}", name_a, name_b)),
+            ("Site".to_string(), "?".to_string()),
+            ("Date".to_string(), date.clone()),
+            ("Round".to_string(), (round + 1).to_string()),
+            ("White".to_string(), white.name.clone()),
+            ("Black".to_string(), black.name.clone()),
+            ("Result".to_string(), outcome.result().to_string()),
+        ];
+        let time_control = config.settings.time_control.map_or("-".to_string(), |tc| tc.to_string());
+        tags.push(("TimeControl".to_string(), time_control));
+        let game = to_pgn(&played, opening, tags);
+        pgn.write_all(write_game(&game).as_bytes()).map_err(|e| format!("{}: {}", config.pgn, e))?;
+
+        let points = match outcome.winner {
+            Some(winner) if winner == a_color => 1.0,
+            Some(_) => 0.0,
+            None => 0.5,
+        };
+        match first.take() {
+            None => first = Some(points),
+            Some(first) => {
+                pairs.add(first, points);
+                if let Some(sprt) = &config.sprt {
+                    let llr = sprt.llr(&pairs);
+                    let (lower, upper) = sprt.bounds();
+                    println!("LLR: {:.2} [{:.2}, {:.2}]", llr, lower, upper);
+                    decision = sprt.decision(llr);
+                    if decision.is_some() {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    println!();
+    println!("{} x {}: {} em {} partidas", name_a, name_b, score, score.games());
+    println!(
+        "Pontos de {}: {}/{} ({:.1}%)",
+        name_a,
+        score.points(),
+        score.games(),
+        score.points() * 100.0 / score.games().max(1) as f64
+    );
+    println!("Com as brancas: {}, com as pretas: {}", by_color[0], by_color[1]);
+    if let Some(estimate) = score.estimate() {
+        println!("Elo: {} (95%), LOS: {:.1}%", estimate, score.los() * 100.0);
+    }
+    if let Some(estimate) = pairs.estimate() {
+        println!("Pares (0 a 2 pontos) {}, {} pares: Elo {}", pairs, pairs.pairs(), estimate);
+    }
+    if let Some(sprt) = &config.sprt {
+        let (lower, upper) = sprt.bounds();
+        let verdict = match decision {
+            Some(true) => "H1 aceita",
+            Some(false) => "H0 aceita",
+            None => "inconclusivo",
+        };
+        println!("SPRT ({}): LLR {:.2} [{:.2}, {:.2}], {}", sprt, sprt.llr(&pairs), lower, upper, verdict);
+    }
+    let mut terminations: Vec<_> = terminations.into_iter().collect();
+    terminations.sort_by_key(|&(termination, count)| (std::cmp::Reverse(count), termination.describe()));
+    for (termination, count) in terminations {
+        println!("  {}: {}", termination.describe(), count);
+    }
+    println!("Partidas salvas em {}", config.pgn);
+    Ok(())
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Err(e) = parse_args(&args).and_then(run) {
+        eprintln!("Erro: {}", e);
+        std::process::exit(1);
+    }
+}
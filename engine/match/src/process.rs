@@ -0,0 +1,122 @@
+// Uma engine rodando como processo filho, falando o protocolo pela entrada
+// padrão. A saída de erros vai para o mesmo pipe da saída padrão, então as
+// linhas chegam na ordem em que a engine as escreveu.
+
+use chess::ChessMove;
+use std::io::{self, BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::str::FromStr;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub enum Reply {
+    Move(ChessMove),
+    Timeout,
+    Disconnected,
+}
+
+pub struct EngineProcess {
+    pub name: String,
+    child: Child,
+    stdin: ChildStdin,
+    lines: Receiver<String>,
+    /// Um `go` cujo lance ainda não chegou: a engine estourou o tempo
+    pending: bool,
+    alive: bool,
+}
+
+impl EngineProcess {
+    /// `command` é o programa seguido dos argumentos, separados por espaços.
+    pub fn spawn(name: &str, command: &str) -> Result<EngineProcess, String> {
+        let mut words = command.split_whitespace();
+        let program = words.next().ok_or("comando de engine vazio")?;
+        let (reader, writer) = io::pipe().map_err(|e| e.to_string())?;
+        let mut child = Command::new(program)
+            .args(words)
+            .stdin(Stdio::piped())
+            .stdout(writer.try_clone().map_err(|e| e.to_string())?)
+            .stderr(writer)
+            .spawn()
+            .map_err(|e| format!("{}: {}", program, e))?;
+        let stdin = child.stdin.take().unwrap();
+
+        let (sender, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(reader).lines() {
+                let Ok(line) = line else { break };
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(EngineProcess { name: name.to_string(), child, stdin, lines, pending: false, alive: true })
+    }
+
+    pub fn alive(&self) -> bool {
+        self.alive
+    }
+
+    pub fn send(&mut self, line: &str) {
+        if writeln!(self.stdin, "{}", line).and_then(|_| self.stdin.flush()).is_err() {
+            self.alive = false;
+        }
+    }
+
+    /// Próxima linha que for um lance em coordenadas; as demais (`info`,
+    /// avisos) são ignoradas. Sem `timeout`, espera o quanto for preciso.
+    fn next_move(&mut self, deadline: Option<Instant>) -> Reply {
+        loop {
+            let line = match deadline {
+                Some(deadline) => match self.lines.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                    Ok(line) => line,
+                    Err(RecvTimeoutError::Timeout) => return Reply::Timeout,
+                    Err(RecvTimeoutError::Disconnected) => return Reply::Disconnected,
+                },
+                None => match self.lines.recv() {
+                    Ok(line) => line,
+                    Err(_) => return Reply::Disconnected,
+                },
+            };
+            if let Ok(chess_move) = ChessMove::from_str(line.trim()) {
+                return Reply::Move(chess_move);
+            }
+        }
+    }
+
+    /// Pede um lance com `go <limits>` e espera até `timeout`.
+    pub fn go(&mut self, position: &str, limits: &str, timeout: Option<Duration>) -> Reply {
+        // O lance atrasado de uma busca anterior não pode ser tomado por este
+        if self.pending {
+            if let Reply::Disconnected = self.next_move(None) {
+                self.alive = false;
+            }
+            self.pending = false;
+        }
+
+        self.send(position);
+        self.send(format!("go {}", limits).trim());
+        if !self.alive {
+            return Reply::Disconnected;
+        }
+        let reply = self.next_move(timeout.map(|timeout| Instant::now() + timeout));
+        match reply {
+            Reply::Timeout => self.pending = true,
+            Reply::Disconnected => self.alive = false,
+            Reply::Move(_) => {}
+        }
+        reply
+    }
+}
+
+impl Drop for EngineProcess {
+    fn drop(&mut self) {
+        self.send("quit");
+        // Uma engine ainda pensando num lance atrasado não leria o `quit` a tempo
+        if self.pending {
+            let _ = self.child.kill();
+        }
+        let _ = self.child.wait();
+    }
+}
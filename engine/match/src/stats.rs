@@ -0,0 +1,263 @@
+// Estatística do torneio: diferença de Elo com margem de erro pelas partidas
+// soltas (trinomial) e pelos pares de partidas da mesma abertura com as cores
+// trocadas (pentanomial), probabilidade de superioridade (LOS) e o teste
+// sequencial da razão de probabilidades (SPRT).
+//
+// O SPRT trabalha com os pares, que descontam a vantagem da abertura, e usa
+// a aproximação normal do log da razão de verossimilhança (a mesma do
+// fishtest): LLR = n (s1 - s0) (2 m - s0 - s1) / (2 v), com n pares de média
+// m e variância v por par e s0, s1 as pontuações esperadas com `elo0` e `elo1`.
+
+use chess::Color;
+use std::fmt;
+
+/// Quantil de 97,5% da normal: margens de 95%.
+const Z_95: f64 = 1.959964;
+
+/// Diferença de Elo correspondente à pontuação média `score` (de 0 a 1).
+pub fn elo(score: f64) -> f64 {
+    -400.0 * (1.0 / score - 1.0).log10()
+}
+
+/// Pontuação média esperada com a diferença `elo`.
+pub fn expected_score(elo: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf(-elo / 400.0))
+}
+
+/// Função erro (Abramowitz e Stegun 7.1.26, erro abaixo de 1,5e-7).
+fn erf(x: f64) -> f64 {
+    let t = 1.0 / (1.0 + 0.3275911 * x.abs());
+    let poly = t * (0.254829592 + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+    (1.0 - poly * (-x * x).exp()).copysign(x)
+}
+
+/// Número de unidades, média e variância da pontuação por unidade, a partir
+/// de (pontuação, vezes).
+fn mean_variance(distribution: &[(f64, u32)]) -> Option<(f64, f64, f64)> {
+    let n: f64 = distribution.iter().map(|&(_, count)| count as f64).sum();
+    if n == 0.0 {
+        return None;
+    }
+    let mean = distribution.iter().map(|&(score, count)| score * count as f64).sum::<f64>() / n;
+    let variance = distribution.iter().map(|&(score, count)| (score - mean).powi(2) * count as f64).sum::<f64>() / n;
+    Some((n, mean, variance))
+}
+
+/// Elo estimado e a metade do intervalo de 95%.
+#[derive(Clone, Copy, Debug)]
+pub struct Estimate {
+    pub elo: f64,
+    pub margin: f64,
+}
+
+impl Estimate {
+    fn from_distribution(distribution: &[(f64, u32)]) -> Option<Estimate> {
+        let (n, mean, variance) = mean_variance(distribution)?;
+        let error = (variance / n).sqrt();
+        let (low, high) = ((mean - Z_95 * error).max(0.0), (mean + Z_95 * error).min(1.0));
+        Some(Estimate { elo: elo(mean), margin: (elo(high) - elo(low)) / 2.0 })
+    }
+}
+
+/// `35.2 ± 20.1`
+impl fmt::Display for Estimate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // `+ 0.0` evita o "-0.0"; com 100% ou 0% dos pontos a margem é infinita
+        let elo = self.elo + 0.0;
+        if self.margin.is_finite() {
+            write!(f, "{:.1} ± {:.1}", elo, self.margin)
+        } else {
+            write!(f, "{:.1} ± inf", elo)
+        }
+    }
+}
+
+/// Vitórias, empates e derrotas.
+#[derive(Clone, Copy, Default)]
+pub struct Score {
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+}
+
+impl Score {
+    pub fn add(&mut self, winner: Option<Color>, color: Color) {
+        match winner {
+            Some(winner) if winner == color => self.wins += 1,
+            Some(_) => self.losses += 1,
+            None => self.draws += 1,
+        }
+    }
+
+    pub fn games(&self) -> u32 {
+        self.wins + self.draws + self.losses
+    }
+
+    pub fn points(&self) -> f64 {
+        self.wins as f64 + self.draws as f64 / 2.0
+    }
+
+    fn distribution(&self) -> [(f64, u32); 3] {
+        [(1.0, self.wins), (0.5, self.draws), (0.0, self.losses)]
+    }
+
+    pub fn estimate(&self) -> Option<Estimate> {
+        Estimate::from_distribution(&self.distribution())
+    }
+
+    /// Probabilidade de a engine ser mais forte, pelas partidas decididas.
+    pub fn los(&self) -> f64 {
+        let decisive = (self.wins + self.losses) as f64;
+        if decisive == 0.0 {
+            return 0.5;
+        }
+        0.5 * (1.0 + erf((self.wins as f64 - self.losses as f64) / (2.0 * decisive).sqrt()))
+    }
+}
+
+/// `+V =E -D`
+impl fmt::Display for Score {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "+{} ={} -{}", self.wins, self.draws, self.losses)
+    }
+}
+
+/// Pares de partidas pela soma dos pontos no par: 0, ½, 1, 1½ ou 2.
+#[derive(Clone, Copy, Default)]
+pub struct Pentanomial(pub [u32; 5]);
+
+impl Pentanomial {
+    /// Soma o par pelos pontos de cada partida (0, 0,5 ou 1).
+    pub fn add(&mut self, first: f64, second: f64) {
+        self.0[((first + second) * 2.0).round() as usize] += 1;
+    }
+
+    pub fn pairs(&self) -> u32 {
+        self.0.iter().sum()
+    }
+
+    fn distribution(&self) -> [(f64, u32); 5] {
+        let [a, b, c, d, e] = self.0;
+        [(0.0, a), (0.25, b), (0.5, c), (0.75, d), (1.0, e)]
+    }
+
+    pub fn estimate(&self) -> Option<Estimate> {
+        Estimate::from_distribution(&self.distribution())
+    }
+}
+
+/// `[0, 2, 5, 3, 1]`
+impl fmt::Display for Pentanomial {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self.0)
+    }
+}
+
+/// Hipóteses do SPRT: H0 é `elo0`, H1 é `elo1`; `alpha` e `beta` são os
+/// erros dos tipos I e II.
+#[derive(Clone, Copy)]
+pub struct Sprt {
+    pub elo0: f64,
+    pub elo1: f64,
+    pub alpha: f64,
+    pub beta: f64,
+}
+
+impl Sprt {
+    /// Limites do LLR: abaixo do primeiro aceita H0, acima do segundo aceita H1.
+    pub fn bounds(&self) -> (f64, f64) {
+        ((self.beta / (1.0 - self.alpha)).ln(), ((1.0 - self.beta) / self.alpha).ln())
+    }
+
+    /// LLR pelos pares de partidas. Fica em 0 enquanto todos os pares tiverem
+    /// a mesma pontuação: sem variância a aproximação não vale.
+    pub fn llr(&self, pairs: &Pentanomial) -> f64 {
+        let Some((n, mean, variance)) = mean_variance(&pairs.distribution()) else {
+            return 0.0;
+        };
+        if variance <= 0.0 {
+            return 0.0;
+        }
+        let (s0, s1) = (expected_score(self.elo0), expected_score(self.elo1));
+        n * (s1 - s0) * (2.0 * mean - s0 - s1) / (2.0 * variance)
+    }
+
+    /// `Some(true)` se H1 foi aceita, `Some(false)` se H0, `None` se ainda não.
+    pub fn decision(&self, llr: f64) -> Option<bool> {
+        let (lower, upper) = self.bounds();
+        if llr >= upper {
+            Some(true)
+        } else if llr <= lower {
+            Some(false)
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for Sprt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "elo0 {}, elo1 {}, alpha {}, beta {}", self.elo0, self.elo1, self.alpha, self.beta)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64, tolerance: f64) -> bool {
+        (a - b).abs() < tolerance
+    }
+
+    #[test]
+    fn elo_and_expected_score_are_inverse() {
+        assert!(close(elo(0.5), 0.0, 1e-9));
+        assert!(close(elo(0.75), 190.85, 0.01));
+        assert!(close(expected_score(elo(0.64)), 0.64, 1e-9));
+        assert!(close(erf(1.0), 0.842701, 1e-6));
+        assert!(close(erf(-0.5), -0.520500, 1e-6));
+    }
+
+    #[test]
+    fn estimates_and_los() {
+        let even = Score { wins: 10, draws: 20, losses: 10 };
+        assert!(close(even.estimate().unwrap().elo, 0.0, 1e-9));
+        assert!(close(even.los(), 0.5, 1e-9));
+
+        let ahead = Score { wins: 30, draws: 20, losses: 10 };
+        let estimate = ahead.estimate().unwrap();
+        assert!(estimate.elo > 100.0 && estimate.margin > 0.0);
+        assert!(ahead.los() > 0.99);
+        // Mais partidas com a mesma proporção estreitam a margem
+        let more = Score { wins: 300, draws: 200, losses: 100 };
+        assert!(more.estimate().unwrap().margin < estimate.margin);
+    }
+
+    #[test]
+    fn pentanomial_pairs() {
+        let mut pairs = Pentanomial::default();
+        pairs.add(1.0, 0.5);
+        pairs.add(0.0, 1.0);
+        pairs.add(0.5, 0.5);
+        pairs.add(0.0, 0.0);
+        assert_eq!(pairs.0, [1, 0, 2, 1, 0]);
+        assert_eq!(pairs.pairs(), 4);
+        assert!(pairs.estimate().unwrap().elo < 0.0);
+    }
+
+    #[test]
+    fn sprt_decides_in_both_directions() {
+        let sprt = Sprt { elo0: 0.0, elo1: 10.0, alpha: 0.05, beta: 0.05 };
+        let (lower, upper) = sprt.bounds();
+        assert!(close(upper, 2.944, 0.001) && close(lower, -2.944, 0.001));
+
+        let strong = Pentanomial([5, 20, 60, 60, 25]);
+        assert_eq!(sprt.decision(sprt.llr(&strong)), Some(true));
+        let weak = Pentanomial([25, 60, 60, 20, 5]);
+        assert_eq!(sprt.decision(sprt.llr(&weak)), Some(false));
+        let even = Pentanomial([1, 2, 4, 2, 1]);
+        assert_eq!(sprt.decision(sprt.llr(&even)), None);
+        // Todos os pares iguais ainda não decidem
+        assert_eq!(sprt.llr(&Pentanomial([0, 0, 0, 0, 40])), 0.0);
+    }
+}
@@ -0,0 +1,25 @@
+// Engine de teste: joga o primeiro lance legal.
+
+use chazz_core::engine::{Engine, Limits, SearchResult};
+use chazz_core::position::Position;
+use chess::{Board, MoveGen};
+
+#[derive(Default)]
+pub struct Random {
+    board: Board,
+}
+
+impl Engine for Random {
+    fn name(&self) -> &'static str {
+        "random"
+    }
+
+    fn set_position(&mut self, position: &Position) {
+        self.board = position.board;
+    }
+
+    fn search(&mut self, _limits: &Limits) -> SearchResult {
+        let best_move = MoveGen::new_legal(&self.board).next();
+        SearchResult { best_move, ..Default::default() }
+    }
+}
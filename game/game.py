@@ -1,5 +1,6 @@
 import pygame
 import chess
+import chess.pgn
 import subprocess
 import time
 from configs import SQ_SIZE,WHITE, BLACK, RED, screen, WIDTH, HEIGHT, board, running, selected_square
@@ -56,7 +57,7 @@ def show_game_over(text):
     time.sleep(6)
 
 # Inicializa a engine personalizada
-engine_path = "../engine/engine_sixth/target/release/engine_sixth" 
+engine_path = "../engine/target/release/engine_sixth" 
 engine = subprocess.Popen(
     [engine_path],
     stdin=subprocess.PIPE,
@@ -65,9 +66,13 @@ engine = subprocess.Popen(
 )
 
 # Função para obter movimento da IA
+# Manda a posição inicial e todos os lances, para a engine conhecer o
+# histórico da partida e enxergar repetições
 def get_ai_move(board):
-    fen = board.fen()
-    engine.stdin.write(f"position {fen}\n")  
+    root = board.root()
+    start = "startpos" if root == chess.Board() else root.fen()
+    moves = " ".join(move.uci() for move in board.move_stack)
+    engine.stdin.write(f"position {start} moves {moves}\n")
     engine.stdin.write("go\n")  
     engine.stdin.flush()
 
@@ -141,4 +146,17 @@ engine.stdin.write("quit\n")
 engine.stdin.flush()
 engine.terminate()
 
+# Acrescenta a partida a game/partidas.pgn, qualquer que seja o diretório
+# atual, para revisão: engine_sixth analyse partidas.pgn
+if board.move_stack:
+    pgn = chess.pgn.Game.from_board(board)
+    pgn.headers["Event"] = "Chazz"
+    pgn.headers["Date"] = time.strftime("%Y.%m.%d")
+    pgn.headers["White"] = "Jogador"
+    pgn.headers["Black"] = "Chazz"
+    pgn_path = os.path.join(os.path.dirname(os.path.abspath(__file__)), "partidas.pgn")
+    with open(pgn_path, "a") as file:
+        print(pgn, file=file, end="\n\n")
+    print(f"Partida salva em {pgn_path}")
+
 pygame.quit()